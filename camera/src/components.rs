@@ -0,0 +1,46 @@
+//! Components used for cameras
+//!
+//! All components are registered to the [`ComponentRegistry`] by [`register_components`], which [`CameraBundle`] does
+//! automatically.
+//!
+//! [`ComponentRegistry`]: ../../game_engine_core/registry/struct.ComponentRegistry.html
+//! [`register_components`]: ./fn.register_components.html
+//! [`CameraBundle`]: ../systems/struct.CameraBundle.html
+
+mod active_camera;
+mod camera;
+mod projection;
+mod viewport;
+
+pub use self::active_camera::ActiveCamera;
+pub use self::camera::Camera;
+pub use self::projection::{Projection, ProjectionError};
+pub use self::viewport::Viewport;
+use crate::core::registry::ComponentRegistry;
+
+/// Register all components of this crate to the given registry
+///
+/// The components are registered with their type names prefixed with `camera::`, e.g. `camera::Camera`.
+pub fn register_components(registry: &mut ComponentRegistry) {
+    registry
+        .register::<Camera>("camera::Camera")
+        .register::<ActiveCamera>("camera::ActiveCamera");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_components_registered() {
+        let mut registry = ComponentRegistry::new();
+        register_components(&mut registry);
+        register_components(&mut registry);
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.get_by_type::<Camera>().unwrap().name(),
+            "camera::Camera"
+        );
+    }
+}
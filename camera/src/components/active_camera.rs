@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Marker component for the cameras that are currently in use
+///
+/// Renderers draw the views of the entities with both this and [`Camera`] components. More than one camera can be
+/// active at once, e.g. for split screens with different [`Viewport`]s.
+///
+/// [`Camera`]: ./struct.Camera.html
+/// [`Viewport`]: ./struct.Viewport.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct ActiveCamera;
@@ -0,0 +1,319 @@
+use super::{Projection, ProjectionError, Viewport};
+use crate::math::{Matrix4, Point3};
+use crate::transform::components::WorldTransform;
+use crate::transform::spatial_index::Ray;
+use crate::window::wm::dpi::{LogicalPosition, PhysicalPosition};
+use crate::window::WindowSize;
+use serde::{Deserialize, Serialize};
+
+/// Component for the entities that view the world
+///
+/// The camera is placed by [`WorldTransform`] component of the same entity, and looks towards its local -Z axis with
+/// +Y axis up. [`CameraUpdate`] system sets the aspect ratio of the [`Projection`] to the one of the [`Viewport`] in
+/// the window, and computes the view and view-projection matrices from [`WorldTransform`]. The matrices are only valid
+/// after the system has run, and are kept as is while the projection is invalid.
+///
+/// # Serialization
+/// This struct is represented as `(projection: Projection, viewport: Viewport)` when being (de)serialized. The matrices
+/// are recomputed by the system.
+///
+/// [`WorldTransform`]: ../../game_engine_transform/components/struct.WorldTransform.html
+/// [`CameraUpdate`]: ../systems/fn.build_camera_update_system.html
+/// [`Projection`]: ./enum.Projection.html
+/// [`Viewport`]: ./struct.Viewport.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "camera_serde::SerializedCamera",
+    into = "camera_serde::SerializedCamera"
+)]
+pub struct Camera {
+    projection: Projection,
+    viewport: Viewport,
+    view: Matrix4<f32>,
+    view_projection: Matrix4<f32>,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new(Projection::default())
+    }
+}
+
+impl Camera {
+    /// Create a new instance with the given projection, covering the whole window
+    pub fn new(projection: Projection) -> Camera {
+        Camera::with_viewport(projection, Viewport::full())
+    }
+
+    /// Create a new instance with the given projection and viewport
+    pub fn with_viewport(projection: Projection, viewport: Viewport) -> Camera {
+        Camera {
+            projection,
+            viewport,
+            view: Matrix4::identity(),
+            view_projection: projection.matrix().unwrap_or_else(Matrix4::identity),
+        }
+    }
+
+    /// Create a new instance with a perspective projection, covering the whole window
+    ///
+    /// See [`Projection::perspective`] for the parameters and the errors.
+    ///
+    /// [`Projection::perspective`]: ./enum.Projection.html#method.perspective
+    pub fn perspective(fov_y: f32, near: f32, far: f32) -> Result<Camera, ProjectionError> {
+        Projection::perspective(fov_y, near, far).map(Camera::new)
+    }
+
+    /// Create a new instance with an orthographic projection, covering the whole window
+    ///
+    /// See [`Projection::orthographic`] for the parameters and the errors.
+    ///
+    /// [`Projection::orthographic`]: ./enum.Projection.html#method.orthographic
+    pub fn orthographic(height: f32, near: f32, far: f32) -> Result<Camera, ProjectionError> {
+        Projection::orthographic(height, near, far).map(Camera::new)
+    }
+
+    /// Get the projection of the camera
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    /// Get a mutable reference to the projection of the camera
+    ///
+    /// The changes are reflected to the matrices on the next run of the system, which also overwrites the aspect ratio.
+    pub fn projection_mut(&mut self) -> &mut Projection {
+        &mut self.projection
+    }
+
+    /// Get the rectangle of the window that the camera renders to
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Set the rectangle of the window that the camera renders to
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+    }
+
+    /// Get the matrix that transforms points from the world space to the view space of the camera
+    pub fn view(&self) -> &Matrix4<f32> {
+        &self.view
+    }
+
+    /// Get the matrix that transforms points from the view space to the normalized device coordinates
+    ///
+    /// Returns `None` if the projection is invalid.
+    pub fn projection_matrix(&self) -> Option<Matrix4<f32>> {
+        self.projection.matrix()
+    }
+
+    /// Get the matrix that transforms points from the world space to the normalized device coordinates
+    pub fn view_projection(&self) -> &Matrix4<f32> {
+        &self.view_projection
+    }
+
+    /// Get the ray in the world space that goes through the given cursor position, starting from the near plane
+    ///
+    /// The position is in logical pixels relative to the top-left corner of the window, as reported by the window
+    /// events. `transform` is [`WorldTransform`] component of the camera. Returns `None` if the position is outside of
+    /// the viewport, or the transform is not invertible, or the projection is invalid.
+    ///
+    /// [`WorldTransform`]: ../../game_engine_transform/components/struct.WorldTransform.html
+    pub fn screen_to_ray(
+        &self,
+        transform: &WorldTransform,
+        window_size: &WindowSize,
+        position: LogicalPosition,
+    ) -> Option<Ray> {
+        let physical_size = window_size.physical_size();
+        if physical_size.width <= 0.0 || physical_size.height <= 0.0 {
+            return None;
+        }
+        let position = position.to_physical(window_size.dpi_factor());
+        let x = (position.x / physical_size.width) as f32;
+        let y = (position.y / physical_size.height) as f32;
+        if !self.viewport.contains(x, y) {
+            return None;
+        }
+
+        let ndc_x = (x - self.viewport.x) / self.viewport.width * 2.0 - 1.0;
+        let ndc_y = 1.0 - (y - self.viewport.y) / self.viewport.height * 2.0;
+        let inverse_projection = self.projection.matrix()?.try_inverse()?;
+        let near = inverse_projection.transform_point(&Point3::new(ndc_x, ndc_y, -1.0));
+        let far = inverse_projection.transform_point(&Point3::new(ndc_x, ndc_y, 1.0));
+        let near = transform.transform_point(&near);
+        let far = transform.transform_point(&far);
+        Some(Ray::new(near, far - near))
+    }
+
+    /// Get the position of the given world-space point on the screen
+    ///
+    /// The position is in logical pixels relative to the top-left corner of the window, and can be outside of the
+    /// viewport or the window. `transform` is [`WorldTransform`] component of the camera. Returns `None` if the point
+    /// is not in front of a perspective camera, or the transform is not invertible, or the projection is invalid.
+    ///
+    /// [`WorldTransform`]: ../../game_engine_transform/components/struct.WorldTransform.html
+    pub fn world_to_screen(
+        &self,
+        transform: &WorldTransform,
+        window_size: &WindowSize,
+        point: &Point3<f32>,
+    ) -> Option<LogicalPosition> {
+        let view_point = transform.inverse_transform_point(point)?;
+        let clip = self.projection.matrix()? * view_point.to_homogeneous();
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.xyz() / clip.w;
+
+        let x = self.viewport.x + (ndc.x + 1.0) / 2.0 * self.viewport.width;
+        let y = self.viewport.y + (1.0 - ndc.y) / 2.0 * self.viewport.height;
+        let physical_size = window_size.physical_size();
+        let position = PhysicalPosition::new(
+            f64::from(x) * physical_size.width,
+            f64::from(y) * physical_size.height,
+        );
+        Some(position.to_logical(window_size.dpi_factor()))
+    }
+
+    /// Update the aspect ratio from the window and the matrices from the world transform of the camera
+    pub(crate) fn update(&mut self, window_aspect_ratio: f32, world_transform: &Matrix4<f32>) {
+        let aspect_ratio = self.viewport.aspect_ratio(window_aspect_ratio);
+        // Keep the last value while the window or the viewport has no area, which is rejected
+        let _ = self.projection.set_aspect_ratio(aspect_ratio);
+        self.view = world_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        if let Some(projection) = self.projection.matrix() {
+            self.view_projection = projection * self.view;
+        }
+    }
+}
+
+mod camera_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub struct SerializedCamera {
+        projection: Projection,
+        viewport: Viewport,
+    }
+
+    impl From<SerializedCamera> for Camera {
+        fn from(
+            SerializedCamera {
+                projection,
+                viewport,
+            }: SerializedCamera,
+        ) -> Self {
+            Camera::with_viewport(projection, viewport)
+        }
+    }
+
+    impl From<Camera> for SerializedCamera {
+        fn from(camera: Camera) -> Self {
+            SerializedCamera {
+                projection: camera.projection,
+                viewport: camera.viewport,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+    use crate::components::{Projection, ProjectionError, Viewport};
+    use crate::math::{Point3, Vector3};
+    use crate::transform::components::{Position, WorldTransform};
+    use crate::window::wm::dpi::LogicalPosition;
+    use crate::window::WindowSize;
+    use std::error::Error;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn screen_conversions() -> Result<(), ProjectionError> {
+        // 400x300 logical pixels
+        let window_size = WindowSize::new((800.0, 600.0), 2.0);
+        let transform = WorldTransform::from(Position::from_z(10.0).to_homogeneous());
+        let mut camera = Camera::with_viewport(
+            Projection::perspective(FRAC_PI_2, 1.0, 100.0)?,
+            Viewport::new(0.5, 0.0, 0.5, 1.0),
+        );
+        camera.update(window_size.aspect_ratio(), transform.matrix());
+
+        let center = LogicalPosition::new(300.0, 150.0);
+        let ray = camera
+            .screen_to_ray(&transform, &window_size, center)
+            .unwrap();
+        assert_eq!(ray.origin, Point3::new(0.0, 0.0, 9.0));
+        assert_eq!(ray.direction, -Vector3::z());
+        assert_eq!(
+            camera.world_to_screen(&transform, &window_size, &Point3::origin()),
+            Some(center)
+        );
+        // The right half of the window is 200x300 logical pixels, with the vertical field of view of 90 degrees
+        let corner = camera
+            .world_to_screen(&transform, &window_size, &Point3::new(-4.0, -6.0, 4.0))
+            .unwrap();
+        assert!((corner.x - 200.0).abs() < 1e-3 && (corner.y - 300.0).abs() < 1e-3);
+        let ray = camera
+            .screen_to_ray(&transform, &window_size, corner)
+            .unwrap();
+        let to_corner = Point3::new(-4.0, -6.0, 4.0) - ray.origin;
+        assert!(ray.direction.cross(&to_corner).norm() < 1e-3);
+
+        assert!(camera
+            .screen_to_ray(&transform, &window_size, LogicalPosition::new(100.0, 150.0))
+            .is_none());
+        assert!(camera
+            .world_to_screen(&transform, &window_size, &Point3::new(0.0, 0.0, 20.0))
+            .is_none());
+
+        // The last matrices are kept while the projection is invalid
+        let view_projection = *camera.view_projection();
+        if let Projection::Perspective { fov_y, .. } = camera.projection_mut() {
+            *fov_y = 0.0;
+        }
+        camera.update(window_size.aspect_ratio(), transform.matrix());
+        assert_eq!(*camera.view_projection(), view_projection);
+        assert!(camera
+            .screen_to_ray(&transform, &window_size, center)
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn camera_serde() -> Result<(), Box<dyn Error>> {
+        let camera: Camera = ron::de::from_str(
+            "(
+                projection: Orthographic(height: 10, near: 0, far: 100),
+                viewport: (x: 0, y: 0, width: 0.5, height: 1),
+            )",
+        )?;
+        assert_eq!(
+            camera,
+            Camera::with_viewport(
+                Projection::orthographic(10.0, 0.0, 100.0)?,
+                Viewport::new(0.0, 0.0, 0.5, 1.0)
+            )
+        );
+        assert_eq!(Some(*camera.view_projection()), camera.projection_matrix());
+        assert_eq!(
+            serde_json::to_string(&Camera::perspective(1.0, 0.5, 10.0)?)?,
+            concat!(
+                r#"{"projection":{"Perspective":{"fov_y":1.0,"near":0.5,"far":10.0}},"#,
+                r#""viewport":{"x":0.0,"y":0.0,"width":1.0,"height":1.0}}"#
+            )
+        );
+        assert!(ron::de::from_str::<Camera>(
+            "(
+                projection: Perspective(fov_y: 1, near: 1, far: 1),
+                viewport: (x: 0, y: 0, width: 1, height: 1),
+            )",
+        )
+        .is_err());
+        Ok(())
+    }
+}
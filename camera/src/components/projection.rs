@@ -0,0 +1,335 @@
+use crate::math::{Matrix4, Orthographic3, Perspective3};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use std::fmt;
+
+/// Projection from the view space of a camera to the normalized device coordinates
+///
+/// The projections follow the OpenGL conventions: the camera looks towards -Z axis, and the visible region between the
+/// near and far planes is mapped to the cube from -1 to 1 along each axis.
+///
+/// The aspect ratio is the width divided by the height of the visible region, which is kept in sync with the viewport
+/// of the camera by [`CameraUpdate`] system.
+///
+/// The constructors reject the values that do not make a projection, and so does the deserialization. The fields can
+/// still be set to such values directly, in which case [`matrix`] returns `None`.
+///
+/// # Serialization
+/// The aspect ratio is not serialized, since it is overwritten by the system anyway. It is 1 after deserialization.
+///
+/// [`CameraUpdate`]: ../systems/fn.build_camera_update_system.html
+/// [`matrix`]: #method.matrix
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    try_from = "projection_serde::SerializedProjection",
+    into = "projection_serde::SerializedProjection"
+)]
+pub enum Projection {
+    /// Perspective projection, where the objects farther away look smaller
+    Perspective {
+        /// The vertical field of view in radians, which must be between 0 and π
+        fov_y: f32,
+        /// The width divided by the height of the visible region, which must be positive
+        aspect_ratio: f32,
+        /// The distance to the near clipping plane, which must be positive
+        near: f32,
+        /// The distance to the far clipping plane, which must be greater than `near`
+        far: f32,
+    },
+
+    /// Orthographic projection, where the objects keep their sizes regardless of the distance
+    Orthographic {
+        /// The height of the visible region in world units, which must be positive
+        height: f32,
+        /// The width divided by the height of the visible region, which must be positive
+        aspect_ratio: f32,
+        /// The distance to the near clipping plane
+        near: f32,
+        /// The distance to the far clipping plane, which must be greater than `near`
+        far: f32,
+    },
+}
+
+/// Error for the values that do not make a [`Projection`]
+///
+/// [`Projection`]: ./enum.Projection.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionError {
+    /// The field of view is not between 0 and π
+    FieldOfView(f32),
+    /// The height of the visible region is not positive
+    Height(f32),
+    /// The aspect ratio is not positive
+    AspectRatio(f32),
+    /// The near plane is not in front of the far plane, or not in front of the camera for a perspective projection
+    ClippingPlanes {
+        /// The distance to the near clipping plane
+        near: f32,
+        /// The distance to the far clipping plane
+        far: f32,
+    },
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionError::FieldOfView(fov_y) => {
+                write!(f, "field of view {} is not between 0 and π", fov_y)
+            }
+            ProjectionError::Height(height) => write!(f, "height {} is not positive", height),
+            ProjectionError::AspectRatio(aspect_ratio) => {
+                write!(f, "aspect ratio {} is not positive", aspect_ratio)
+            }
+            ProjectionError::ClippingPlanes { near, far } => {
+                write!(f, "near plane {} and far plane {} are invalid", near, far)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Projection::Perspective {
+            fov_y: std::f32::consts::FRAC_PI_3,
+            aspect_ratio: 1.0,
+            near: 0.1,
+            far: 1000.0,
+        }
+    }
+}
+
+impl Projection {
+    /// Create a new perspective projection with the given vertical field of view in radians
+    ///
+    /// The aspect ratio is 1 until it is updated. Returns an error if the field of view is not between 0 and π, or
+    /// `near` is not positive, or `far` is not greater than `near`.
+    pub fn perspective(fov_y: f32, near: f32, far: f32) -> Result<Projection, ProjectionError> {
+        let projection = Projection::Perspective {
+            fov_y,
+            aspect_ratio: 1.0,
+            near,
+            far,
+        };
+        projection.validate().map(|_| projection)
+    }
+
+    /// Create a new orthographic projection with the given height of the visible region
+    ///
+    /// The aspect ratio is 1 until it is updated. Returns an error if the height is not positive, or `far` is not
+    /// greater than `near`.
+    pub fn orthographic(height: f32, near: f32, far: f32) -> Result<Projection, ProjectionError> {
+        let projection = Projection::Orthographic {
+            height,
+            aspect_ratio: 1.0,
+            near,
+            far,
+        };
+        projection.validate().map(|_| projection)
+    }
+
+    /// Check whether the values make a projection
+    pub fn validate(&self) -> Result<(), ProjectionError> {
+        let (aspect_ratio, near, far) = match *self {
+            Projection::Perspective {
+                fov_y,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                if !(fov_y > 0.0 && fov_y < PI) {
+                    return Err(ProjectionError::FieldOfView(fov_y));
+                }
+                if !(near > 0.0 && near.is_finite()) {
+                    return Err(ProjectionError::ClippingPlanes { near, far });
+                }
+                (aspect_ratio, near, far)
+            }
+            Projection::Orthographic {
+                height,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                if !(height > 0.0 && height.is_finite()) {
+                    return Err(ProjectionError::Height(height));
+                }
+                (aspect_ratio, near, far)
+            }
+        };
+        if !(aspect_ratio > 0.0 && aspect_ratio.is_finite()) {
+            return Err(ProjectionError::AspectRatio(aspect_ratio));
+        }
+        if !(near.is_finite() && far.is_finite() && far > near) {
+            return Err(ProjectionError::ClippingPlanes { near, far });
+        }
+        Ok(())
+    }
+
+    /// Check whether this is a perspective projection
+    pub fn is_perspective(&self) -> bool {
+        match self {
+            Projection::Perspective { .. } => true,
+            Projection::Orthographic { .. } => false,
+        }
+    }
+
+    /// Get the width divided by the height of the visible region
+    pub fn aspect_ratio(&self) -> f32 {
+        match *self {
+            Projection::Perspective { aspect_ratio, .. } => aspect_ratio,
+            Projection::Orthographic { aspect_ratio, .. } => aspect_ratio,
+        }
+    }
+
+    /// Set the width divided by the height of the visible region
+    ///
+    /// Returns an error and keeps the current value if the given value is not positive.
+    pub fn set_aspect_ratio(&mut self, value: f32) -> Result<(), ProjectionError> {
+        if !(value > 0.0 && value.is_finite()) {
+            return Err(ProjectionError::AspectRatio(value));
+        }
+        match self {
+            Projection::Perspective { aspect_ratio, .. } => *aspect_ratio = value,
+            Projection::Orthographic { aspect_ratio, .. } => *aspect_ratio = value,
+        }
+        Ok(())
+    }
+
+    /// Get the distance to the near clipping plane
+    pub fn near(&self) -> f32 {
+        match *self {
+            Projection::Perspective { near, .. } => near,
+            Projection::Orthographic { near, .. } => near,
+        }
+    }
+
+    /// Get the distance to the far clipping plane
+    pub fn far(&self) -> f32 {
+        match *self {
+            Projection::Perspective { far, .. } => far,
+            Projection::Orthographic { far, .. } => far,
+        }
+    }
+
+    /// Get the homogeneous matrix that represents this projection
+    ///
+    /// Returns `None` if the values do not make a projection, which can only happen when the fields are set directly.
+    pub fn matrix(&self) -> Option<Matrix4<f32>> {
+        self.validate().ok()?;
+        let matrix = match *self {
+            Projection::Perspective {
+                fov_y,
+                aspect_ratio,
+                near,
+                far,
+            } => Perspective3::new(aspect_ratio, fov_y, near, far).into_inner(),
+            Projection::Orthographic {
+                height,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                let half_height = height / 2.0;
+                let half_width = half_height * aspect_ratio;
+                Orthographic3::new(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    near,
+                    far,
+                )
+                .into_inner()
+            }
+        };
+        Some(matrix)
+    }
+}
+
+mod projection_serde {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub enum SerializedProjection {
+        Perspective { fov_y: f32, near: f32, far: f32 },
+        Orthographic { height: f32, near: f32, far: f32 },
+    }
+
+    impl TryFrom<SerializedProjection> for Projection {
+        type Error = ProjectionError;
+
+        fn try_from(projection: SerializedProjection) -> Result<Self, Self::Error> {
+            match projection {
+                SerializedProjection::Perspective { fov_y, near, far } => {
+                    Projection::perspective(fov_y, near, far)
+                }
+                SerializedProjection::Orthographic { height, near, far } => {
+                    Projection::orthographic(height, near, far)
+                }
+            }
+        }
+    }
+
+    impl From<Projection> for SerializedProjection {
+        fn from(projection: Projection) -> Self {
+            match projection {
+                Projection::Perspective {
+                    fov_y, near, far, ..
+                } => SerializedProjection::Perspective { fov_y, near, far },
+                Projection::Orthographic {
+                    height, near, far, ..
+                } => SerializedProjection::Orthographic { height, near, far },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Projection, ProjectionError};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn validation() {
+        assert!(Projection::perspective(FRAC_PI_2, 0.1, 100.0).is_ok());
+        assert_eq!(
+            Projection::perspective(PI, 0.1, 100.0),
+            Err(ProjectionError::FieldOfView(PI))
+        );
+        assert_eq!(
+            Projection::perspective(FRAC_PI_2, 0.0, 100.0),
+            Err(ProjectionError::ClippingPlanes {
+                near: 0.0,
+                far: 100.0
+            })
+        );
+        assert!(Projection::orthographic(10.0, -1.0, 100.0).is_ok());
+        assert_eq!(
+            Projection::orthographic(0.0, 0.0, 100.0),
+            Err(ProjectionError::Height(0.0))
+        );
+        assert_eq!(
+            Projection::orthographic(10.0, 1.0, 1.0),
+            Err(ProjectionError::ClippingPlanes {
+                near: 1.0,
+                far: 1.0
+            })
+        );
+
+        let mut projection = Projection::default();
+        assert_eq!(
+            projection.set_aspect_ratio(0.0),
+            Err(ProjectionError::AspectRatio(0.0))
+        );
+        assert_eq!(projection.aspect_ratio(), 1.0);
+        assert!(projection.matrix().is_some());
+        if let Projection::Perspective { far, .. } = &mut projection {
+            *far = 0.0;
+        }
+        assert_eq!(projection.matrix(), None);
+    }
+}
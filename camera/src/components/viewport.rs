@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Rectangle of the window that a camera renders to
+///
+/// The values are relative to the size of the window, from 0 to 1, with the origin at the top-left corner of the
+/// window and Y axis going down, like the cursor positions reported by the window.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Viewport {
+    /// The left edge of the rectangle
+    pub x: f32,
+    /// The top edge of the rectangle
+    pub y: f32,
+    /// The width of the rectangle
+    pub width: f32,
+    /// The height of the rectangle
+    pub height: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport::full()
+    }
+}
+
+impl Viewport {
+    /// Create a new instance with the given top-left corner and size
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Viewport {
+        Viewport {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Create a new instance that covers the whole window
+    pub fn full() -> Viewport {
+        Viewport::new(0.0, 0.0, 1.0, 1.0)
+    }
+
+    /// Get the aspect ratio of the rectangle in a window with the given aspect ratio
+    pub fn aspect_ratio(&self, window_aspect_ratio: f32) -> f32 {
+        window_aspect_ratio * self.width / self.height
+    }
+
+    /// Check whether the given point, relative to the size of the window, is inside the rectangle
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.x <= x && x <= self.x + self.width && self.y <= y && y <= self.y + self.height
+    }
+}
@@ -0,0 +1,38 @@
+//! Cameras that view the world through [`WorldTransform`] and fit their projections to [`WindowSize`]
+//!
+//! An entity becomes a camera by having [`Camera`] component along with the usual transform components. Cameras look
+//! towards their local -Z axis with +Y axis up, so [`WorldTransform::forward`] is the viewing direction. Only the
+//! cameras with [`ActiveCamera`] component are supposed to be rendered.
+//!
+//! [`CameraBundle`] registers the components and adds [`CameraUpdate`] system, which keeps the aspect ratios of the
+//! projections in sync with [`WindowSize`] and computes the view and view-projection matrices.
+//!
+//! The cursor positions are turned into world-space rays by [`Camera::screen_to_ray`], and the world-space points are
+//! projected to the screen by [`Camera::world_to_screen`]. See [`picking`] module for picking entities by clicking on
+//! them.
+//!
+//! [`WorldTransform`]: ../game_engine_transform/components/struct.WorldTransform.html
+//! [`WindowSize`]: ../game_engine_window/struct.WindowSize.html
+//! [`Camera`]: ./components/struct.Camera.html
+//! [`WorldTransform::forward`]: ../game_engine_transform/components/struct.WorldTransform.html#method.forward
+//! [`ActiveCamera`]: ./components/struct.ActiveCamera.html
+//! [`CameraBundle`]: ./systems/struct.CameraBundle.html
+//! [`CameraUpdate`]: ./systems/fn.build_camera_update_system.html
+//! [`Camera::screen_to_ray`]: ./components/struct.Camera.html#method.screen_to_ray
+//! [`Camera::world_to_screen`]: ./components/struct.Camera.html#method.world_to_screen
+//! [`picking`]: ./picking/index.html
+
+#![warn(clippy::all, missing_docs, unused_qualifications)]
+#![deny(
+    clippy::correctness,
+    missing_copy_implementations,
+    missing_debug_implementations
+)]
+
+use game_engine_core::{self as core, ecs, math};
+use game_engine_transform as transform;
+use game_engine_window as window;
+
+pub mod components;
+pub mod picking;
+pub mod systems;
@@ -0,0 +1,55 @@
+//! Picking entities in the world with the mouse cursor
+//!
+//! [`Picking`] system turns each mouse click in the viewport of an active camera into a ray through
+//! [`Camera::screen_to_ray`], and writes a [`Picked`] event for the closest entity whose world-space bounds are hit by
+//! the ray. The bounds are [`WorldAabb`] and [`WorldBoundingSphere`] components, written by `bounds_update` system.
+//!
+//! [`Picking`]: ../systems/fn.build_picking_system.html
+//! [`Camera::screen_to_ray`]: ../components/struct.Camera.html#method.screen_to_ray
+//! [`Picked`]: ./struct.Picked.html
+//! [`WorldAabb`]: ../../game_engine_transform/components/struct.WorldAabb.html
+//! [`WorldBoundingSphere`]: ../../game_engine_transform/components/struct.WorldBoundingSphere.html
+
+use crate::ecs::entity::Entity;
+use crate::math::Point3;
+use crate::transform::components::{WorldAabb, WorldBoundingSphere};
+use crate::transform::spatial_index::Ray;
+use crate::window::wm::MouseButton;
+
+/// Event written by [`Picking`] system when an entity is clicked
+///
+/// [`Picking`]: ../systems/fn.build_picking_system.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Picked {
+    /// The entity that was clicked
+    pub entity: Entity,
+    /// The camera through which the entity was clicked
+    pub camera: Entity,
+    /// The mouse button that was pressed
+    pub button: MouseButton,
+    /// The point where the ray enters the bounds of the entity
+    pub point: Point3<f32>,
+    /// The distance from the near plane of the camera to `point`
+    pub distance: f32,
+}
+
+/// Get the distance from the origin of the ray to where it enters the given bounds
+///
+/// The entities with both of the bounds are only hit when the ray goes through both of them, at the larger of the two
+/// distances. Returns `None` if the ray misses or there are no bounds.
+pub fn intersect_bounds(
+    ray: &Ray,
+    aabb: Option<&WorldAabb>,
+    sphere: Option<&WorldBoundingSphere>,
+) -> Option<f32> {
+    match (aabb, sphere) {
+        (Some(aabb), Some(sphere)) => {
+            let aabb_distance = ray.intersect_aabb(aabb)?;
+            let sphere_distance = ray.intersect_sphere(sphere)?;
+            Some(aabb_distance.max(sphere_distance))
+        }
+        (Some(aabb), None) => ray.intersect_aabb(aabb),
+        (None, Some(sphere)) => ray.intersect_sphere(sphere),
+        (None, None) => None,
+    }
+}
@@ -0,0 +1,70 @@
+//! Functions that build systems needed for cameras to work
+//!
+//! # System Execution Order
+//!
+//! 1. Window size control system of [`WindowBundle`], which inserts [`WindowSize`] resource
+//! 1. Transform systems of [`TransformBundle`]
+//! 1. **Flush**
+//! 1. `camera_update`
+//! 1. `picking`, after `bounds_update` system of the transform crate as well
+//! 1. Do thread-local stuff like rendering that uses [`Camera`]
+//!
+//! [`WindowBundle`]: ../../game_engine_window/struct.WindowBundle.html
+//! [`WindowSize`]: ../../game_engine_window/struct.WindowSize.html
+//! [`TransformBundle`]: ../../game_engine_transform/systems/struct.TransformBundle.html
+//! [`Camera`]: ../components/struct.Camera.html
+
+mod camera_update;
+mod picking;
+
+pub use self::camera_update::{build_camera_update_system, CameraUpdateSystem};
+pub use self::picking::{build_picking_system, PickingSystem};
+use crate::components::register_components;
+use crate::core::registry::ComponentRegistry;
+use crate::core::systems::{ScheduleBuilder, SystemBundle, SystemOrder};
+use crate::ecs::world::World;
+
+/// [`SystemBundle`] that adds camera related systems
+///
+/// This also registers all camera components to the [`ComponentRegistry`] resource, inserting the resource if it does
+/// not exist.
+///
+/// [`SystemBundle`]: ../../game_engine_core/systems/trait.SystemBundle.html
+/// [`ComponentRegistry`]: ../../game_engine_core/registry/struct.ComponentRegistry.html
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CameraBundle {
+    /// The [`SystemOrder`] for [`CameraUpdate`] system
+    ///
+    /// [`SystemOrder`]: ../../game_engine_core/systems/struct.SystemOrder.html
+    /// [`CameraUpdate`]: ./fn.build_camera_update_system.html
+    pub update_order: SystemOrder,
+}
+
+impl CameraBundle {
+    /// Create a new instance with the given system execution order
+    pub fn new(update_order: SystemOrder) -> Self {
+        Self { update_order }
+    }
+
+    /// Chain method to construct the bundle; sets the order of [`CameraUpdate`] system
+    ///
+    /// [`CameraUpdate`]: ./fn.build_camera_update_system.html
+    pub fn with_update_order(mut self, update_order: SystemOrder) -> Self {
+        self.update_order = update_order;
+        self
+    }
+}
+
+impl SystemBundle for CameraBundle {
+    fn build_systems(self, world: &mut World, builder: &mut ScheduleBuilder) {
+        register_components(
+            &mut world
+                .resources
+                .get_mut_or_insert_with(ComponentRegistry::new)
+                .expect("the component registry is just inserted"),
+        );
+
+        builder.add_system_create_fn(self.update_order, build_camera_update_system);
+    }
+}
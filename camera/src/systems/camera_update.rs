@@ -0,0 +1,104 @@
+use crate::components::Camera;
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::query::{IntoQuery, TryRead, Write};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::math::Matrix4;
+use crate::transform::components::WorldTransform;
+use crate::window::WindowSize;
+
+/// System descriptor(builder) for keeping [`Camera`] components in sync with the window and their transforms
+///
+/// This is a wrapper for [`build_camera_update_system`].
+///
+/// [`Camera`]: ../components/struct.Camera.html
+/// [`build_camera_update_system`]: ./fn.build_camera_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_camera_update_system))]
+pub struct CameraUpdateSystem;
+
+/// Build a system that sets the aspect ratios of [`Camera`] components to the ones of their viewports in the window,
+/// and computes their view and view-projection matrices from [`WorldTransform`] components
+///
+/// The cameras without [`WorldTransform`] component are treated as if they were at the origin without rotation.
+///
+/// [`WindowSize`] resource must exist by the time this system runs, which is inserted when the window size control
+/// system of [`WindowBundle`] is built.
+///
+/// [`Camera`]: ../components/struct.Camera.html
+/// [`WorldTransform`]: ../../game_engine_transform/components/struct.WorldTransform.html
+/// [`WindowSize`]: ../../game_engine_window/struct.WindowSize.html
+/// [`WindowBundle`]: ../../game_engine_window/struct.WindowBundle.html
+pub fn build_camera_update_system(_: &mut World) -> Box<dyn Schedulable> {
+    SystemBuilder::new("CameraUpdate")
+        .read_resource::<WindowSize>()
+        .with_query(<(Write<Camera>, TryRead<WorldTransform>)>::query())
+        .build(|_, world, window_size, query| {
+            let aspect_ratio = window_size.aspect_ratio();
+            for (mut camera, transform) in query.iter(world) {
+                match transform {
+                    Some(transform) => camera.update(aspect_ratio, transform.matrix()),
+                    None => camera.update(aspect_ratio, &Matrix4::identity()),
+                }
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_camera_update_system;
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3, Vector4};
+    use crate::transform::components::Position;
+    use crate::transform::systems::TransformBundle;
+    use crate::window::WindowSize;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn update_cameras() {
+        let mut world = World::new();
+        world.resources.insert(WindowSize::new((800.0, 400.0), 1.0));
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_camera_update_system)
+            .build(&mut world);
+
+        let perspective = world.insert(
+            (),
+            Some((
+                Camera::perspective(FRAC_PI_2, 1.0, 100.0).unwrap(),
+                Position::from_z(10.0),
+                ActiveCamera,
+            )),
+        )[0];
+        let orthographic = world.insert(
+            (),
+            Some((Camera::with_viewport(
+                Projection::orthographic(10.0, 0.0, 100.0).unwrap(),
+                Viewport::new(0.5, 0.0, 0.5, 1.0),
+            ),)),
+        )[0];
+        // The transforms are added by the command buffers in the first run
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let camera = *world.get_component::<Camera>(perspective).unwrap();
+        assert_eq!(camera.projection().aspect_ratio(), 2.0);
+        assert_eq!(
+            camera.view().transform_point(&Point3::origin()),
+            Point3::new(0.0, 0.0, -10.0)
+        );
+        // A point at the top-right corner of the near plane is on the corner of the normalized device coordinates
+        let clip = camera.view_projection() * Vector4::new(2.0, 1.0, 9.0, 1.0);
+        let ndc = clip.xyz() / clip.w;
+        assert!((ndc - Vector3::new(1.0, 1.0, -1.0)).norm() < 1e-5);
+
+        let camera = *world.get_component::<Camera>(orthographic).unwrap();
+        assert_eq!(camera.projection().aspect_ratio(), 1.0);
+        let ndc = camera.view_projection() * Vector4::new(-5.0, 5.0, -100.0, 1.0);
+        assert!((ndc - Vector4::new(-1.0, 1.0, 1.0, 1.0)).norm() < 1e-5);
+    }
+}
@@ -0,0 +1,419 @@
+use crate::components::{ActiveCamera, Camera};
+use crate::core::event_channel::{EventChannel, ReaderId};
+use crate::core::events::Events;
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::borrow::Ref;
+use crate::ecs::entity::Entity;
+use crate::ecs::filter::filter_fns::component;
+use crate::ecs::query::{IntoQuery, Read, TryRead};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::picking::{intersect_bounds, Picked};
+use crate::transform::components::{WorldAabb, WorldBoundingSphere, WorldTransform};
+use crate::transform::spatial_index::{Ray, SpatialIndex};
+use crate::window::wm::dpi::LogicalPosition;
+use crate::window::wm::{ElementState, Event, MouseButton, WindowEvent};
+use crate::window::WindowSize;
+use std::cmp::Ordering;
+
+/// System descriptor(builder) for writing [`Picked`] events for mouse clicks
+///
+/// This is a wrapper for [`build_picking_system`].
+///
+/// [`Picked`]: ../picking/struct.Picked.html
+/// [`build_picking_system`]: ./fn.build_picking_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_picking_system))]
+pub struct PickingSystem;
+
+/// Build a system that reads the mouse clicks from [`EventChannel<Event>`] resource, and writes [`Picked`] events for
+/// the closest entities under the cursor
+///
+/// Each click is cast as a ray from the active camera whose viewport contains the cursor, and the entity whose
+/// [`WorldAabb`] or [`WorldBoundingSphere`] is the closest along the ray is picked. Nothing is written for the clicks
+/// that hit nothing. The camera entity itself is never picked. The cameras without [`WorldTransform`] component are
+/// treated as if they were at the origin without rotation, as in `camera_update` system.
+///
+/// If [`SpatialIndex`] resource exists when this system is built, the entities with [`WorldAabb`] are found through
+/// [`SpatialIndex::ray_cast`] instead of testing all of them against each ray. The choice is made once, since a system
+/// cannot read a resource only while it exists. The entities with only [`WorldBoundingSphere`] are indexed as points,
+/// so they are always tested one by one.
+///
+/// This system is not added by [`CameraBundle`]. Run it after `camera_update` and `bounds_update` systems, and after
+/// `spatial_index_update` if the index is used. [`WindowSize`] resource must exist by the time this system runs.
+/// [`EventChannel<Event>`] resource is inserted if it does not exist.
+///
+/// [`EventChannel<Event>`]: ../../shrev/struct.EventChannel.html
+/// [`Picked`]: ../picking/struct.Picked.html
+/// [`WorldTransform`]: ../../game_engine_transform/components/struct.WorldTransform.html
+/// [`WorldAabb`]: ../../game_engine_transform/components/struct.WorldAabb.html
+/// [`WorldBoundingSphere`]: ../../game_engine_transform/components/struct.WorldBoundingSphere.html
+/// [`SpatialIndex`]: ../../game_engine_transform/spatial_index/struct.SpatialIndex.html
+/// [`SpatialIndex::ray_cast`]: ../../game_engine_transform/spatial_index/struct.SpatialIndex.html#method.ray_cast
+/// [`CameraBundle`]: ./struct.CameraBundle.html
+/// [`WindowSize`]: ../../game_engine_window/struct.WindowSize.html
+pub fn build_picking_system(world: &mut World) -> Box<dyn Schedulable> {
+    let mut clicks = ClickReader::register(world);
+    Events::<Picked>::ensure(world);
+
+    if world.resources.contains::<SpatialIndex>() {
+        SystemBuilder::new("Picking")
+            .read_resource::<EventChannel<Event>>()
+            .read_resource::<WindowSize>()
+            .read_resource::<SpatialIndex>()
+            .write_resource::<Events<Picked>>()
+            .with_query(
+                <(Read<Camera>, TryRead<WorldTransform>)>::query()
+                    .filter(component::<ActiveCamera>()),
+            )
+            // entities that are indexed as points
+            .with_query(<Read<WorldBoundingSphere>>::query().filter(!component::<WorldAabb>()))
+            .read_component::<WorldAabb>()
+            .read_component::<WorldBoundingSphere>()
+            .build(move |_, world, resources, queries| {
+                let (ref events, ref window_size, ref index, ref mut picked) = *resources;
+                let (cameras, spheres) = queries;
+
+                for (position, button) in clicks.read(events) {
+                    let (camera, ray) =
+                        match cast_ray(cameras.iter_entities(world), window_size, position) {
+                            Some(ray) => ray,
+                            None => continue,
+                        };
+
+                    let mut closest = spheres
+                        .iter_entities(world)
+                        .filter(|(entity, _)| *entity != camera)
+                        .filter_map(|(entity, sphere)| {
+                            ray.intersect_sphere(&sphere)
+                                .map(|distance| (entity, distance))
+                        })
+                        .min_by(compare_hits);
+                    // The hits are sorted by the distances to the boxes, and the bounds are never closer than those
+                    for hit in index.ray_cast(&ray, f32::INFINITY) {
+                        if matches!(closest, Some((_, distance)) if distance <= hit.distance) {
+                            break;
+                        }
+                        if hit.entity == camera {
+                            continue;
+                        }
+                        let aabb = match world.get_component::<WorldAabb>(hit.entity) {
+                            Some(aabb) => aabb,
+                            None => continue,
+                        };
+                        let sphere = world.get_component::<WorldBoundingSphere>(hit.entity);
+                        if let Some(distance) =
+                            intersect_bounds(&ray, Some(&aabb), sphere.as_deref())
+                        {
+                            if !matches!(closest, Some((_, closest)) if closest <= distance) {
+                                closest = Some((hit.entity, distance));
+                            }
+                        }
+                    }
+                    write_picked(picked, closest, camera, button, &ray);
+                }
+            })
+    } else {
+        SystemBuilder::new("Picking")
+            .read_resource::<EventChannel<Event>>()
+            .read_resource::<WindowSize>()
+            .write_resource::<Events<Picked>>()
+            .with_query(
+                <(Read<Camera>, TryRead<WorldTransform>)>::query()
+                    .filter(component::<ActiveCamera>()),
+            )
+            .with_query(
+                <(TryRead<WorldAabb>, TryRead<WorldBoundingSphere>)>::query()
+                    .filter(component::<WorldAabb>() | component::<WorldBoundingSphere>()),
+            )
+            .build(move |_, world, resources, queries| {
+                let (ref events, ref window_size, ref mut picked) = *resources;
+                let (cameras, bounds) = queries;
+
+                for (position, button) in clicks.read(events) {
+                    let (camera, ray) =
+                        match cast_ray(cameras.iter_entities(world), window_size, position) {
+                            Some(ray) => ray,
+                            None => continue,
+                        };
+
+                    let closest = bounds
+                        .iter_entities(world)
+                        .filter(|(entity, _)| *entity != camera)
+                        .filter_map(|(entity, (aabb, sphere))| {
+                            intersect_bounds(&ray, aabb.as_deref(), sphere.as_deref())
+                                .map(|distance| (entity, distance))
+                        })
+                        .min_by(compare_hits);
+                    write_picked(picked, closest, camera, button, &ray);
+                }
+            })
+    }
+}
+
+/// Reader of the mouse clicks from the window events
+struct ClickReader {
+    reader: ReaderId<Event>,
+    // The last position of the cursor in the window, since the clicks do not carry one
+    cursor: Option<LogicalPosition>,
+}
+
+impl ClickReader {
+    fn register(world: &mut World) -> ClickReader {
+        let reader = world
+            .resources
+            .get_mut_or_insert_with(|| EventChannel::<Event>::with_capacity(128))
+            .unwrap()
+            .register_reader();
+        ClickReader {
+            reader,
+            cursor: None,
+        }
+    }
+
+    /// Get the positions and the buttons of the clicks since the last read
+    fn read(&mut self, events: &EventChannel<Event>) -> Vec<(LogicalPosition, MouseButton)> {
+        let mut clicks = Vec::new();
+        for event in events.read(&mut self.reader) {
+            if let Event::WindowEvent { event, .. } = event {
+                match *event {
+                    WindowEvent::CursorMoved { position, .. } => self.cursor = Some(position),
+                    WindowEvent::CursorLeft { .. } => self.cursor = None,
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button,
+                        ..
+                    } => clicks.extend(self.cursor.map(|position| (position, button))),
+                    _ => {}
+                }
+            }
+        }
+        clicks
+    }
+}
+
+/// Get the first of the given cameras whose viewport contains the cursor, and the ray through the cursor
+fn cast_ray<'a>(
+    cameras: impl Iterator<Item = (Entity, (Ref<'a, Camera>, Option<Ref<'a, WorldTransform>>))>,
+    window_size: &WindowSize,
+    position: LogicalPosition,
+) -> Option<(Entity, Ray)> {
+    let identity = WorldTransform::identity();
+    for (entity, (camera, transform)) in cameras {
+        let transform = transform.as_deref().unwrap_or(&identity);
+        if let Some(ray) = camera.screen_to_ray(transform, window_size, position) {
+            return Some((entity, ray));
+        }
+    }
+    None
+}
+
+fn compare_hits(a: &(Entity, f32), b: &(Entity, f32)) -> Ordering {
+    a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal)
+}
+
+fn write_picked(
+    picked: &mut Events<Picked>,
+    closest: Option<(Entity, f32)>,
+    camera: Entity,
+    button: MouseButton,
+    ray: &Ray,
+) {
+    if let Some((entity, distance)) = closest {
+        picked.single_write(Picked {
+            entity,
+            camera,
+            button,
+            point: ray.point_at(distance),
+            distance,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_picking_system;
+    use crate::components::*;
+    use crate::core::event_channel::EventChannel;
+    use crate::core::events::{EventReader, Events};
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3};
+    use crate::picking::Picked;
+    use crate::systems::build_camera_update_system;
+    use crate::transform::components::{Aabb, BoundingSphere, Position, WorldTransform};
+    use crate::transform::spatial_index::SpatialIndex;
+    use crate::transform::systems::{
+        build_bounds_update_system, build_spatial_index_update_system, TransformBundle,
+    };
+    use crate::window::wm::dpi::LogicalPosition;
+    use crate::window::wm::{
+        DeviceId, ElementState, Event, ModifiersState, MouseButton, WindowEvent, WindowId,
+    };
+    use crate::window::WindowSize;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn window_event(event: WindowEvent) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event,
+        }
+    }
+
+    fn click(x: f64, y: f64, button: MouseButton) -> Vec<Event> {
+        let device_id = unsafe { DeviceId::dummy() };
+        vec![
+            window_event(WindowEvent::CursorMoved {
+                device_id,
+                position: LogicalPosition::new(x, y),
+                modifiers: ModifiersState::default(),
+            }),
+            window_event(WindowEvent::MouseInput {
+                device_id,
+                state: ElementState::Pressed,
+                button,
+                modifiers: ModifiersState::default(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn pick_entities() {
+        pick_entities_with(false);
+    }
+
+    #[test]
+    fn pick_entities_with_spatial_index() {
+        pick_entities_with(true);
+    }
+
+    fn pick_entities_with(spatial_index: bool) {
+        let mut world = World::new();
+        world.resources.insert(WindowSize::new((800.0, 600.0), 2.0));
+        let mut reader = EventReader::<Picked>::register(&mut world);
+        let mut schedule_builder = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_bounds_update_system)
+            .with_flush(SystemOrder::numbered(1, 0))
+            .with_system_create_fn(SystemOrder::numbered(2, 0), build_camera_update_system)
+            .with_system_create_fn(SystemOrder::numbered(3, 0), build_picking_system);
+        if spatial_index {
+            world.resources.insert(SpatialIndex::default());
+            schedule_builder = schedule_builder.with_system_create_fn(
+                SystemOrder::numbered(2, 1),
+                build_spatial_index_update_system,
+            );
+        }
+        let mut schedule = schedule_builder.build(&mut world);
+
+        let camera = world.insert(
+            (),
+            Some((
+                Camera::perspective(FRAC_PI_2, 1.0, 100.0).unwrap(),
+                ActiveCamera,
+                Position::from_z(10.0),
+                BoundingSphere::new(Point3::origin(), 2.0),
+            )),
+        )[0];
+        let unit_box = Aabb::from_center_half_extents(Point3::origin(), Vector3::repeat(1.0));
+        let near = world.insert((), Some((Position::from_z(2.0), unit_box)))[0];
+        let far = world.insert(
+            (),
+            Some((
+                Position::from_x(4.0),
+                unit_box,
+                BoundingSphere::new(Point3::origin(), 3.0),
+            )),
+        )[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let mut picked = |world: &mut World, events: Vec<Event>| {
+            world
+                .resources
+                .get_mut::<EventChannel<Event>>()
+                .unwrap()
+                .iter_write(events);
+            schedule.execute(world);
+            world
+                .resources
+                .get::<Events<Picked>>()
+                .unwrap()
+                .read(&mut reader)
+                .copied()
+                .collect::<Vec<_>>()
+        };
+
+        // The window is 400x300 logical pixels, with the near plane 1 unit in front of the camera, which is inside the
+        // sphere of the camera itself
+        assert_eq!(
+            picked(&mut world, click(200.0, 150.0, MouseButton::Left)),
+            vec![Picked {
+                entity: near,
+                camera,
+                button: MouseButton::Left,
+                point: Point3::new(0.0, 0.0, 3.0),
+                distance: 6.0,
+            }]
+        );
+        // The second click goes through the sphere of `far`, but not through its box
+        let mut events = click(260.0, 150.0, MouseButton::Right);
+        events.extend(click(222.5, 150.0, MouseButton::Left));
+        let picked_far = picked(&mut world, events);
+        assert_eq!(picked_far.len(), 1);
+        assert_eq!(picked_far[0].entity, far);
+        assert_eq!(picked_far[0].button, MouseButton::Right);
+    }
+
+    #[test]
+    fn pick_through_camera_without_transform() {
+        let mut world = World::new();
+        world.resources.insert(WindowSize::new((800.0, 600.0), 2.0));
+        let mut reader = EventReader::<Picked>::register(&mut world);
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_bounds_update_system)
+            .with_flush(SystemOrder::numbered(1, 0))
+            .with_system_create_fn(SystemOrder::numbered(2, 0), build_camera_update_system)
+            .with_system_create_fn(SystemOrder::numbered(3, 0), build_picking_system)
+            .build(&mut world);
+
+        let camera = world.insert(
+            (),
+            Some((
+                Camera::perspective(FRAC_PI_2, 1.0, 100.0).unwrap(),
+                ActiveCamera,
+            )),
+        )[0];
+        let unit_box = Aabb::from_center_half_extents(Point3::origin(), Vector3::repeat(1.0));
+        let target = world.insert((), Some((Position::from_z(-5.0), unit_box)))[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert!(world.get_component::<WorldTransform>(camera).is_none());
+
+        world
+            .resources
+            .get_mut::<EventChannel<Event>>()
+            .unwrap()
+            .iter_write(click(200.0, 150.0, MouseButton::Left));
+        schedule.execute(&mut world);
+        let picked = world
+            .resources
+            .get::<Events<Picked>>()
+            .unwrap()
+            .read(&mut reader)
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            picked,
+            vec![Picked {
+                entity: target,
+                camera,
+                button: MouseButton::Left,
+                point: Point3::new(0.0, 0.0, -4.0),
+                distance: 3.0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! Serialization of entity references
+//!
+//! An [`Entity`] is only meaningful inside the world that created it, so components that refer to
+//! other entities are serialized with ids given by an [`EntityMapper`]. Such components implement
+//! [`MapEntities`] instead of the serde traits, and the mapper is passed down explicitly: through
+//! [`Mapped`] when serializing, and through [`MappedSeed`], a [`DeserializeSeed`], when
+//! deserializing.
+//!
+//! [`MapEntities`] is implemented for [`Entity`], and for `Option` and `Vec` of the types that
+//! implement it.
+//!
+//! # Examples
+//!
+//! Scenes pass their own mappers, so such components only need to be registered with
+//! [`register_mapped`] to be saved and loaded.
+//!
+//! ```rust
+//! use game_engine::core::entity_serde::{EntityMapper, MapEntities};
+//! use game_engine::core::registry::ComponentRegistry;
+//! use game_engine::core::scene;
+//! use game_engine::ecs::prelude::*;
+//! use serde::{Deserializer, Serializer};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct Target(Entity);
+//!
+//! impl MapEntities for Target {
+//!     fn serialize_mapped<S: Serializer>(
+//!         &self,
+//!         mapper: &dyn EntityMapper,
+//!         serializer: S,
+//!     ) -> Result<S::Ok, S::Error> {
+//!         self.0.serialize_mapped(mapper, serializer)
+//!     }
+//!
+//!     fn deserialize_mapped<'de, D: Deserializer<'de>>(
+//!         mapper: &mut dyn EntityMapper,
+//!         deserializer: D,
+//!     ) -> Result<Self, D::Error> {
+//!         Entity::deserialize_mapped(mapper, deserializer).map(Target)
+//!     }
+//! }
+//!
+//! let mut registry = ComponentRegistry::new();
+//! registry.register_mapped::<Target>("game::Target");
+//!
+//! let mut world = World::new();
+//! let entities = world.insert((), vec![(0u8,), (1u8,)]).to_vec();
+//! world.add_component(entities[0], Target(entities[1]));
+//! let json = scene::save_json(&world, &registry, &entities).unwrap();
+//! assert!(json.contains(r#""game::Target": 1"#));
+//!
+//! let loaded = scene::load_json(&mut world, &registry, &json).unwrap();
+//! assert_eq!(*world.get_component::<Target>(loaded[0]).unwrap(), Target(loaded[1]));
+//! ```
+//!
+//! [`Entity`]: ../../legion/entity/struct.Entity.html
+//! [`EntityMapper`]: ./trait.EntityMapper.html
+//! [`MapEntities`]: ./trait.MapEntities.html
+//! [`Mapped`]: ./struct.Mapped.html
+//! [`MappedSeed`]: ./struct.MappedSeed.html
+//! [`DeserializeSeed`]: https://docs.rs/serde/1/serde/de/trait.DeserializeSeed.html
+//! [`register_mapped`]: ../registry/struct.ComponentRegistry.html#method.register_mapped
+
+use crate::ecs::entity::Entity;
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error as _, SeqAccess, Visitor};
+use serde::ser::{Error as _, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Mapping between entities and the ids they are serialized with
+pub trait EntityMapper {
+    /// Get the id to serialize the given entity with
+    ///
+    /// Returning `None` makes the serialization fail.
+    fn entity_to_id(&self, entity: Entity) -> Option<u64>;
+
+    /// Get the entity for the given serialized id
+    ///
+    /// Returning `None` makes the deserialization fail.
+    fn id_to_entity(&mut self, id: u64) -> Option<Entity>;
+}
+
+/// Mapper that maps nothing, used where no mapper is given
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NoMapper;
+
+impl EntityMapper for NoMapper {
+    fn entity_to_id(&self, _entity: Entity) -> Option<u64> {
+        None
+    }
+
+    fn id_to_entity(&mut self, _id: u64) -> Option<Entity> {
+        None
+    }
+}
+
+/// Values that refer to entities, and are (de)serialized with an [`EntityMapper`]
+///
+/// [`EntityMapper`]: ./trait.EntityMapper.html
+pub trait MapEntities: Sized {
+    /// Serialize this value, turning the entities into ids with the given mapper
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize a value, turning the ids into entities with the given mapper
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+impl MapEntities for Entity {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match mapper.entity_to_id(*self) {
+            Some(id) => serializer.serialize_u64(id),
+            None => Err(S::Error::custom(format_args!(
+                "entity {} is not mapped to any id",
+                self
+            ))),
+        }
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let id = u64::deserialize(deserializer)?;
+        mapper
+            .id_to_entity(id)
+            .ok_or_else(|| D::Error::custom(format_args!("id {} is not mapped to any entity", id)))
+    }
+}
+
+impl<T: MapEntities> MapEntities for Option<T> {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Mapped::new(value, mapper)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_option(OptionVisitor(MappedSeed::new(mapper)))
+    }
+}
+
+impl<T: MapEntities> MapEntities for Vec<T> {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|value| Mapped::new(value, mapper)))
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(VecVisitor(MappedSeed::new(mapper)))
+    }
+}
+
+/// Serializable view of a value that refers to entities, with the mapper to serialize it with
+pub struct Mapped<'a, T> {
+    value: &'a T,
+    mapper: &'a dyn EntityMapper,
+}
+
+impl<'a, T> Mapped<'a, T> {
+    /// Create a new instance that serializes the given value with the given mapper
+    pub fn new(value: &'a T, mapper: &'a dyn EntityMapper) -> Self {
+        Self { value, mapper }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Mapped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Mapped").field(self.value).finish()
+    }
+}
+
+impl<T: MapEntities> Serialize for Mapped<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize_mapped(self.mapper, serializer)
+    }
+}
+
+/// Seed that deserializes a value of `T` with the given mapper
+pub struct MappedSeed<'a, T> {
+    mapper: &'a mut dyn EntityMapper,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> MappedSeed<'a, T> {
+    /// Create a new instance that deserializes with the given mapper
+    pub fn new(mapper: &'a mut dyn EntityMapper) -> Self {
+        Self {
+            mapper,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for MappedSeed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MappedSeed")
+    }
+}
+
+impl<'de, T: MapEntities> DeserializeSeed<'de> for MappedSeed<'_, T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        T::deserialize_mapped(self.mapper, deserializer)
+    }
+}
+
+struct OptionVisitor<'a, T>(MappedSeed<'a, T>);
+
+impl<'de, T: MapEntities> Visitor<'de> for OptionVisitor<'_, T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an optional value")
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(deserializer).map(Some)
+    }
+}
+
+struct VecVisitor<'a, T>(MappedSeed<'a, T>);
+
+impl<'de, T: MapEntities> Visitor<'de> for VecVisitor<'_, T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mapper = self.0.mapper;
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element_seed(MappedSeed::new(&mut *mapper))? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntityMapper, Mapped, MappedSeed, NoMapper};
+    use crate::ecs::entity::Entity;
+    use crate::ecs::world::World;
+    use serde::de::DeserializeSeed;
+
+    struct IndexMapper(Vec<Entity>);
+
+    impl EntityMapper for IndexMapper {
+        fn entity_to_id(&self, entity: Entity) -> Option<u64> {
+            self.0.iter().position(|e| *e == entity).map(|i| i as u64)
+        }
+
+        fn id_to_entity(&mut self, id: u64) -> Option<Entity> {
+            self.0.get(id as usize).copied()
+        }
+    }
+
+    fn from_json<T: super::MapEntities>(
+        mapper: &mut dyn EntityMapper,
+        json: &str,
+    ) -> Result<T, serde_json::Error> {
+        MappedSeed::new(mapper).deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn round_trip_with_mapper() {
+        let mut world = World::new();
+        let entities = world.insert((), vec![(0u8,), (1u8,), (2u8,)]).to_vec();
+        let mut mapper = IndexMapper(entities.clone());
+
+        let value = vec![Some(entities[2]), None, Some(entities[0])];
+        let json = serde_json::to_string(&Mapped::new(&value, &mapper)).unwrap();
+        assert_eq!(json, "[2,null,0]");
+
+        let parsed: Vec<Option<Entity>> = from_json(&mut mapper, &json).unwrap();
+        assert_eq!(parsed, value);
+
+        assert!(from_json::<Entity>(&mut mapper, "3").is_err());
+    }
+
+    #[test]
+    fn no_mapper_maps_nothing() {
+        let mut world = World::new();
+        let entity = world.insert((), vec![(0u8,)])[0];
+
+        assert!(serde_json::to_string(&Mapped::new(&entity, &NoMapper)).is_err());
+        assert!(from_json::<Entity>(&mut NoMapper, "0").is_err());
+        assert_eq!(
+            from_json::<Option<Entity>>(&mut NoMapper, "null").unwrap(),
+            None
+        );
+    }
+}
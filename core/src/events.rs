@@ -0,0 +1,330 @@
+//! Typed event queues with a limited capacity
+//!
+//! [`Events<T>`] is a resource that keeps the last events of type `T` written into it, and
+//! [`EventReader<T>`] is a cursor that each consumer owns to read the events it has not read yet.
+//! When a reader falls behind so much that the events it has not read are dropped from the queue,
+//! the reader counts the lost events.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::core::events::{EventReader, Events};
+//! use game_engine::ecs::prelude::*;
+//!
+//! let mut world = World::new();
+//!
+//! // The channel is created on demand when the reader is registered
+//! let mut reader = EventReader::<u32>::register(&mut world);
+//!
+//! let mut events = world.resources.get_mut::<Events<u32>>().unwrap();
+//! events.iter_write(vec![1, 2, 3]);
+//!
+//! assert_eq!(events.read(&mut reader).copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+//! assert_eq!(events.read(&mut reader).count(), 0);
+//! ```
+//!
+//! [`Events<T>`]: ./struct.Events.html
+//! [`EventReader<T>`]: ./struct.EventReader.html
+
+use crate::ecs::world::World;
+use crate::event_channel::Event;
+use crate::sized_queue::{self, SizedQueue};
+use std::fmt;
+use std::iter::Skip;
+use std::marker::PhantomData;
+
+/// The default capacity of [`Events<T>`]
+///
+/// [`Events<T>`]: ./struct.Events.html
+pub const DEFAULT_EVENTS_CAPACITY: usize = 64;
+
+/// Resource that keeps the last events of type `T`
+///
+/// The oldest events are dropped when the number of events exceeds the capacity. Use
+/// [`EventReader<T>`] to read the events.
+///
+/// [`EventReader<T>`]: ./struct.EventReader.html
+#[derive(Clone, Debug)]
+pub struct Events<T> {
+    queue: SizedQueue<T>,
+    /// The number of all events dropped from the queue, which is also the index of the oldest
+    /// event in the queue
+    dropped: u64,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Events<T> {
+    /// Create a new instance with the default capacity
+    ///
+    /// The default capacity is `64`.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_EVENTS_CAPACITY)
+    }
+
+    /// Create a new instance that keeps at most <capacity> events
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            queue: SizedQueue::new(capacity),
+            dropped: 0,
+        }
+    }
+
+    /// Get the maximum number of events this channel keeps
+    pub fn capacity(&self) -> usize {
+        self.queue.size()
+    }
+
+    /// Change the maximum number of events this channel keeps
+    ///
+    /// If the new capacity is smaller than the number of stored events, the oldest events are
+    /// dropped.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.dropped += self.queue.resize(capacity) as u64;
+    }
+
+    /// Get how many events are stored
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Check if there are no events stored
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Write a single event
+    pub fn single_write(&mut self, event: T) {
+        if self.queue.push(event).is_some() {
+            self.dropped += 1;
+        }
+    }
+
+    /// Write all events from the given iterator
+    pub fn iter_write<I>(&mut self, events: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for event in events {
+            self.single_write(event);
+        }
+    }
+
+    /// Create a new reader that reads events written after this call
+    pub fn register_reader(&self) -> EventReader<T> {
+        EventReader {
+            next: self.end(),
+            lost: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Read the events that the given reader has not read yet
+    ///
+    /// If some of those events are already dropped, the reader records how many of them are lost.
+    pub fn read(&self, reader: &mut EventReader<T>) -> EventIter<'_, T> {
+        if reader.next < self.dropped {
+            let lost = self.dropped - reader.next;
+            log::warn!(
+                "An event reader of `{}` was too slow and lost {} events",
+                std::any::type_name::<T>(),
+                lost
+            );
+            reader.lost += lost;
+            reader.next = self.dropped;
+        }
+
+        let skip = (reader.next - self.dropped) as usize;
+        reader.next = self.end();
+
+        EventIter {
+            iter: self.queue.iter().skip(skip),
+        }
+    }
+
+    /// Get the number of events the given reader has not read yet, including the lost ones
+    pub fn unread_count(&self, reader: &EventReader<T>) -> u64 {
+        self.end().saturating_sub(reader.next)
+    }
+
+    /// Check if writing one more event would make the given reader lose an event
+    pub fn would_lose(&self, reader: &EventReader<T>) -> bool {
+        self.unread_count(reader) >= self.capacity() as u64
+    }
+
+    fn end(&self) -> u64 {
+        self.dropped + self.queue.len() as u64
+    }
+}
+
+impl<T: Event> Events<T> {
+    /// Insert a new channel with the default capacity into the world if it does not exist
+    ///
+    /// The capacity of an existing channel is left as is.
+    pub fn ensure(world: &mut World) {
+        world.resources.get_or_insert_with(Events::<T>::new);
+    }
+
+    /// Insert a new channel with the given capacity into the world if it does not exist
+    ///
+    /// If the channel already exists with a smaller capacity, its capacity grows to the given one.
+    pub fn ensure_with_capacity(world: &mut World, capacity: usize) {
+        let mut events = world
+            .resources
+            .get_mut_or_insert_with(|| Events::<T>::with_capacity(capacity))
+            .expect("the event channel is just inserted");
+        if events.capacity() < capacity {
+            events.set_capacity(capacity);
+        }
+    }
+}
+
+/// Cursor for reading events from [`Events<T>`]
+///
+/// Each consumer should own its own reader.
+///
+/// [`Events<T>`]: ./struct.Events.html
+pub struct EventReader<T> {
+    /// The index of the next event to read
+    next: u64,
+    /// The number of events dropped before this reader read them
+    lost: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for EventReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventReader")
+            .field("next", &self.next)
+            .field("lost", &self.lost)
+            .finish()
+    }
+}
+
+impl<T: Event> EventReader<T> {
+    /// Register a new reader, inserting the channel into the world if it does not exist
+    pub fn register(world: &mut World) -> Self {
+        Events::<T>::ensure(world);
+        world
+            .resources
+            .get::<Events<T>>()
+            .expect("the event channel is just inserted")
+            .register_reader()
+    }
+}
+
+impl<T> EventReader<T> {
+    /// Get the total number of events that were dropped before this reader read them
+    pub fn lost_events(&self) -> u64 {
+        self.lost
+    }
+
+    /// Get the number of lost events and reset the counter
+    pub fn take_lost_events(&mut self) -> u64 {
+        std::mem::replace(&mut self.lost, 0)
+    }
+}
+
+/// Iterator of events returned by [`Events::read`]
+///
+/// [`Events::read`]: ./struct.Events.html#method.read
+#[derive(Clone, Debug)]
+pub struct EventIter<'a, T> {
+    iter: Skip<sized_queue::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for EventIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for EventIter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventReader, Events};
+    use crate::ecs::world::World;
+
+    #[test]
+    fn read_only_new_events() {
+        let mut events = Events::with_capacity(4);
+        events.single_write(0u32);
+
+        let mut reader = events.register_reader();
+        assert_eq!(events.read(&mut reader).count(), 0);
+
+        events.iter_write(vec![1, 2]);
+        assert_eq!(
+            events.read(&mut reader).copied().collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        events.single_write(3);
+        assert_eq!(events.read(&mut reader).copied().collect::<Vec<_>>(), [3]);
+        assert_eq!(reader.lost_events(), 0);
+    }
+
+    #[test]
+    fn slow_reader_loses_events() {
+        let mut events = Events::with_capacity(3);
+        let mut slow = events.register_reader();
+        let mut fast = events.register_reader();
+
+        events.iter_write(vec![0u32, 1, 2]);
+        assert!(events.would_lose(&slow));
+        assert_eq!(events.read(&mut fast).count(), 3);
+
+        events.iter_write(vec![3, 4]);
+        assert_eq!(events.unread_count(&slow), 5);
+        assert_eq!(
+            events.read(&mut slow).copied().collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert_eq!(slow.take_lost_events(), 2);
+        assert_eq!(slow.lost_events(), 0);
+
+        assert_eq!(events.read(&mut fast).copied().collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(fast.lost_events(), 0);
+    }
+
+    #[test]
+    fn shrinking_capacity_drops_old_events() {
+        let mut events = Events::with_capacity(4);
+        let mut reader = events.register_reader();
+
+        events.iter_write(vec![0u32, 1, 2, 3]);
+        events.set_capacity(2);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events.read(&mut reader).copied().collect::<Vec<_>>(),
+            [2, 3]
+        );
+        assert_eq!(reader.lost_events(), 2);
+    }
+
+    #[test]
+    fn channel_created_on_demand() {
+        let mut world = World::new();
+
+        Events::<u32>::ensure_with_capacity(&mut world, 8);
+        let mut reader = EventReader::<u32>::register(&mut world);
+        Events::<u32>::ensure_with_capacity(&mut world, 16);
+
+        let mut events = world.resources.get_mut::<Events<u32>>().unwrap();
+        assert_eq!(events.capacity(), 16);
+
+        events.single_write(7);
+        assert_eq!(events.read(&mut reader).copied().collect::<Vec<_>>(), [7]);
+    }
+}
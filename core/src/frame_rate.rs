@@ -119,13 +119,14 @@ impl FrameRateConfig {
 }
 
 /// How to wait for the time to start the next frame
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum FrameRateStrategy {
     /// Ignore the desired fps value and make the frame rate as fast as possible
     AsFastAsPossible,
     /// Keep the frame rate as close as possible to the desired fps value
     ///
     /// This is the default value.
+    #[default]
     Precise,
     /// Keep the frame rate equal to or slower than the desired fps value
     ///
@@ -142,12 +143,6 @@ pub enum FrameRateStrategy {
     },
 }
 
-impl Default for FrameRateStrategy {
-    fn default() -> Self {
-        FrameRateStrategy::Precise
-    }
-}
-
 /// Get the duration of a single frame from the given fps value
 pub fn frame_duration_from_fps(fps: u32) -> Duration {
     Duration::from_secs(1) / fps
@@ -228,12 +223,43 @@ fn nanoseconds_to_fps(nanoseconds: f32) -> f32 {
 #[system_desc(type(types::Parallel), fn(build_fps_count_system))]
 pub struct FpsCountSystem;
 
+/// System descriptor(builder) for fps counting with the given size of the queue of fps values
+///
+/// This is a wrapper for [`build_fps_count_system_with_queue_size`].
+///
+/// [`build_fps_count_system_with_queue_size`]: ./fn.build_fps_count_system_with_queue_size.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(
+    type(types::Parallel),
+    fn(build_fps_count_system_with_queue_size),
+    args(queue_size)
+)]
+pub struct FpsCountSystemWithQueueSize {
+    /// The number of the last fps values to keep
+    pub queue_size: usize,
+}
+
 /// Build a system that measures frame per second value
 ///
-/// The `size` parameter is for determining the size of the queue that stores the last fps values.
-/// Thus, it impacts how the average fps value is calculated.
+/// This keeps the default amount of the last fps values. See [`build_fps_count_system_with_queue_size`]
+/// for configuring it.
+///
+/// [`build_fps_count_system_with_queue_size`]: ./fn.build_fps_count_system_with_queue_size.html
 pub fn build_fps_count_system(world: &mut World) -> Box<dyn Schedulable> {
-    world.resources.insert(FpsValue::new());
+    build_fps_count_system_with_queue_size(world, 10)
+}
+
+/// Build a system that measures frame per second value
+///
+/// The `queue_size` parameter is for determining the size of the queue that stores the last fps
+/// values. Thus, it impacts how the average fps value is calculated.
+pub fn build_fps_count_system_with_queue_size(
+    world: &mut World,
+    queue_size: usize,
+) -> Box<dyn Schedulable> {
+    world
+        .resources
+        .insert(FpsValue::from_queue_size(queue_size));
 
     SystemBuilder::new("FpsCount")
         .write_resource::<FpsValue>()
@@ -247,7 +273,9 @@ pub fn build_fps_count_system(world: &mut World) -> Box<dyn Schedulable> {
 
 #[cfg(test)]
 mod tests {
-    use super::FrameRateKeeper;
+    use super::{FpsCountSystemWithQueueSize, FpsValue, FrameRateKeeper};
+    use crate::ecs::world::World;
+    use crate::systems::SystemDesc;
     use crate::time::Timer;
 
     #[test]
@@ -262,4 +290,13 @@ mod tests {
         let duration = timer.get();
         assert!((duration - keeper.frame_duration).as_nanos() < 100_000);
     }
+
+    #[test]
+    fn fps_count_system_queue_size() {
+        let mut world = World::new();
+        let _system = FpsCountSystemWithQueueSize { queue_size: 3 }.build(&mut world);
+
+        let fps_value = world.resources.get::<FpsValue>().unwrap();
+        assert_eq!(fps_value.past_fps.size(), 3);
+    }
 }
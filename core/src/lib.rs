@@ -7,8 +7,12 @@
     missing_debug_implementations
 )]
 
+pub mod entity_serde;
+pub mod events;
 pub mod frame_rate;
 mod query_all;
+pub mod registry;
+pub mod scene;
 pub mod sized_queue;
 pub mod systems;
 pub mod time;
@@ -0,0 +1,598 @@
+//! Registry of component types that can be referred to by name at runtime
+//!
+//! [`ComponentRegistry`] is a resource that maps a stable name to each registered component type.
+//! Each [`ComponentRegistration`] can check, read, add, remove, and (de)serialize the component on
+//! any entity of a world without knowing its type at compile time, which is what inspectors, scene
+//! files, and console commands need.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::core::registry::ComponentRegistry;
+//! use game_engine::ecs::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+//! struct Health(u32);
+//!
+//! let mut registry = ComponentRegistry::new();
+//! registry.register::<Health>("game::Health");
+//!
+//! let mut world = World::new();
+//! let entity = world.insert((), vec![(Health(10),)])[0];
+//!
+//! let health = registry.get("game::Health").unwrap();
+//! assert!(health.has(&world, entity));
+//!
+//! let serialized = health.serialize(&world, entity).unwrap();
+//! assert_eq!(serde_json::to_string(&serialized).unwrap(), "10");
+//!
+//! let mut deserializer = serde_json::Deserializer::from_str("42");
+//! let component = health.deserialize(&mut deserializer).unwrap();
+//! health.add(&mut world, entity, component).unwrap();
+//! assert_eq!(*world.get_component::<Health>(entity).unwrap(), Health(42));
+//! ```
+//!
+//! [`ComponentRegistry`]: ./struct.ComponentRegistry.html
+//! [`ComponentRegistration`]: ./struct.ComponentRegistration.html
+
+use crate::ecs::entity::Entity;
+use crate::ecs::storage::Component;
+use crate::ecs::world::World;
+use crate::entity_serde::{EntityMapper, MapEntities, Mapped, MappedSeed, NoMapper};
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde::{Serialize, Serializer};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Component value whose type is only known at runtime
+pub type BoxedComponent = Box<dyn Any + Send + Sync>;
+
+/// Error returned when a component could not be added to an entity
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComponentError {
+    /// The entity is not alive in the world
+    NoSuchEntity(Entity),
+    /// The given value is not of the registered component type
+    TypeMismatch {
+        /// The name of the registered component
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentError::NoSuchEntity(entity) => write!(f, "entity {} is not alive", entity),
+            ComponentError::TypeMismatch { name } => {
+                write!(f, "the value is not a component of `{}`", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ComponentError {}
+
+/// Type-erased operations on a registered component type
+#[derive(Clone, Copy)]
+pub struct ComponentRegistration {
+    name: &'static str,
+    type_id: TypeId,
+    type_name: &'static str,
+    derived: bool,
+    has: fn(&World, Entity) -> bool,
+    read: fn(&World, Entity) -> Option<BoxedComponent>,
+    add: fn(&mut World, Entity, BoxedComponent, &'static str) -> Result<(), ComponentError>,
+    remove: fn(&mut World, Entity) -> bool,
+    serialize: SerializeFn,
+    serialize_value: SerializeValueFn,
+    deserialize: DeserializeFn,
+}
+
+type SerializeFn = for<'m> fn(
+    &World,
+    Entity,
+    &'m dyn EntityMapper,
+) -> Option<Box<dyn erased_serde::Serialize + 'm>>;
+
+type SerializeValueFn = for<'a> fn(
+    &'a (dyn Any + Send + Sync),
+    &'a dyn EntityMapper,
+) -> Option<Box<dyn erased_serde::Serialize + 'a>>;
+
+type DeserializeFn = fn(
+    &mut dyn erased_serde::Deserializer<'_>,
+    &mut dyn EntityMapper,
+) -> Result<BoxedComponent, erased_serde::Error>;
+
+impl fmt::Debug for ComponentRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentRegistration")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("derived", &self.derived)
+            .finish()
+    }
+}
+
+impl ComponentRegistration {
+    /// Create a registration of the component type `T` with the given name
+    pub fn of<T>(name: &'static str) -> Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        Self::with_serde::<T>(
+            name,
+            |world, entity, _| {
+                world.get_component::<T>(entity).map(|component| {
+                    Box::new(T::clone(&component)) as Box<dyn erased_serde::Serialize>
+                })
+            },
+            |component, _| {
+                component
+                    .downcast_ref::<T>()
+                    .map(|component| Box::new(component) as Box<dyn erased_serde::Serialize>)
+            },
+            |deserializer, _| {
+                erased_serde::deserialize::<T>(deserializer)
+                    .map(|component| Box::new(component) as BoxedComponent)
+            },
+        )
+    }
+
+    /// Create a registration of the component type `T`, which refers to other entities, with the
+    /// given name
+    ///
+    /// The component is (de)serialized through [`MapEntities`], with the mapper given to
+    /// [`serialize_mapped`] and [`deserialize_mapped`].
+    ///
+    /// [`MapEntities`]: ../entity_serde/trait.MapEntities.html
+    /// [`serialize_mapped`]: #method.serialize_mapped
+    /// [`deserialize_mapped`]: #method.deserialize_mapped
+    pub fn of_mapped<T>(name: &'static str) -> Self
+    where
+        T: Component + Clone + MapEntities,
+    {
+        Self::with_serde::<T>(
+            name,
+            |world, entity, mapper| {
+                world.get_component::<T>(entity).map(|component| {
+                    Box::new(OwnedMapped(T::clone(&component), mapper))
+                        as Box<dyn erased_serde::Serialize>
+                })
+            },
+            |component, mapper| {
+                component.downcast_ref::<T>().map(|component| {
+                    Box::new(Mapped::new(component, mapper)) as Box<dyn erased_serde::Serialize>
+                })
+            },
+            |deserializer, mapper| {
+                MappedSeed::<T>::new(mapper)
+                    .deserialize(deserializer)
+                    .map(|component| Box::new(component) as BoxedComponent)
+            },
+        )
+    }
+
+    fn with_serde<T: Component + Clone>(
+        name: &'static str,
+        serialize: SerializeFn,
+        serialize_value: SerializeValueFn,
+        deserialize: DeserializeFn,
+    ) -> Self {
+        Self {
+            name,
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            derived: false,
+            has: |world, entity| world.get_component::<T>(entity).is_some(),
+            read: |world, entity| {
+                world
+                    .get_component::<T>(entity)
+                    .map(|component| Box::new(T::clone(&component)) as BoxedComponent)
+            },
+            add: add_component::<T>,
+            remove: |world, entity| {
+                let had = world.get_component::<T>(entity).is_some();
+                world.remove_component::<T>(entity);
+                had
+            },
+            serialize,
+            serialize_value,
+            deserialize,
+        }
+    }
+
+    /// Mark the component as derived from other components
+    ///
+    /// Derived components, like the list of children that is rebuilt from the parents, are not
+    /// saved to scenes.
+    pub fn derived(mut self) -> Self {
+        self.derived = true;
+        self
+    }
+
+    /// Check if the component is derived from other components
+    pub fn is_derived(&self) -> bool {
+        self.derived
+    }
+
+    /// Get the registered name of the component
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the [`TypeId`] of the component
+    ///
+    /// [`TypeId`]: https://doc.rust-lang.org/std/any/struct.TypeId.html
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Get the Rust type name of the component, which is only meant for diagnostics
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Check if the entity has the component
+    pub fn has(&self, world: &World, entity: Entity) -> bool {
+        (self.has)(world, entity)
+    }
+
+    /// Get a copy of the component of the entity
+    pub fn read(&self, world: &World, entity: Entity) -> Option<BoxedComponent> {
+        (self.read)(world, entity)
+    }
+
+    /// Add the component to the entity, or replace it if the entity already has one
+    pub fn add(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        component: BoxedComponent,
+    ) -> Result<(), ComponentError> {
+        (self.add)(world, entity, component, self.name)
+    }
+
+    /// Remove the component from the entity, and return whether the entity had one
+    pub fn remove(&self, world: &mut World, entity: Entity) -> bool {
+        (self.remove)(world, entity)
+    }
+
+    /// Get a serializable copy of the component of the entity
+    ///
+    /// Components that refer to other entities fail to serialize; use [`serialize_mapped`] for
+    /// them.
+    ///
+    /// [`serialize_mapped`]: #method.serialize_mapped
+    pub fn serialize(
+        &self,
+        world: &World,
+        entity: Entity,
+    ) -> Option<Box<dyn erased_serde::Serialize>> {
+        (self.serialize)(world, entity, &NoMapper)
+    }
+
+    /// Get a serializable copy of the component of the entity, which serializes the entities it
+    /// refers to with the given mapper
+    pub fn serialize_mapped<'m>(
+        &self,
+        world: &World,
+        entity: Entity,
+        mapper: &'m dyn EntityMapper,
+    ) -> Option<Box<dyn erased_serde::Serialize + 'm>> {
+        (self.serialize)(world, entity, mapper)
+    }
+
+    /// Get the given component value as serializable, or `None` if it is not of this component type
+    ///
+    /// Components that refer to other entities fail to serialize.
+    pub fn serialize_value<'a>(
+        &self,
+        component: &'a (dyn Any + Send + Sync),
+    ) -> Option<Box<dyn erased_serde::Serialize + 'a>> {
+        (self.serialize_value)(component, &NoMapper)
+    }
+
+    /// Deserialize a component value from the given deserializer
+    ///
+    /// Components that refer to other entities fail to deserialize; use [`deserialize_mapped`]
+    /// for them.
+    ///
+    /// [`deserialize_mapped`]: #method.deserialize_mapped
+    pub fn deserialize<'de, D>(&self, deserializer: D) -> Result<BoxedComponent, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.deserialize_mapped(deserializer, &mut NoMapper)
+    }
+
+    /// Deserialize a component value from the given deserializer, getting the entities it refers
+    /// to from the given mapper
+    pub fn deserialize_mapped<'de, D>(
+        &self,
+        deserializer: D,
+        mapper: &mut dyn EntityMapper,
+    ) -> Result<BoxedComponent, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut erased = <dyn erased_serde::Deserializer<'_>>::erase(deserializer);
+        (self.deserialize)(&mut erased, mapper).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Owned component with the mapper to serialize it with
+struct OwnedMapped<'m, T>(T, &'m dyn EntityMapper);
+
+impl<T: MapEntities> Serialize for OwnedMapped<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_mapped(self.1, serializer)
+    }
+}
+
+fn add_component<T: Component>(
+    world: &mut World,
+    entity: Entity,
+    component: BoxedComponent,
+    name: &'static str,
+) -> Result<(), ComponentError> {
+    if !world.is_alive(entity) {
+        return Err(ComponentError::NoSuchEntity(entity));
+    }
+
+    let component = component
+        .downcast::<T>()
+        .map_err(|_| ComponentError::TypeMismatch { name })?;
+    world.add_component(entity, *component);
+    Ok(())
+}
+
+/// Resource that maps stable names to component types
+#[derive(Clone, Debug, Default)]
+pub struct ComponentRegistry {
+    registrations: Vec<ComponentRegistration>,
+    by_name: HashMap<&'static str, usize>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl ComponentRegistry {
+    /// Create a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the component type `T` with the given name
+    ///
+    /// Registering the same type with the same name again does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is already used by another type, or the type is already registered with
+    /// another name.
+    pub fn register<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        self.add_registration(ComponentRegistration::of::<T>(name))
+    }
+
+    /// Register the component type `T` with the given name as a derived component
+    ///
+    /// See [`ComponentRegistration::derived`] for what a derived component is.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`register`].
+    ///
+    /// [`ComponentRegistration::derived`]: ./struct.ComponentRegistration.html#method.derived
+    /// [`register`]: #method.register
+    pub fn register_derived<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        self.add_registration(ComponentRegistration::of::<T>(name).derived())
+    }
+
+    /// Register the component type `T`, which refers to other entities, with the given name
+    ///
+    /// See [`ComponentRegistration::of_mapped`] for how the component is (de)serialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`register`].
+    ///
+    /// [`ComponentRegistration::of_mapped`]: ./struct.ComponentRegistration.html#method.of_mapped
+    /// [`register`]: #method.register
+    pub fn register_mapped<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Clone + MapEntities,
+    {
+        self.add_registration(ComponentRegistration::of_mapped::<T>(name))
+    }
+
+    /// Chain method version of [`register`]
+    ///
+    /// [`register`]: #method.register
+    pub fn with<T>(mut self, name: &'static str) -> Self
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+    {
+        self.register::<T>(name);
+        self
+    }
+
+    /// Add the given registration
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`register`].
+    ///
+    /// [`register`]: #method.register
+    pub fn add_registration(&mut self, registration: ComponentRegistration) -> &mut Self {
+        let by_name = self.by_name.get(registration.name).copied();
+        let by_type = self.by_type.get(&registration.type_id).copied();
+
+        match (by_name, by_type) {
+            (Some(a), Some(b)) if a == b => {}
+            (None, None) => {
+                let index = self.registrations.len();
+                self.by_name.insert(registration.name, index);
+                self.by_type.insert(registration.type_id, index);
+                self.registrations.push(registration);
+            }
+            (Some(index), _) => panic!(
+                "component name `{}` is already registered for `{}`",
+                registration.name, self.registrations[index].type_name,
+            ),
+            (None, Some(index)) => panic!(
+                "component `{}` is already registered as `{}`",
+                registration.type_name, self.registrations[index].name,
+            ),
+        }
+
+        self
+    }
+
+    /// Get the registration with the given name
+    pub fn get(&self, name: &str) -> Option<&ComponentRegistration> {
+        self.by_name.get(name).map(|i| &self.registrations[*i])
+    }
+
+    /// Get the registration of the component type `T`
+    pub fn get_by_type<T: Component>(&self) -> Option<&ComponentRegistration> {
+        self.get_by_type_id(TypeId::of::<T>())
+    }
+
+    /// Get the registration of the component type with the given [`TypeId`]
+    ///
+    /// [`TypeId`]: https://doc.rust-lang.org/std/any/struct.TypeId.html
+    pub fn get_by_type_id(&self, type_id: TypeId) -> Option<&ComponentRegistration> {
+        self.by_type.get(&type_id).map(|i| &self.registrations[*i])
+    }
+
+    /// Check if a component is registered with the given name
+    pub fn contains(&self, name: &str) -> bool {
+        self.by_name.contains_key(name)
+    }
+
+    /// Get how many component types are registered
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    /// Check if no component types are registered
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    /// Get an iterator of the registrations in the order they were registered
+    pub fn iter(&self) -> std::slice::Iter<'_, ComponentRegistration> {
+        self.registrations.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentError, ComponentRegistry};
+    use crate::ecs::world::World;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Health(u32);
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Name(String);
+
+    fn registry() -> ComponentRegistry {
+        ComponentRegistry::new()
+            .with::<Health>("test::Health")
+            .with::<Name>("test::Name")
+    }
+
+    #[test]
+    fn lookup_by_name_and_type() {
+        let mut registry = registry();
+        registry.register::<Health>("test::Health");
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get_by_type::<Name>().unwrap().name(), "test::Name");
+        assert!(registry
+            .get("test::Health")
+            .unwrap()
+            .type_name()
+            .ends_with("Health"));
+        assert!(!registry.contains("test::Missing"));
+        assert_eq!(
+            registry.iter().map(|r| r.name()).collect::<Vec<_>>(),
+            ["test::Health", "test::Name"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn conflicting_name_panics() {
+        registry().register::<u64>("test::Health");
+    }
+
+    #[test]
+    fn read_add_and_remove() {
+        let registry = registry();
+        let health = registry.get("test::Health").unwrap();
+        let name = registry.get("test::Name").unwrap();
+
+        let mut world = World::new();
+        let entity = world.insert((), vec![(Health(3),)])[0];
+
+        let value = health.read(&world, entity).unwrap();
+        assert_eq!(value.downcast_ref::<Health>(), Some(&Health(3)));
+        let serialized = serde_json::to_string(&health.serialize_value(&*value).unwrap()).unwrap();
+        assert_eq!(serialized, "3");
+        assert!(name.serialize_value(&*value).is_none());
+        assert!(name.read(&world, entity).is_none());
+
+        name.add(&mut world, entity, Box::new(Name("orc".into())))
+            .unwrap();
+        assert_eq!(
+            *world.get_component::<Name>(entity).unwrap(),
+            Name("orc".into())
+        );
+
+        assert_eq!(
+            name.add(&mut world, entity, Box::new(Health(1))),
+            Err(ComponentError::TypeMismatch { name: "test::Name" })
+        );
+
+        assert!(health.remove(&mut world, entity));
+        assert!(!health.has(&world, entity));
+        assert!(!health.remove(&mut world, entity));
+
+        world.delete(entity);
+        assert_eq!(
+            health.add(&mut world, entity, Box::new(Health(1))),
+            Err(ComponentError::NoSuchEntity(entity))
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let registry = registry();
+        let name = registry.get("test::Name").unwrap();
+
+        let mut world = World::new();
+        let from = world.insert((), vec![(Name("elf".into()),)])[0];
+        let to = world.insert((), vec![(Health(1),)])[0];
+
+        let ron = ron::ser::to_string(&name.serialize(&world, from).unwrap()).unwrap();
+        assert_eq!(ron, r#"("elf")"#);
+
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        let value = name.deserialize(&mut deserializer).unwrap();
+        name.add(&mut world, to, value).unwrap();
+        assert_eq!(
+            *world.get_component::<Name>(to).unwrap(),
+            Name("elf".into())
+        );
+    }
+}
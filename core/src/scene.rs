@@ -0,0 +1,632 @@
+//! Saving entities to scene files and loading them back
+//!
+//! A scene is a list of entities, each with an id and a map of its components keyed by the names
+//! in the [`ComponentRegistry`]. The ids are only used inside the scene, so that components
+//! referring to other entities, like `Parent`, are remapped to the newly spawned entities when the
+//! scene is loaded. Derived components are not saved; they are expected to be rebuilt by the
+//! systems that maintain them.
+//!
+//! In RON, a scene looks like below.
+//!
+//! ```ron
+//! (
+//!     entities: [
+//!         (id: 0, components: {"transform::Position": (0, 1, 0)}),
+//!         (id: 1, components: {"transform::Position": (2, 0, 0), "transform::Parent": 0}),
+//!     ],
+//! )
+//! ```
+//!
+//! [`SceneSerializer`] and [`SceneDeserializer`] work with any serde format, and [`save_ron`],
+//! [`load_ron`], [`save_json`], and [`load_json`] are shortcuts for RON and JSON.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::core::registry::ComponentRegistry;
+//! use game_engine::core::scene;
+//! use game_engine::ecs::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+//! struct Health(u32);
+//!
+//! let registry = ComponentRegistry::new().with::<Health>("game::Health");
+//!
+//! let mut world = World::new();
+//! let entities = world.insert((), vec![(Health(3),), (Health(5),)]).to_vec();
+//! let ron = scene::save_ron(&world, &registry, &entities).unwrap();
+//!
+//! let mut other_world = World::new();
+//! let loaded = scene::load_ron(&mut other_world, &registry, &ron).unwrap();
+//! assert_eq!(*other_world.get_component::<Health>(loaded[1]).unwrap(), Health(5));
+//! ```
+//!
+//! [`ComponentRegistry`]: ../registry/struct.ComponentRegistry.html
+//! [`SceneSerializer`]: ./struct.SceneSerializer.html
+//! [`SceneDeserializer`]: ./struct.SceneDeserializer.html
+//! [`save_ron`]: ./fn.save_ron.html
+//! [`load_ron`]: ./fn.load_ron.html
+//! [`save_json`]: ./fn.save_json.html
+//! [`load_json`]: ./fn.load_json.html
+
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use crate::entity_serde::EntityMapper;
+use crate::registry::{BoxedComponent, ComponentRegistration, ComponentRegistry};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+const SCENE_FIELDS: &[&str] = &["entities"];
+const ENTITY_FIELDS: &[&str] = &["id", "components"];
+
+/// Save the given entities to a RON string
+pub fn save_ron(
+    world: &World,
+    registry: &ComponentRegistry,
+    entities: &[Entity],
+) -> Result<String, ron::ser::Error> {
+    let config = ron::ser::PrettyConfig::default();
+    ron::ser::to_string_pretty(&SceneSerializer::new(world, registry, entities), config)
+}
+
+/// Load the entities from the given RON string into the world
+///
+/// This returns the spawned entities in the order they are listed in the scene.
+pub fn load_ron(
+    world: &mut World,
+    registry: &ComponentRegistry,
+    ron: &str,
+) -> Result<Vec<Entity>, ron::de::Error> {
+    let mut deserializer = ron::de::Deserializer::from_str(ron)?;
+    let entities = SceneDeserializer::new(world, registry).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(entities)
+}
+
+/// Save the given entities to a JSON string
+pub fn save_json(
+    world: &World,
+    registry: &ComponentRegistry,
+    entities: &[Entity],
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&SceneSerializer::new(world, registry, entities))
+}
+
+/// Load the entities from the given JSON string into the world
+///
+/// This returns the spawned entities in the order they are listed in the scene.
+pub fn load_json(
+    world: &mut World,
+    registry: &ComponentRegistry,
+    json: &str,
+) -> Result<Vec<Entity>, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let entities = SceneDeserializer::new(world, registry).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(entities)
+}
+
+/// Serializable view of a scene made of the given entities
+///
+/// The entities are saved with their indices in the given slice as ids, and every registered
+/// component that is not derived is saved. Serializing fails if a component refers to an entity
+/// that is not in the slice.
+#[derive(Clone, Copy)]
+pub struct SceneSerializer<'a> {
+    world: &'a World,
+    registry: &'a ComponentRegistry,
+    entities: &'a [Entity],
+}
+
+impl<'a> SceneSerializer<'a> {
+    /// Create a new instance that saves the given entities
+    pub fn new(world: &'a World, registry: &'a ComponentRegistry, entities: &'a [Entity]) -> Self {
+        Self {
+            world,
+            registry,
+            entities,
+        }
+    }
+}
+
+impl fmt::Debug for SceneSerializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SceneSerializer")
+            .field("registry", self.registry)
+            .field("entities", &self.entities)
+            .finish()
+    }
+}
+
+impl Serialize for SceneSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mapper = SaveMapper(
+            self.entities
+                .iter()
+                .enumerate()
+                .map(|(id, entity)| (*entity, id as u64))
+                .collect(),
+        );
+
+        let mut state = serializer.serialize_struct("Scene", SCENE_FIELDS.len())?;
+        state.serialize_field(
+            "entities",
+            &EntitiesSer {
+                scene: self,
+                mapper: &mapper,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct SaveMapper(HashMap<Entity, u64>);
+
+impl EntityMapper for SaveMapper {
+    fn entity_to_id(&self, entity: Entity) -> Option<u64> {
+        self.0.get(&entity).copied()
+    }
+
+    fn id_to_entity(&mut self, _id: u64) -> Option<Entity> {
+        None
+    }
+}
+
+struct EntitiesSer<'a> {
+    scene: &'a SceneSerializer<'a>,
+    mapper: &'a SaveMapper,
+}
+
+impl Serialize for EntitiesSer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let scene = self.scene;
+        let mut seq = serializer.serialize_seq(Some(scene.entities.len()))?;
+        for (id, entity) in scene.entities.iter().enumerate() {
+            if !scene.world.is_alive(*entity) {
+                return Err(ser::Error::custom(format_args!(
+                    "entity {} is not alive",
+                    entity
+                )));
+            }
+            seq.serialize_element(&EntitySer {
+                scene,
+                mapper: self.mapper,
+                id: id as u64,
+                entity: *entity,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct EntitySer<'a> {
+    scene: &'a SceneSerializer<'a>,
+    mapper: &'a SaveMapper,
+    id: u64,
+    entity: Entity,
+}
+
+impl Serialize for EntitySer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Entity", ENTITY_FIELDS.len())?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("components", &ComponentsSer(self))?;
+        state.end()
+    }
+}
+
+struct ComponentsSer<'a>(&'a EntitySer<'a>);
+
+impl Serialize for ComponentsSer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let SceneSerializer {
+            world, registry, ..
+        } = *self.0.scene;
+        let entity = self.0.entity;
+
+        let mut map = serializer.serialize_map(None)?;
+        for registration in registry.iter().filter(|r| !r.is_derived()) {
+            if let Some(component) = registration.serialize_mapped(world, entity, self.0.mapper) {
+                map.serialize_entry(registration.name(), &component)?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Seed that loads a scene into the world
+///
+/// Every entity in the scene is spawned as a new entity, and references to the scene ids inside
+/// components are remapped to the spawned entities. Deserializing returns the spawned entities in
+/// the order they are listed in the scene. If deserializing fails, nothing is left in the world.
+pub struct SceneDeserializer<'a> {
+    world: &'a mut World,
+    registry: &'a ComponentRegistry,
+}
+
+impl<'a> SceneDeserializer<'a> {
+    /// Create a new instance that loads into the given world
+    pub fn new(world: &'a mut World, registry: &'a ComponentRegistry) -> Self {
+        Self { world, registry }
+    }
+}
+
+impl fmt::Debug for SceneDeserializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SceneDeserializer")
+            .field("registry", self.registry)
+            .finish()
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for SceneDeserializer<'_> {
+    type Value = Vec<Entity>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let registry = self.registry;
+        let mut mapper = LoadMapper {
+            world: self.world,
+            ids: HashMap::new(),
+        };
+
+        // Entities are spawned when they are first referred to, which may be before they are listed
+        let result = deserializer.deserialize_struct(
+            "Scene",
+            SCENE_FIELDS,
+            SceneVisitor {
+                registry,
+                mapper: &mut mapper,
+            },
+        );
+
+        let result = result.and_then(|entities| mapper.spawn(entities).map_err(de::Error::custom));
+        if result.is_err() {
+            for entity in mapper.ids.values() {
+                mapper.world.delete(*entity);
+            }
+        }
+        result
+    }
+}
+
+type EntityData<'r> = (u64, Vec<(&'r ComponentRegistration, BoxedComponent)>);
+
+struct LoadMapper<'w> {
+    world: &'w mut World,
+    ids: HashMap<u64, Entity>,
+}
+
+impl LoadMapper<'_> {
+    fn entity(&mut self, id: u64) -> Entity {
+        let world = &mut *self.world;
+        *self
+            .ids
+            .entry(id)
+            .or_insert_with(|| world.insert((), vec![()])[0])
+    }
+
+    fn spawn(&mut self, entities: Vec<EntityData<'_>>) -> Result<Vec<Entity>, String> {
+        let mut spawned = Vec::with_capacity(entities.len());
+        for (id, components) in entities {
+            let entity = self.entity(id);
+            if spawned.contains(&entity) {
+                return Err(format!("duplicate entity id {}", id));
+            }
+
+            for (registration, component) in components {
+                registration
+                    .add(self.world, entity, component)
+                    .map_err(|e| e.to_string())?;
+            }
+            spawned.push(entity);
+        }
+
+        if let Some(id) = self.ids.iter().find_map(|(id, entity)| {
+            if spawned.contains(entity) {
+                None
+            } else {
+                Some(*id)
+            }
+        }) {
+            return Err(format!("entity id {} is referred to but not listed", id));
+        }
+
+        Ok(spawned)
+    }
+}
+
+impl EntityMapper for LoadMapper<'_> {
+    fn entity_to_id(&self, _entity: Entity) -> Option<u64> {
+        None
+    }
+
+    fn id_to_entity(&mut self, id: u64) -> Option<Entity> {
+        Some(self.entity(id))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneField {
+    Entities,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum EntityField {
+    Id,
+    Components,
+}
+
+struct SceneVisitor<'r, 'm> {
+    registry: &'r ComponentRegistry,
+    mapper: &'m mut dyn EntityMapper,
+}
+
+impl<'de, 'r> Visitor<'de> for SceneVisitor<'r, '_> {
+    type Value = Vec<EntityData<'r>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("struct Scene")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        seq.next_element_seed(EntitiesSeed(self.registry, &mut *self.mapper))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entities = None;
+        while let Some(SceneField::Entities) = map.next_key()? {
+            if entities.is_some() {
+                return Err(de::Error::duplicate_field("entities"));
+            }
+            entities = Some(map.next_value_seed(EntitiesSeed(self.registry, &mut *self.mapper))?);
+        }
+        entities.ok_or_else(|| de::Error::missing_field("entities"))
+    }
+}
+
+struct EntitiesSeed<'r, 'm>(&'r ComponentRegistry, &'m mut dyn EntityMapper);
+
+impl<'de, 'r> DeserializeSeed<'de> for EntitiesSeed<'r, '_> {
+    type Value = Vec<EntityData<'r>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'r> Visitor<'de> for EntitiesSeed<'r, '_> {
+    type Value = Vec<EntityData<'r>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a list of entities")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut entities = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(entity) = seq.next_element_seed(EntitySeed(self.0, &mut *self.1))? {
+            entities.push(entity);
+        }
+        Ok(entities)
+    }
+}
+
+struct EntitySeed<'r, 'm>(&'r ComponentRegistry, &'m mut dyn EntityMapper);
+
+impl<'de, 'r> DeserializeSeed<'de> for EntitySeed<'r, '_> {
+    type Value = EntityData<'r>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("Entity", ENTITY_FIELDS, self)
+    }
+}
+
+impl<'de, 'r> Visitor<'de> for EntitySeed<'r, '_> {
+    type Value = EntityData<'r>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("struct Entity")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let id = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let components = seq
+            .next_element_seed(ComponentsSeed(self.0, &mut *self.1))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((id, components))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut id = None;
+        let mut components = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                EntityField::Id => {
+                    if id.is_some() {
+                        return Err(de::Error::duplicate_field("id"));
+                    }
+                    id = Some(map.next_value()?);
+                }
+                EntityField::Components => {
+                    if components.is_some() {
+                        return Err(de::Error::duplicate_field("components"));
+                    }
+                    components = Some(map.next_value_seed(ComponentsSeed(self.0, &mut *self.1))?);
+                }
+            }
+        }
+
+        let id = id.ok_or_else(|| de::Error::missing_field("id"))?;
+        let components = components.ok_or_else(|| de::Error::missing_field("components"))?;
+        Ok((id, components))
+    }
+}
+
+struct ComponentsSeed<'r, 'm>(&'r ComponentRegistry, &'m mut dyn EntityMapper);
+
+impl<'de, 'r> DeserializeSeed<'de> for ComponentsSeed<'r, '_> {
+    type Value = Vec<(&'r ComponentRegistration, BoxedComponent)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'r> Visitor<'de> for ComponentsSeed<'r, '_> {
+    type Value = Vec<(&'r ComponentRegistration, BoxedComponent)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map of component names to components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(name) = map.next_key::<String>()? {
+            let registration = self
+                .0
+                .get(&name)
+                .ok_or_else(|| de::Error::custom(format_args!("unknown component `{}`", name)))?;
+            let component = map.next_value_seed(ComponentSeed(registration, &mut *self.1))?;
+            components.push((registration, component));
+        }
+        Ok(components)
+    }
+}
+
+struct ComponentSeed<'r, 'm>(&'r ComponentRegistration, &'m mut dyn EntityMapper);
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed<'_, '_> {
+    type Value = BoxedComponent;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize_mapped(deserializer, self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_json, load_ron, save_json, save_ron};
+    use crate::ecs::entity::Entity;
+    use crate::ecs::query::{IntoQuery, TryRead};
+    use crate::ecs::world::World;
+    use crate::entity_serde::{EntityMapper, MapEntities};
+    use crate::registry::ComponentRegistry;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Name(String);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Owner(Entity);
+
+    impl MapEntities for Owner {
+        fn serialize_mapped<S: Serializer>(
+            &self,
+            mapper: &dyn EntityMapper,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            self.0.serialize_mapped(mapper, serializer)
+        }
+
+        fn deserialize_mapped<'de, D: Deserializer<'de>>(
+            mapper: &mut dyn EntityMapper,
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            Entity::deserialize_mapped(mapper, deserializer).map(Owner)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Cache(u32);
+
+    fn registry() -> ComponentRegistry {
+        let mut registry = ComponentRegistry::new().with::<Name>("test::Name");
+        registry
+            .register_mapped::<Owner>("test::Owner")
+            .register_derived::<Cache>("test::Cache");
+        registry
+    }
+
+    fn name_of(world: &World, entity: Entity) -> String {
+        world.get_component::<Name>(entity).unwrap().0.clone()
+    }
+
+    #[test]
+    fn entity_references_are_remapped() {
+        let registry = registry();
+        let mut world = World::new();
+        let sword = world.insert((), vec![(Name("sword".into()), Cache(1))])[0];
+        let knight = world.insert((), vec![(Name("knight".into()),)])[0];
+        world.add_component(sword, Owner(knight));
+
+        let ron = save_ron(&world, &registry, &[sword, knight]).unwrap();
+
+        // Spawn something first so that the new entities do not reuse the old handles
+        let mut other = World::new();
+        other.insert((), vec![(Cache(0),), (Cache(0),), (Cache(0),)]);
+        let loaded = load_ron(&mut other, &registry, &ron).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(name_of(&other, loaded[0]), "sword");
+        assert_eq!(name_of(&other, loaded[1]), "knight");
+        assert_eq!(
+            *other.get_component::<Owner>(loaded[0]).unwrap(),
+            Owner(loaded[1])
+        );
+        assert!(other.get_component::<Cache>(loaded[0]).is_none());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.insert((), vec![(Name("tree".into()),)])[0];
+
+        let json = save_json(&world, &registry, &[entity]).unwrap();
+        let loaded = load_json(&mut world, &registry, &json).unwrap();
+        assert_ne!(loaded[0], entity);
+        assert_eq!(name_of(&world, loaded[0]), "tree");
+    }
+
+    #[test]
+    fn reference_outside_of_scene_fails_to_save() {
+        let registry = registry();
+        let mut world = World::new();
+        let entities = world.insert((), vec![(Name("a".into()),), (Name("b".into()),)]);
+        let (a, b) = (entities[0], entities[1]);
+        world.add_component(a, Owner(b));
+
+        assert!(save_ron(&world, &registry, &[a]).is_err());
+    }
+
+    #[test]
+    fn failed_load_leaves_nothing() {
+        let registry = registry();
+        let mut world = World::new();
+        let query = <TryRead<Owner>>::query();
+
+        // Empty entities are also counted by the query
+        let empty = world.insert((), vec![()])[0];
+        assert_eq!(query.iter_entities(&mut world).count(), 1);
+        world.delete(empty);
+
+        let unknown = r#"(entities: [(id: 0, components: {"test::Unknown": 1})])"#;
+        assert!(load_ron(&mut world, &registry, unknown).is_err());
+
+        let dangling = r#"(entities: [(id: 0, components: {"test::Owner": 7})])"#;
+        assert!(load_ron(&mut world, &registry, dangling).is_err());
+
+        let duplicate = r#"(entities: [(id: 0, components: {}), (id: 0, components: {})])"#;
+        assert!(load_ron(&mut world, &registry, duplicate).is_err());
+
+        assert_eq!(query.iter_entities(&mut world).count(), 0);
+    }
+}
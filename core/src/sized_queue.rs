@@ -54,6 +54,16 @@ impl<T> SizedQueue<T> {
         self.size
     }
 
+    /// Change the size of this queue, dropping the oldest items that do not fit anymore
+    ///
+    /// This returns how many items are dropped.
+    pub fn resize(&mut self, size: usize) -> usize {
+        let dropped = self.queue.len().saturating_sub(size);
+        self.queue.drain(..dropped);
+        self.size = size;
+        dropped
+    }
+
     /// Get how many items are actually stored in the queue
     pub fn len(&self) -> usize {
         self.queue.len()
@@ -5,7 +5,7 @@ mod order;
 pub mod types;
 
 pub use self::order::{SystemOrder, SystemPriority};
-pub use crate::derive::SystemDesc;
+pub use crate::derive::{system, SystemBundle, SystemDesc};
 
 use self::entry::*;
 use self::types::{Parallel, ThreadLocal, ThreadLocalFn};
@@ -20,7 +20,7 @@ mod seal {
 }
 
 /// Trait for types that represent types of systems: either thread-local or non-thread-local
-pub trait SystemType: self::seal::Seal {
+pub trait SystemType: seal::Seal {
     /// The real type of the built system: `Box<dyn Runnable>`, `Box<dyn Schedulable>`, or
     /// `Box<dyn FnMut(&mut World)>`
     ///
@@ -54,7 +54,7 @@ pub trait SystemBundle {
 /// # Lifetime Parameters
 ///
 /// - `a`: The lifetime of the closures, system descriptors, and system bundles passed into this
-///    builder
+///   builder
 ///
 /// [`Schedule`]: ../../legion/schedule/struct.Schedule.html
 #[derive(Default)]
@@ -245,6 +245,7 @@ impl<'a> ScheduleBuilder<'a> {
 impl<'a> fmt::Debug for ScheduleBuilder<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         #[derive(Debug)]
+        #[allow(dead_code)]
         struct Vec {
             len: usize,
         }
@@ -35,11 +35,7 @@ impl Timer {
 
     /// Is this timer running?
     pub fn is_running(&self) -> bool {
-        if let Timer::Running { .. } = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Timer::Running { .. })
     }
 
     /// Get the measured value
@@ -101,6 +97,7 @@ pub struct Time {
     real_fixed_delta_duration: Duration,
     total_duration: Duration,
     real_total_duration: Duration,
+    fixed_step_accumulator: Duration,
     scale: f32,
 }
 
@@ -117,6 +114,7 @@ impl Default for Time {
             real_fixed_delta_duration: Duration::default(),
             total_duration: Duration::default(),
             real_total_duration: Duration::default(),
+            fixed_step_accumulator: Duration::default(),
             scale: 1.0,
         }
     }
@@ -188,6 +186,37 @@ impl Time {
         self.real_total_duration
     }
 
+    /// Get how far the current frame is between the last fixed step and the next one
+    ///
+    /// This is the scaled time accumulated by [`set_delta_duration`] and not yet consumed by
+    /// [`next_fixed_step`], divided by the fixed delta time, in the range of `[0, 1]`. It is `1.0`
+    /// when the fixed delta time is zero.
+    ///
+    /// [`set_delta_duration`]: #method.set_delta_duration
+    /// [`next_fixed_step`]: #method.next_fixed_step
+    pub fn fixed_step_fraction(&self) -> f32 {
+        if self.fixed_delta_duration == Duration::default() {
+            return 1.0;
+        }
+        let fraction = duration_to_f32(self.fixed_step_accumulator)
+            / duration_to_f32(self.fixed_delta_duration);
+        fraction.min(1.0)
+    }
+
+    /// Consume one fixed step from the accumulated time, if enough time has been accumulated
+    ///
+    /// The loop that runs the fixed steps calls this until it returns `false`, running one step each
+    /// time it returns `true`. This always returns `false` when the fixed delta time is zero.
+    pub fn next_fixed_step(&mut self) -> bool {
+        if self.fixed_delta_duration == Duration::default()
+            || self.fixed_step_accumulator < self.fixed_delta_duration
+        {
+            return false;
+        }
+        self.fixed_step_accumulator -= self.fixed_delta_duration;
+        true
+    }
+
     /// Set the delta time
     pub fn set_delta_duration(&mut self, duration: Duration) {
         self.real_delta_time = duration_to_f32(duration);
@@ -196,6 +225,7 @@ impl Time {
         self.delta_duration = duration_from_seconds(self.delta_time);
         self.real_total_duration += self.real_delta_duration;
         self.total_duration += self.delta_duration;
+        self.fixed_step_accumulator += self.delta_duration;
     }
 
     /// Set the fixed delta time
@@ -209,19 +239,19 @@ impl Time {
     /// Set the time scale factor
     pub fn set_scale(&mut self, new_scale: f32) {
         assert!(new_scale >= 0.0);
-        assert!(new_scale < std::f32::INFINITY);
+        assert!(new_scale < f32::INFINITY);
         self.scale = new_scale;
     }
 }
 
 /// Convert the given [`Duration`] to seconds value in [`f32`] format
 pub fn duration_to_f32(duration: Duration) -> f32 {
-    duration.as_secs_f32() + duration.subsec_nanos() as f32 / 1.0e9
+    duration.as_secs_f32()
 }
 
 /// Convert the given [`Duration`] to seconds value in [`f64`] format
 pub fn duration_to_f64(duration: Duration) -> f64 {
-    duration.as_secs_f64() + duration.subsec_nanos() as f64 / 1.0e9
+    duration.as_secs_f64()
 }
 
 /// Convert the given [`Duration`] to nanoseconds value in [`u64`] format
@@ -237,3 +267,32 @@ pub fn duration_to_nanoseconds(duration: Duration) -> u64 {
 pub fn duration_from_seconds(seconds: f32) -> Duration {
     Duration::new(seconds as u64, ((seconds % 1.0) * 1.0e9) as u32)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{duration_to_f32, duration_to_f64, Time};
+    use std::time::Duration;
+
+    #[test]
+    fn duration_conversions() {
+        let duration = Duration::new(2, 500_000_000);
+        assert_eq!(duration_to_f32(duration), 2.5);
+        assert_eq!(duration_to_f64(duration), 2.5);
+        assert_eq!(duration_to_f64(Duration::from_millis(250)), 0.25);
+    }
+
+    #[test]
+    fn fixed_steps() {
+        let mut time = Time::new();
+        time.set_delta_duration(Duration::from_millis(30));
+        assert!(!time.next_fixed_step());
+        assert_eq!(time.fixed_step_fraction(), 1.0);
+
+        time.set_fixed_delta_duration(Duration::from_millis(20));
+        time.set_delta_duration(Duration::from_millis(20));
+        assert!(time.next_fixed_step());
+        assert!(time.next_fixed_step());
+        assert!(!time.next_fixed_step());
+        assert!((time.fixed_step_fraction() - 0.5).abs() < 1.0e-6);
+    }
+}
@@ -1,9 +1,202 @@
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
+mod system_bundle;
 mod system_desc;
+mod system_fn;
 
+/// Derive `SystemDesc` for a struct that wraps a build function
+///
+/// The `system_desc` attribute takes the following values:
+///
+/// - `type(...)`: The system type; one of `types::Parallel`, `types::ThreadLocal`, or
+///   `types::ThreadLocalFn`
+/// - `fn(...)`: The build function, called with the world as the first argument
+/// - `args(...)`: *(optional)* Either `self` to pass the descriptor itself, or a list of field
+///   names or indices to pass after the world
+/// - `bound = "..."`: *(optional)* Additional where clause predicates for the generated impl
+///
+/// `SystemDesc`, `SystemType`, and `World` must be in scope where this is derived.
+///
+/// # Examples
+///
+/// ```rust
+/// use game_engine::core::systems::{types, SystemDesc, SystemType};
+/// use game_engine::ecs::prelude::*;
+///
+/// struct Speed(f32);
+///
+/// #[derive(SystemDesc)]
+/// #[system_desc(type(types::Parallel), fn(build_move_system), args(speed))]
+/// struct MoveSystem {
+///     speed: f32,
+/// }
+///
+/// fn build_move_system(world: &mut World, speed: f32) -> Box<dyn Schedulable> {
+///     world.resources.insert(Speed(speed));
+///     SystemBuilder::new("Move").build(|_, _, _, _| {})
+/// }
+///
+/// let mut world = World::new();
+/// let _system = MoveSystem { speed: 3.0 }.build(&mut world);
+/// assert_eq!(world.resources.get::<Speed>().unwrap().0, 3.0);
+/// ```
+///
+/// Fields that do not exist are reported at the given field name.
+///
+/// ```rust,compile_fail
+/// # use game_engine::core::systems::{types, SystemDesc, SystemType};
+/// # use game_engine::ecs::prelude::*;
+/// #
+/// #[derive(SystemDesc)]
+/// #[system_desc(type(types::Parallel), fn(build_move_system), args(velocity))]
+/// struct MoveSystem {
+///     speed: f32,
+/// }
+/// #
+/// # fn build_move_system(_: &mut World, _: f32) -> Box<dyn Schedulable> {
+/// #     SystemBuilder::new("Move").build(|_, _, _, _| {})
+/// # }
+/// ```
+///
+/// Each value can only be given once, even within a single attribute.
+///
+/// ```rust,compile_fail
+/// # use game_engine::core::systems::{types, SystemDesc, SystemType};
+/// # use game_engine::ecs::prelude::*;
+/// #
+/// #[derive(SystemDesc)]
+/// #[system_desc(type(types::Parallel), fn(build_move_system), args(speed), args())]
+/// struct MoveSystem {
+///     speed: f32,
+/// }
+/// #
+/// # fn build_move_system(_: &mut World, _: f32) -> Box<dyn Schedulable> {
+/// #     SystemBuilder::new("Move").build(|_, _, _, _| {})
+/// # }
+/// ```
 #[proc_macro_derive(SystemDesc, attributes(system_desc))]
 pub fn derive_system_desc(input: TokenStream) -> TokenStream {
     system_desc::derive(parse_macro_input!(input as DeriveInput)).into()
 }
+
+/// Derive `SystemBundle` for a struct whose fields are system descriptors or bundles
+///
+/// Each field must have a `system_bundle` attribute with one of the following values:
+///
+/// - `order = <expr>`: The field is a system descriptor added at the given `SystemOrder`
+/// - `bundle`: The field is a system bundle
+/// - `skip`: The field is ignored
+///
+/// `SystemBundle`, `SystemDesc`, `ScheduleBuilder`, and `World` must be in scope where this is
+/// derived.
+///
+/// # Examples
+///
+/// ```rust
+/// use game_engine::core::frame_rate::FpsCountSystem;
+/// use game_engine::core::systems::*;
+/// use game_engine::ecs::prelude::*;
+///
+/// #[derive(SystemBundle)]
+/// struct DiagnosticsBundle {
+///     #[system_bundle(order = SystemOrder::first())]
+///     fps_count: FpsCountSystem,
+///     #[system_bundle(skip)]
+///     _verbose: bool,
+/// }
+///
+/// let mut world = World::new();
+/// let _schedule = ScheduleBuilder::new()
+///     .with_system_bundle(DiagnosticsBundle {
+///         fps_count: FpsCountSystem,
+///         _verbose: false,
+///     })
+///     .build(&mut world);
+/// ```
+#[proc_macro_derive(SystemBundle, attributes(system_bundle))]
+pub fn derive_system_bundle(input: TokenStream) -> TokenStream {
+    system_bundle::derive(parse_macro_input!(input as DeriveInput)).into()
+}
+
+/// Turn a plain function into a system
+///
+/// This generates a function named `build_<name>_system` which builds the system, and a
+/// `SystemDesc` struct named `<Name>System` which wraps it. The system itself is named `<Name>`.
+///
+/// The parameters of the function decide what the system accesses:
+///
+/// - `&T`: Read access to the resource `T`
+/// - `&mut T`: Write access to the resource `T`
+/// - `&mut CommandBuffer`: The command buffer of the system
+/// - `&mut SubWorld`: The world the system runs on
+/// - `&mut Query<V>`: A query of the view `V`; a `#[filter(...)]` attribute adds a filter to it
+/// - `&mut EventReader<T>`: A reader of `Events<T>` owned by the system, registered when the
+///   system is built
+///
+/// `Events<T>` resources taken as parameters are inserted into the world when the system is built
+/// if they do not exist.
+///
+/// The attribute takes the following optional values:
+///
+/// - `thread_local`: Build a thread-local system
+/// - `name = "..."`: Override the name of the system
+/// - `read_component(...)`, `write_component(...)`: Declare access to components outside queries
+///
+/// `SystemBuilder`, `IntoQuery`, `Schedulable` (or `Runnable`), `World`, `types`, `SystemDesc`,
+/// and `SystemType` must be in scope where this is used.
+///
+/// # Examples
+///
+/// ```rust
+/// use game_engine::core::events::{EventReader, Events};
+/// use game_engine::core::frame_rate::FpsValue;
+/// use game_engine::core::systems::{system, types, SystemDesc, SystemType};
+/// use game_engine::core::time::Time;
+/// use game_engine::ecs::prelude::*;
+/// use game_engine::ecs::system::SubWorld;
+///
+/// struct Velocity(f32);
+/// struct Distance(f32);
+///
+/// #[system]
+/// fn movement(
+///     time: &Time,
+///     world: &mut SubWorld,
+///     #[filter(changed::<Velocity>())] query: &mut Query<(Read<Velocity>, Write<Distance>)>,
+/// ) {
+///     for (velocity, mut distance) in query.iter(world) {
+///         distance.0 += velocity.0 * time.delta_time();
+///     }
+/// }
+///
+/// #[system(write_component(Distance))]
+/// fn log_fps(fps_value: &FpsValue) {
+///     let _ = fps_value.average_fps();
+/// }
+///
+/// struct Collision(u32);
+///
+/// #[system]
+/// fn log_collisions(events: &Events<Collision>, reader: &mut EventReader<Collision>) {
+///     for collision in events.read(reader) {
+///         let _ = collision.0;
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// world.resources.insert(Time::default());
+/// world.resources.insert(FpsValue::default());
+///
+/// let _movement = MovementSystem.build(&mut world);
+/// let _log_fps = build_log_fps_system(&mut world);
+///
+/// // The event channel is created when the system is built
+/// let _log_collisions = LogCollisionsSystem.build(&mut world);
+/// assert!(world.resources.contains::<Events<Collision>>());
+/// ```
+#[proc_macro_attribute]
+pub fn system(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as system_fn::SystemOptions);
+    system_fn::expand(options, parse_macro_input!(item as ItemFn)).into()
+}
@@ -0,0 +1,133 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Expr, Field, Fields, Ident, Index, Member, Token};
+
+pub fn derive(input: DeriveInput) -> TokenStream {
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+        },
+        _ => {
+            return Error::new(
+                name.span(),
+                "`SystemBundle` can only be derived for structs",
+            )
+            .to_compile_error();
+        }
+    };
+
+    let mut bounds = Vec::with_capacity(fields.len());
+    let mut statements = Vec::with_capacity(fields.len());
+
+    for (index, field) in fields.into_iter().enumerate() {
+        let kind = match parse_field_kind(field) {
+            Ok(kind) => kind,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index {
+                index: index as u32,
+                span: field.span(),
+            }),
+        };
+        let ty = &field.ty;
+
+        match kind {
+            FieldKind::Desc(order) => {
+                bounds.push(quote_spanned!(ty.span()=> #ty: SystemDesc + 'static));
+                statements.push(quote_spanned! {field.span()=>
+                    builder.add_system_create_desc(#order, self.#member);
+                });
+            }
+            FieldKind::Bundle => {
+                bounds.push(quote_spanned!(ty.span()=> #ty: SystemBundle + 'static));
+                statements.push(quote_spanned! {field.span()=>
+                    builder.add_system_bundle(self.#member);
+                });
+            }
+            FieldKind::Skip => {}
+        }
+    }
+
+    let (impl_gen, type_gen, where_clause) = input.generics.split_for_impl();
+    let where_clause = match where_clause {
+        Some(where_clause) if !where_clause.predicates.is_empty() => {
+            let predicates = where_clause.predicates.iter();
+            quote!(where #( #predicates, )* #( #bounds, )*)
+        }
+        _ => quote!(where #( #bounds, )*),
+    };
+
+    quote! {
+        impl #impl_gen SystemBundle for #name #type_gen #where_clause {
+            #[allow(unused_variables)]
+            fn build_systems(self, world: &mut World, builder: &mut ScheduleBuilder<'_>) {
+                #( #statements )*
+            }
+        }
+    }
+}
+
+/// How a field of the bundle should be added to the schedule builder
+enum FieldKind {
+    /// The field is a system descriptor added at the given order
+    Desc(Box<Expr>),
+    /// The field is a system bundle
+    Bundle,
+    /// The field is not a part of the schedule, e.g. a configuration value
+    Skip,
+}
+
+impl Parse for FieldKind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+
+        if ident == "order" {
+            input.parse::<Token![=]>()?;
+            let order = input.parse::<Expr>()?;
+            Ok(FieldKind::Desc(Box::new(order)))
+        } else if ident == "bundle" {
+            Ok(FieldKind::Bundle)
+        } else if ident == "skip" {
+            Ok(FieldKind::Skip)
+        } else {
+            Err(Error::new(
+                ident.span(),
+                "Either `order = <expr>`, `bundle`, or `skip` should be specified here",
+            ))
+        }
+    }
+}
+
+fn parse_field_kind(field: &Field) -> syn::Result<FieldKind> {
+    let mut kind = None;
+
+    for attr in &field.attrs {
+        if !attr.path.is_ident("system_bundle") {
+            continue;
+        }
+
+        if kind.replace(attr.parse_args::<FieldKind>()?).is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "The field kind cannot be specified multiple times",
+            ));
+        }
+    }
+
+    kind.ok_or_else(|| {
+        Error::new(
+            field.span(),
+            "Every field of `SystemBundle` must be specified with `system_bundle` attribute: \
+             `order = <expr>` for system descriptors, `bundle` for system bundles, or `skip`",
+        )
+    })
+}
@@ -1,15 +1,21 @@
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Attribute, DeriveInput, Error, Meta, NestedMeta, Path};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Index, Lit, Member, Meta, NestedMeta, Path, Token,
+    WherePredicate,
+};
 
 pub fn derive(input: DeriveInput) -> TokenStream {
-    let name = input.ident;
-    let (impl_gen, type_gen, where_clause) = input.generics.split_for_impl();
+    let name = &input.ident;
 
     let mut sys_ty = None;
     let mut build_fn = None;
+    let mut args = None;
+    let mut bounds = Vec::new();
 
-    for attr in input.attrs {
+    for attr in &input.attrs {
         if !attr.path.is_ident("system_desc") {
             continue;
         }
@@ -21,7 +27,7 @@ pub fn derive(input: DeriveInput) -> TokenStream {
             }
         };
 
-        if let Some(ty) = parsed.0 {
+        if let Some(ty) = parsed.sys_ty {
             if let Some(old_ty) = sys_ty.replace(ty) {
                 return Error::new_spanned(
                     old_ty,
@@ -31,7 +37,7 @@ pub fn derive(input: DeriveInput) -> TokenStream {
             }
         }
 
-        if let Some(ty) = parsed.1 {
+        if let Some(ty) = parsed.build_fn {
             if let Some(old_ty) = build_fn.replace(ty) {
                 return Error::new_spanned(
                     old_ty,
@@ -40,6 +46,18 @@ pub fn derive(input: DeriveInput) -> TokenStream {
                 .to_compile_error();
             }
         }
+
+        if let Some((span, new_args)) = parsed.args {
+            if args.replace(new_args).is_some() {
+                return Error::new(
+                    span,
+                    "Build function arguments cannot be specified multiple times",
+                )
+                .to_compile_error();
+            }
+        }
+
+        bounds.extend(parsed.bounds);
     }
 
     let sys_ty = match sys_ty {
@@ -64,32 +82,79 @@ pub fn derive(input: DeriveInput) -> TokenStream {
         Some(x) => x,
     };
 
+    let args = match args.unwrap_or_default() {
+        BuildArgs::Fields(fields) => match validate_fields(&input, &fields) {
+            Ok(()) => {
+                let args = fields
+                    .iter()
+                    .map(|field| quote_spanned!(field.span()=> self.#field));
+                quote!(#( , #args )*)
+            }
+            Err(err) => return err.to_compile_error(),
+        },
+        BuildArgs::SelfValue(span) => quote_spanned!(span=> , self),
+    };
+
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_gen, type_gen, where_clause) = generics.split_for_impl();
+
     quote! {
         impl #impl_gen SystemDesc for #name #type_gen #where_clause {
             type SystemType = #sys_ty;
 
             fn build(self, world: &mut World) -> <Self::SystemType as SystemType>::System {
-                #build_fn(world)
+                #build_fn(world #args)
             }
         }
     }
 }
 
-fn parse_attribute(attr: Attribute) -> syn::Result<(Option<Path>, Option<Path>)> {
-    let mut sys_ty = None;
-    let mut build_fn = None;
+/// Values that are passed to the build function after the world
+enum BuildArgs {
+    /// Pass the selected fields of the descriptor
+    Fields(Vec<Member>),
+    /// Pass the descriptor itself
+    SelfValue(Span),
+}
+
+impl Default for BuildArgs {
+    fn default() -> Self {
+        BuildArgs::Fields(Vec::new())
+    }
+}
+
+#[derive(Default)]
+struct ParsedAttribute {
+    sys_ty: Option<Path>,
+    build_fn: Option<Path>,
+    args: Option<(Span, BuildArgs)>,
+    bounds: Vec<WherePredicate>,
+}
+
+fn parse_attribute(attr: &Attribute) -> syn::Result<ParsedAttribute> {
+    let mut parsed = ParsedAttribute::default();
 
     let list = if let Meta::List(list) = attr.parse_meta()? {
         list.nested
     } else {
         return Err(Error::new_spanned(
-            attr.tokens,
-            "The attribute must contain a list of `type` and `fn`",
+            &attr.tokens,
+            "The attribute must contain a list of `type`, `fn`, `args`, and `bound`",
         ));
     };
 
     for nested in list {
         match nested {
+            NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("args") => {
+                let span = l.path.span();
+                if parsed.args.replace((span, parse_args(l.nested)?)).is_some() {
+                    return Err(Error::new(
+                        span,
+                        "Build function arguments cannot be specified multiple times",
+                    ));
+                }
+            }
             NestedMeta::Meta(Meta::List(l)) => {
                 if l.path.is_ident("type") || l.path.is_ident("fn") {
                     if l.nested.len() != 1 {
@@ -108,25 +173,127 @@ fn parse_attribute(attr: Attribute) -> syn::Result<(Option<Path>, Option<Path>)>
                         }
                     };
                     if l.path.is_ident("type") {
-                        sys_ty.replace(path.clone());
-                    } else {
-                        build_fn.replace(path.clone());
+                        if parsed.sys_ty.replace(path.clone()).is_some() {
+                            return Err(Error::new_spanned(
+                                l.path,
+                                "System type cannot be specified multiple times",
+                            ));
+                        }
+                    } else if parsed.build_fn.replace(path.clone()).is_some() {
+                        return Err(Error::new_spanned(
+                            l.path,
+                            "Build function cannot be specified multiple times",
+                        ));
                     }
                 } else {
                     return Err(Error::new_spanned(
                         l,
-                        "Either `type` or `fn` should be specified here",
+                        "Either `type`, `fn`, `args`, or `bound` should be specified here",
                     ));
                 }
             }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                let bounds = match &nv.lit {
+                    Lit::Str(s) => {
+                        s.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?
+                    }
+                    lit => {
+                        return Err(Error::new_spanned(
+                            lit,
+                            "The bounds should be given as a string literal",
+                        ));
+                    }
+                };
+                parsed.bounds.extend(bounds);
+            }
             _ => {
                 return Err(Error::new_spanned(
                     nested,
-                    "Either `type` or `fn` should be specified here",
+                    "Either `type`, `fn`, `args`, or `bound` should be specified here",
                 ));
             }
         }
     }
 
-    Ok((sys_ty, build_fn))
+    Ok(parsed)
+}
+
+fn parse_args(list: Punctuated<NestedMeta, Token![,]>) -> syn::Result<BuildArgs> {
+    let mut fields = Vec::with_capacity(list.len());
+
+    for nested in list.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("self") => {
+                if list.len() != 1 {
+                    return Err(Error::new_spanned(
+                        path,
+                        "`self` cannot be passed together with other fields",
+                    ));
+                }
+                return Ok(BuildArgs::SelfValue(path.span()));
+            }
+            NestedMeta::Meta(Meta::Path(path)) => match path.get_ident() {
+                Some(ident) => fields.push(Member::Named(ident.clone())),
+                None => {
+                    return Err(Error::new_spanned(
+                        path,
+                        "The given value should be a field name",
+                    ));
+                }
+            },
+            NestedMeta::Lit(Lit::Int(int)) => {
+                let index = int.base10_parse::<u32>()?;
+                fields.push(Member::Unnamed(Index {
+                    index,
+                    span: int.span(),
+                }));
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    nested,
+                    "Either `self`, a field name, or a field index should be specified here",
+                ));
+            }
+        }
+    }
+
+    Ok(BuildArgs::Fields(fields))
+}
+
+fn validate_fields(input: &DeriveInput, members: &[Member]) -> syn::Result<()> {
+    if members.is_empty() {
+        return Ok(());
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &members[0],
+                "Fields can only be passed for structs; consider passing `self` instead",
+            ));
+        }
+    };
+
+    for member in members {
+        let exists = match (member, fields) {
+            (Member::Named(ident), Fields::Named(named)) => named
+                .named
+                .iter()
+                .any(|field| field.ident.as_ref() == Some(ident)),
+            (Member::Unnamed(index), Fields::Unnamed(unnamed)) => {
+                (index.index as usize) < unnamed.unnamed.len()
+            }
+            _ => false,
+        };
+
+        if !exists {
+            return Err(Error::new_spanned(
+                member,
+                format!("`{}` does not have this field", input.ident),
+            ));
+        }
+    }
+
+    Ok(())
 }
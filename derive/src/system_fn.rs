@@ -0,0 +1,409 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parenthesized, Error, Expr, FnArg, GenericArgument, Ident, ItemFn, Pat, PathArguments,
+    ReturnType, Token, Type, TypePath,
+};
+
+pub fn expand(options: SystemOptions, item: ItemFn) -> TokenStream {
+    match expand_inner(options, item) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn expand_inner(options: SystemOptions, item: ItemFn) -> syn::Result<TokenStream> {
+    let sig = &item.sig;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new_spanned(
+            asyncness,
+            "Systems cannot be async functions",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "Systems cannot have generic parameters",
+        ));
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return Err(Error::new_spanned(ty, "Systems cannot return any values"));
+    }
+
+    let mut cmd = None;
+    let mut world = None;
+    let mut resources = Vec::new();
+    let mut queries = Vec::new();
+    let mut readers = Vec::new();
+
+    for input in sig.inputs.iter() {
+        let pat_type = match input {
+            FnArg::Typed(pat_type) => pat_type,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(
+                    receiver,
+                    "Systems cannot take `self` as a parameter",
+                ));
+            }
+        };
+        let pat = &*pat_type.pat;
+        let ty = &*pat_type.ty;
+
+        match classify_param(pat_type.attrs.iter(), ty)? {
+            Param::CommandBuffer => {
+                replace_once(&mut cmd, (pat, ty), ty, "command buffer")?;
+            }
+            Param::SubWorld => {
+                replace_once(&mut world, (pat, ty), ty, "world")?;
+            }
+            Param::Resource { mutable, inner } => {
+                resources.push((pat, ty, mutable, inner));
+            }
+            Param::Query { view, filter } => {
+                queries.push((pat, view, filter));
+            }
+            Param::EventReader { inner } => {
+                readers.push((pat, ty, inner));
+            }
+        }
+    }
+
+    let fn_name = &sig.ident;
+    let vis = &item.vis;
+    let attrs = &item.attrs;
+    let body = &item.block;
+
+    let pascal_name = to_pascal_case(&fn_name.to_string());
+    let system_name = options.name.unwrap_or_else(|| pascal_name.clone());
+    let desc_name = Ident::new(&format!("{}System", pascal_name), fn_name.span());
+    let build_fn_name = format_ident!("build_{}_system", fn_name);
+
+    let (sys_ty, system_trait, build_method) = if options.thread_local {
+        (
+            quote!(types::ThreadLocal),
+            quote!(Runnable),
+            quote!(build_thread_local),
+        )
+    } else {
+        (quote!(types::Parallel), quote!(Schedulable), quote!(build))
+    };
+
+    let resource_access = resources.iter().map(|(_, ty, mutable, inner)| {
+        if *mutable {
+            quote_spanned!(ty.span()=> .write_resource::<#inner>())
+        } else {
+            quote_spanned!(ty.span()=> .read_resource::<#inner>())
+        }
+    });
+    let query_access = queries.iter().map(|(pat, view, filter)| match filter {
+        Some(filter) => quote_spanned!(pat.span()=> .with_query(<#view>::query().filter(#filter))),
+        None => quote_spanned!(pat.span()=> .with_query(<#view>::query())),
+    });
+    let read_components = options.read_components.iter();
+    let write_components = options.write_components.iter();
+
+    // Event channels are created on demand, and each event reader is registered at build time
+    let events_init = resources
+        .iter()
+        .filter(|(_, _, _, inner)| matches!(last_ident(inner), Some(i) if i == "Events"))
+        .map(|(_, ty, _, inner)| quote_spanned!(ty.span()=> <#inner>::ensure(world);));
+    let reader_idents = (0..readers.len())
+        .map(|i| format_ident!("__reader_{}", i))
+        .collect::<Vec<_>>();
+    let reader_init = readers
+        .iter()
+        .zip(reader_idents.iter())
+        .map(|((_, ty, inner), ident)| {
+            quote_spanned!(ty.span()=> let mut #ident = <#inner>::register(world);)
+        });
+    let reader_bindings = readers
+        .iter()
+        .zip(reader_idents.iter())
+        .map(|((pat, ty, _), ident)| quote!(let #pat: #ty = &mut #ident;));
+
+    let cmd_binding = match cmd {
+        Some((pat, ty)) => quote!(let #pat: #ty = __cmd;),
+        None => quote!(let _ = __cmd;),
+    };
+    let world_binding = match world {
+        Some((pat, ty)) => quote!(let #pat: #ty = __world;),
+        None => quote!(let _ = __world;),
+    };
+
+    let resource_idents = (0..resources.len())
+        .map(|i| format_ident!("__resource_{}", i))
+        .collect::<Vec<_>>();
+    let resource_destructure = destructure(&resource_idents, quote!(__resources));
+    let resource_bindings =
+        resources
+            .iter()
+            .zip(resource_idents.iter())
+            .map(|((pat, ty, mutable, _), ident)| {
+                if *mutable {
+                    quote!(let #pat: #ty = &mut **#ident;)
+                } else {
+                    quote!(let #pat: #ty = &**#ident;)
+                }
+            });
+
+    let query_idents = (0..queries.len())
+        .map(|i| format_ident!("__query_{}", i))
+        .collect::<Vec<_>>();
+    let query_destructure = destructure(&query_idents, quote!(__queries));
+    let query_bindings = queries
+        .iter()
+        .zip(query_idents.iter())
+        .map(|((pat, _, _), ident)| quote!(let #pat = #ident;));
+
+    let desc_doc = format!(
+        "System descriptor(builder) for `{}` system\n\nThis is a wrapper for [`{}`].\n\n\
+         [`{}`]: ./fn.{}.html",
+        system_name, build_fn_name, build_fn_name, build_fn_name
+    );
+
+    Ok(quote! {
+        #[doc = #desc_doc]
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+        #vis struct #desc_name;
+
+        impl SystemDesc for #desc_name {
+            type SystemType = #sys_ty;
+
+            fn build(self, world: &mut World) -> <Self::SystemType as SystemType>::System {
+                #build_fn_name(world)
+            }
+        }
+
+        #( #attrs )*
+        #vis fn #build_fn_name(world: &mut World) -> Box<dyn #system_trait> {
+            let _ = &world;
+            #( #events_init )*
+            #( #reader_init )*
+
+            SystemBuilder::new(#system_name)
+                #( #resource_access )*
+                #( #query_access )*
+                #( .read_component::<#read_components>() )*
+                #( .write_component::<#write_components>() )*
+                .#build_method(move |__cmd, __world, __resources, __queries| {
+                    #cmd_binding
+                    #world_binding
+                    #resource_destructure
+                    #( #resource_bindings )*
+                    #query_destructure
+                    #( #query_bindings )*
+                    #( #reader_bindings )*
+                    #body
+                })
+        }
+    })
+}
+
+/// Options given to the `system` attribute
+#[derive(Default)]
+pub struct SystemOptions {
+    thread_local: bool,
+    name: Option<String>,
+    read_components: Vec<Type>,
+    write_components: Vec<Type>,
+}
+
+impl Parse for SystemOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = SystemOptions::default();
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+
+            if ident == "thread_local" {
+                options.thread_local = true;
+            } else if ident == "name" {
+                input.parse::<Token![=]>()?;
+                options.name = Some(input.parse::<syn::LitStr>()?.value());
+            } else if ident == "read_component" || ident == "write_component" {
+                let content;
+                parenthesized!(content in input);
+                let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                if ident == "read_component" {
+                    options.read_components.extend(types);
+                } else {
+                    options.write_components.extend(types);
+                }
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    "Either `thread_local`, `name`, `read_component`, or `write_component` \
+                     should be specified here",
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// What a parameter of the system function is bound to
+enum Param<'a> {
+    CommandBuffer,
+    SubWorld,
+    Resource {
+        mutable: bool,
+        inner: &'a Type,
+    },
+    Query {
+        view: &'a Type,
+        filter: Option<Box<Expr>>,
+    },
+    EventReader {
+        inner: &'a Type,
+    },
+}
+
+fn classify_param<'a>(
+    mut attrs: impl Iterator<Item = &'a syn::Attribute>,
+    ty: &'a Type,
+) -> syn::Result<Param<'a>> {
+    let filter = match attrs.next() {
+        Some(attr) if attr.path.is_ident("filter") => Some(Box::new(attr.parse_args::<Expr>()?)),
+        Some(attr) => {
+            return Err(Error::new_spanned(
+                attr,
+                "Only `filter` attribute is allowed for query parameters",
+            ));
+        }
+        None => None,
+    };
+    if let Some(attr) = attrs.next() {
+        return Err(Error::new_spanned(
+            attr,
+            "Only one `filter` attribute is allowed",
+        ));
+    }
+
+    let (mutable, inner) = match ty {
+        Type::Reference(reference) => (reference.mutability.is_some(), &*reference.elem),
+        Type::Path(_) => (false, ty),
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "The parameter should be a reference to a resource, a command buffer, or a \
+                 sub-world, or a query",
+            ));
+        }
+    };
+
+    if let Some(view) = query_view(inner) {
+        return Ok(Param::Query { view, filter });
+    }
+
+    if filter.is_some() {
+        return Err(Error::new_spanned(
+            ty,
+            "`filter` attribute can only be used for query parameters",
+        ));
+    }
+
+    match (ty, last_ident(inner)) {
+        (Type::Reference(_), Some(ident)) if ident == "CommandBuffer" => {
+            if mutable {
+                Ok(Param::CommandBuffer)
+            } else {
+                Err(Error::new_spanned(
+                    ty,
+                    "The command buffer should be a mutable reference",
+                ))
+            }
+        }
+        (Type::Reference(_), Some(ident)) if ident == "SubWorld" => Ok(Param::SubWorld),
+        (Type::Reference(_), Some(ident)) if ident == "EventReader" => {
+            if mutable {
+                Ok(Param::EventReader { inner })
+            } else {
+                Err(Error::new_spanned(
+                    ty,
+                    "The event reader should be a mutable reference",
+                ))
+            }
+        }
+        (Type::Reference(_), _) => Ok(Param::Resource { mutable, inner }),
+        _ => Err(Error::new_spanned(
+            ty,
+            "Resources should be taken as references, e.g. `&Time` or `&mut FpsValue`",
+        )),
+    }
+}
+
+/// Get the identifier of the last path segment of the given type
+fn last_ident(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().map(|s| &s.ident),
+        _ => None,
+    }
+}
+
+/// Get `V` from `Query<V>`
+fn query_view(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Query" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(view) => Some(view),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn replace_once<'a>(
+    slot: &mut Option<(&'a Pat, &'a Type)>,
+    value: (&'a Pat, &'a Type),
+    span_source: &Type,
+    what: &str,
+) -> syn::Result<()> {
+    if slot.replace(value).is_some() {
+        return Err(Error::new(
+            span_source.span(),
+            format!("The {} cannot be taken multiple times", what),
+        ));
+    }
+    Ok(())
+}
+
+fn destructure(idents: &[Ident], source: TokenStream) -> TokenStream {
+    match idents.len() {
+        0 => quote!(let _ = #source;),
+        1 => {
+            let ident = &idents[0];
+            quote!(let #ident = #source;)
+        }
+        _ => quote!(let ( #( #idents, )* ) = #source;),
+    }
+}
+
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -1,15 +1,14 @@
 use game_engine::app::{init_logger, Application};
 use game_engine::core::frame_rate::{build_fps_count_system, FpsValue};
-use game_engine::core::systems::{ScheduleBuilder, SystemOrder};
+use game_engine::core::systems::{
+    system, types, ScheduleBuilder, SystemDesc, SystemOrder, SystemType,
+};
 use game_engine::ecs::prelude::*;
 
-fn build_fps_log_system(_: &mut World) -> Box<dyn Schedulable> {
-    SystemBuilder::new("LogFps")
-        .read_resource::<FpsValue>()
-        .build(|_, _, res, _| {
-            log::debug!("Average fps: {}", res.average_fps());
-            log::debug!("Last fps: {}", res.last_fps());
-        })
+#[system]
+fn log_fps(fps_value: &FpsValue) {
+    log::debug!("Average fps: {}", fps_value.average_fps());
+    log::debug!("Last fps: {}", fps_value.last_fps());
 }
 
 fn main() {
@@ -17,7 +16,7 @@ fn main() {
 
     let schedule_builder = ScheduleBuilder::new()
         .with_system_create_fn(SystemOrder::numbered(0, 0), build_fps_count_system)
-        .with_system_create_fn(SystemOrder::numbered(0, 0), build_fps_log_system);
+        .with_system_create_desc(SystemOrder::numbered(0, 0), LogFpsSystem);
 
     let mut app = Application::builder().build(schedule_builder);
 
@@ -8,6 +8,8 @@
 )]
 
 pub use game_engine_app as app;
+#[cfg(feature = "camera")]
+pub use game_engine_camera as camera;
 pub use game_engine_core as core;
 pub use game_engine_core::{ecs, event_channel, math};
 #[cfg(feature = "transform")]
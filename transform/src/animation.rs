@@ -0,0 +1,885 @@
+//! Keyframe animation of [`Position`], [`Rotation`], and [`Scale`] components
+//!
+//! An [`AnimationClip`] has a keyframe [`Track`] for each of the properties it animates, and named events placed on its
+//! timeline. An [`AnimationPlayer`] component plays clips on the entity it is attached to, and the system built by
+//! [`build_animation_system`] samples the clips and writes the transform components. When the playback passes an event
+//! of the clip, an [`AnimationEvent`] is written to the [`Events`] resource.
+//!
+//! A clip is written in RON as below. Each of `position`, `rotation`, `scale`, and `events` can be omitted, and so can
+//! `interpolation`, which defaults to `Linear`. Keyframes do not have to be sorted by time.
+//!
+//! ```ron
+//! (
+//!     position: (
+//!         interpolation: Cubic,
+//!         keyframes: [(time: 0, value: (0, 0, 0)), (time: 1, value: (0, 2, 0)), (time: 2, value: (0, 0, 0))],
+//!     ),
+//!     rotation: (keyframes: [(time: 0, value: Euler(0, 0, 0)), (time: 2, value: Euler(0, 0, 3))]),
+//!     events: [(time: 1, name: "top")],
+//! )
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::transform::animation::{AnimationClip, AnimationPlayer};
+//! use game_engine::transform::components::Position;
+//! use std::sync::Arc;
+//!
+//! let clip = AnimationClip::from_ron(r#"(
+//!     position: (keyframes: [(time: 0, value: (0, 0, 0)), (time: 2, value: (4, 0, 0))]),
+//! )"#).unwrap();
+//! assert_eq!(clip.duration(), 2.0);
+//! assert_eq!(clip.sample(0.5).position, Some(Position::from_x(1.0)));
+//!
+//! let mut player = AnimationPlayer::new().with_looping(true);
+//! player.play(Arc::new(clip));
+//! assert!(player.is_playing());
+//! ```
+//!
+//! [`Position`]: ../components/struct.Position.html
+//! [`Rotation`]: ../components/struct.Rotation.html
+//! [`Scale`]: ../components/struct.Scale.html
+//! [`AnimationClip`]: ./struct.AnimationClip.html
+//! [`Track`]: ./struct.Track.html
+//! [`AnimationPlayer`]: ./struct.AnimationPlayer.html
+//! [`build_animation_system`]: ../systems/fn.build_animation_system.html
+//! [`AnimationEvent`]: ./struct.AnimationEvent.html
+//! [`Events`]: ../../game_engine_core/events/struct.Events.html
+
+use crate::components::{Position, Rotation, Scale};
+use crate::ecs::entity::Entity;
+use crate::math::Vector3;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How the values between two keyframes of a [`Track`] are computed
+///
+/// [`Track`]: ./struct.Track.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum Interpolation {
+    /// Keep the value of the previous keyframe until the next keyframe
+    Step,
+
+    /// Interpolate linearly between the two keyframes, or spherically for [`Rotation`]
+    ///
+    /// [`Rotation`]: ../components/struct.Rotation.html
+    #[default]
+    Linear,
+
+    /// Interpolate along a Catmull-Rom spline through the keyframes, which passes through every keyframe smoothly
+    ///
+    /// [`Rotation`] is interpolated the same way as [`Linear`].
+    ///
+    /// [`Rotation`]: ../components/struct.Rotation.html
+    /// [`Linear`]: #variant.Linear
+    Cubic,
+}
+
+/// Value that can be animated by a [`Track`]
+///
+/// [`Track`]: ./struct.Track.html
+pub trait Animatable: Copy {
+    /// Interpolate from this value to the other one, where `t` is in the range of `[0, 1]`
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+
+    /// Interpolate from `from` to `to` along a smooth curve that also passes through `before` and `after`
+    ///
+    /// This falls back to [`interpolate`] by default.
+    ///
+    /// [`interpolate`]: #tymethod.interpolate
+    fn interpolate_cubic(before: &Self, from: &Self, to: &Self, after: &Self, t: f32) -> Self {
+        let _ = (before, after);
+        from.interpolate(to, t)
+    }
+}
+
+impl Animatable for Position {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.vector.lerp(&other.vector, t).into()
+    }
+
+    fn interpolate_cubic(before: &Self, from: &Self, to: &Self, after: &Self, t: f32) -> Self {
+        catmull_rom(&before.vector, &from.vector, &to.vector, &after.vector, t).into()
+    }
+}
+
+impl Animatable for Scale {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t).into()
+    }
+
+    fn interpolate_cubic(before: &Self, from: &Self, to: &Self, after: &Self, t: f32) -> Self {
+        catmull_rom(before, from, to, after, t).into()
+    }
+}
+
+impl Animatable for Rotation {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        // The rotations are half a turn apart, so there is no single shortest path between them
+        self.try_slerp(other, t, f32::EPSILON)
+            .unwrap_or_else(|| if t < 0.5 { **self } else { **other })
+            .into()
+    }
+}
+
+/// Point on the Catmull-Rom spline between `p1` and `p2`
+fn catmull_rom(
+    p0: &Vector3<f32>,
+    p1: &Vector3<f32>,
+    p2: &Vector3<f32>,
+    p3: &Vector3<f32>,
+    t: f32,
+) -> Vector3<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+/// Value of a [`Track`] at the given time, in seconds
+///
+/// [`Track`]: ./struct.Track.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Keyframe<T> {
+    /// Time of this keyframe in seconds from the start of the clip
+    pub time: f32,
+    /// Value at the time
+    pub value: T,
+}
+
+impl<T> Keyframe<T> {
+    /// Create a new instance with the given time and value
+    pub fn new(time: f32, value: T) -> Keyframe<T> {
+        Keyframe { time, value }
+    }
+}
+
+/// Keyframes of a property of an [`AnimationClip`], sorted by time
+///
+/// Before the first keyframe and after the last one, the value of the closest keyframe is used.
+///
+/// [`AnimationClip`]: ./struct.AnimationClip.html
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "track_serde::SerializedTrack<T>",
+    into = "track_serde::SerializedTrack<T>",
+    bound(
+        deserialize = "T: Deserialize<'de>",
+        serialize = "T: Serialize + Clone"
+    )
+)]
+pub struct Track<T> {
+    interpolation: Interpolation,
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Track::new(Interpolation::default(), Vec::new())
+    }
+}
+
+impl<T> Track<T> {
+    /// Create a new instance from the given keyframes, sorting them by time
+    pub fn new(interpolation: Interpolation, mut keyframes: Vec<Keyframe<T>>) -> Track<T> {
+        keyframes.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Track {
+            interpolation,
+            keyframes,
+        }
+    }
+
+    /// Get how the values between keyframes are computed
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Get the keyframes sorted by time
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// Check if there are no keyframes
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Get the time of the last keyframe, or zero if there is none
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+}
+
+impl<T: Animatable> Track<T> {
+    /// Get the value at the given time, or `None` if there are no keyframes
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let keyframes = &self.keyframes;
+        let first = keyframes.first()?;
+        let last = keyframes[keyframes.len() - 1];
+        if time <= first.time {
+            return Some(first.value);
+        }
+        if time >= last.time {
+            return Some(last.value);
+        }
+
+        // The index of the keyframe right after the time, which is neither the first nor past the last
+        let next = keyframes.iter().position(|keyframe| keyframe.time > time)?;
+        let (from, to) = (&keyframes[next - 1], &keyframes[next]);
+        let t = (time - from.time) / (to.time - from.time);
+        Some(match self.interpolation {
+            Interpolation::Step => from.value,
+            Interpolation::Linear => from.value.interpolate(&to.value, t),
+            Interpolation::Cubic => {
+                let before = &keyframes[next.saturating_sub(2)];
+                let after = keyframes.get(next + 1).unwrap_or(to);
+                T::interpolate_cubic(&before.value, &from.value, &to.value, &after.value, t)
+            }
+        })
+    }
+}
+
+mod track_serde {
+    use super::{Interpolation, Keyframe, Track};
+    use serde::{Deserialize, Serialize};
+
+    #[allow(missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub struct SerializedTrack<T> {
+        #[serde(default)]
+        interpolation: Interpolation,
+        keyframes: Vec<Keyframe<T>>,
+    }
+
+    impl<T> From<SerializedTrack<T>> for Track<T> {
+        fn from(ser: SerializedTrack<T>) -> Self {
+            Track::new(ser.interpolation, ser.keyframes)
+        }
+    }
+
+    impl<T> From<Track<T>> for SerializedTrack<T> {
+        fn from(track: Track<T>) -> Self {
+            SerializedTrack {
+                interpolation: track.interpolation,
+                keyframes: track.keyframes,
+            }
+        }
+    }
+}
+
+/// Named event placed on the timeline of an [`AnimationClip`]
+///
+/// [`AnimationClip`]: ./struct.AnimationClip.html
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AnimationEventKey {
+    /// Time of the event in seconds from the start of the clip
+    pub time: f32,
+    /// Name of the event, which is passed to [`AnimationEvent`]
+    ///
+    /// [`AnimationEvent`]: ./struct.AnimationEvent.html
+    pub name: String,
+}
+
+/// Event written to the [`Events`] resource when an [`AnimationPlayer`] passes an [`AnimationEventKey`]
+///
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+/// [`AnimationPlayer`]: ./struct.AnimationPlayer.html
+/// [`AnimationEventKey`]: ./struct.AnimationEventKey.html
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AnimationEvent {
+    /// The entity with the [`AnimationPlayer`]
+    ///
+    /// [`AnimationPlayer`]: ./struct.AnimationPlayer.html
+    pub entity: Entity,
+    /// The name of the event
+    pub name: String,
+}
+
+/// Keyframe animation of the transform components of an entity
+///
+/// The duration of the clip is the time of its last keyframe or event.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "clip_serde::SerializedClip",
+    into = "clip_serde::SerializedClip"
+)]
+pub struct AnimationClip {
+    position: Option<Track<Position>>,
+    rotation: Option<Track<Rotation>>,
+    scale: Option<Track<Scale>>,
+    events: Vec<AnimationEventKey>,
+    duration: f32,
+}
+
+impl AnimationClip {
+    /// Create a new instance that animates nothing
+    pub fn new() -> AnimationClip {
+        AnimationClip::default()
+    }
+
+    /// Load a clip from the given RON string
+    pub fn from_ron(ron: &str) -> Result<AnimationClip, ron::de::Error> {
+        ron::de::from_str(ron)
+    }
+
+    /// Animate [`Position`] component with the given track
+    ///
+    /// [`Position`]: ../components/struct.Position.html
+    pub fn with_position_track(mut self, track: Track<Position>) -> Self {
+        self.position = Some(track);
+        self.update_duration();
+        self
+    }
+
+    /// Animate [`Rotation`] component with the given track
+    ///
+    /// [`Rotation`]: ../components/struct.Rotation.html
+    pub fn with_rotation_track(mut self, track: Track<Rotation>) -> Self {
+        self.rotation = Some(track);
+        self.update_duration();
+        self
+    }
+
+    /// Animate [`Scale`] component with the given track
+    ///
+    /// [`Scale`]: ../components/struct.Scale.html
+    pub fn with_scale_track(mut self, track: Track<Scale>) -> Self {
+        self.scale = Some(track);
+        self.update_duration();
+        self
+    }
+
+    /// Fire an event with the given name when the playback passes the given time
+    pub fn with_event<S: Into<String>>(mut self, time: f32, name: S) -> Self {
+        self.events.push(AnimationEventKey {
+            time,
+            name: name.into(),
+        });
+        self.update_duration();
+        self
+    }
+
+    /// Get the track for [`Position`] component if there is one
+    ///
+    /// [`Position`]: ../components/struct.Position.html
+    pub fn position_track(&self) -> Option<&Track<Position>> {
+        self.position.as_ref()
+    }
+
+    /// Get the track for [`Rotation`] component if there is one
+    ///
+    /// [`Rotation`]: ../components/struct.Rotation.html
+    pub fn rotation_track(&self) -> Option<&Track<Rotation>> {
+        self.rotation.as_ref()
+    }
+
+    /// Get the track for [`Scale`] component if there is one
+    ///
+    /// [`Scale`]: ../components/struct.Scale.html
+    pub fn scale_track(&self) -> Option<&Track<Scale>> {
+        self.scale.as_ref()
+    }
+
+    /// Get the events of this clip sorted by time
+    pub fn events(&self) -> &[AnimationEventKey] {
+        &self.events
+    }
+
+    /// Get the duration of this clip in seconds
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Get the values of the animated properties at the given time
+    pub fn sample(&self, time: f32) -> AnimationPose {
+        AnimationPose {
+            position: self.position.as_ref().and_then(|track| track.sample(time)),
+            rotation: self.rotation.as_ref().and_then(|track| track.sample(time)),
+            scale: self.scale.as_ref().and_then(|track| track.sample(time)),
+        }
+    }
+
+    fn update_duration(&mut self) {
+        self.events.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        self.duration = [
+            self.position.as_ref().map_or(0.0, Track::duration),
+            self.rotation.as_ref().map_or(0.0, Track::duration),
+            self.scale.as_ref().map_or(0.0, Track::duration),
+            self.events.last().map_or(0.0, |event| event.time),
+        ]
+        .iter()
+        .copied()
+        .fold(0.0, f32::max);
+    }
+}
+
+mod clip_serde {
+    use super::{AnimationClip, AnimationEventKey, Track};
+    use crate::components::{Position, Rotation, Scale};
+    use serde::{Deserialize, Serialize};
+
+    /// Same as `AnimationClip`, except that an empty track stands for a property that is not animated, so that the
+    /// tracks are written without `Some` in RON
+    #[allow(missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub struct SerializedClip {
+        #[serde(default, skip_serializing_if = "Track::is_empty")]
+        position: Track<Position>,
+        #[serde(default, skip_serializing_if = "Track::is_empty")]
+        rotation: Track<Rotation>,
+        #[serde(default, skip_serializing_if = "Track::is_empty")]
+        scale: Track<Scale>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        events: Vec<AnimationEventKey>,
+    }
+
+    fn non_empty<T>(track: Track<T>) -> Option<Track<T>> {
+        Some(track).filter(|track| !track.is_empty())
+    }
+
+    impl From<SerializedClip> for AnimationClip {
+        fn from(ser: SerializedClip) -> Self {
+            let mut clip = AnimationClip {
+                position: non_empty(ser.position),
+                rotation: non_empty(ser.rotation),
+                scale: non_empty(ser.scale),
+                events: ser.events,
+                duration: 0.0,
+            };
+            clip.update_duration();
+            clip
+        }
+    }
+
+    impl From<AnimationClip> for SerializedClip {
+        fn from(clip: AnimationClip) -> Self {
+            SerializedClip {
+                position: clip.position.unwrap_or_default(),
+                rotation: clip.rotation.unwrap_or_default(),
+                scale: clip.scale.unwrap_or_default(),
+                events: clip.events,
+            }
+        }
+    }
+}
+
+/// Values of the properties animated by an [`AnimationClip`] at some time
+///
+/// The properties that are not animated are `None`.
+///
+/// [`AnimationClip`]: ./struct.AnimationClip.html
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AnimationPose {
+    /// The value of [`Position`] component
+    ///
+    /// [`Position`]: ../components/struct.Position.html
+    pub position: Option<Position>,
+    /// The value of [`Rotation`] component
+    ///
+    /// [`Rotation`]: ../components/struct.Rotation.html
+    pub rotation: Option<Rotation>,
+    /// The value of [`Scale`] component
+    ///
+    /// [`Scale`]: ../components/struct.Scale.html
+    pub scale: Option<Scale>,
+}
+
+impl AnimationPose {
+    /// Blend this pose with the other one, where `weight` is how much of the other one is used
+    ///
+    /// A property that is animated by only one of the poses is taken from that pose as is.
+    pub fn blend(&self, other: &AnimationPose, weight: f32) -> AnimationPose {
+        fn blend<T: Animatable>(a: Option<T>, b: Option<T>, weight: f32) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.interpolate(&b, weight)),
+                (a, b) => a.or(b),
+            }
+        }
+        AnimationPose {
+            position: blend(self.position, other.position, weight),
+            rotation: blend(self.rotation, other.rotation, weight),
+            scale: blend(self.scale, other.scale, weight),
+        }
+    }
+}
+
+/// Playback state of a clip in an [`AnimationPlayer`]
+///
+/// [`AnimationPlayer`]: ./struct.AnimationPlayer.html
+#[derive(Clone, Debug)]
+struct ClipPlayback {
+    clip: Arc<AnimationClip>,
+    time: f32,
+    /// Whether the events at the current time are yet to be fired, which is true right after the clip is started
+    at_start: bool,
+}
+
+impl ClipPlayback {
+    fn new(clip: Arc<AnimationClip>) -> ClipPlayback {
+        ClipPlayback {
+            clip,
+            time: 0.0,
+            at_start: true,
+        }
+    }
+
+    /// Advance the time by the given delta, pushing the names of the passed events, and return whether the end of the
+    /// clip is reached without looping
+    fn advance(&mut self, delta: f32, looping: bool, fired: &mut Vec<String>) -> bool {
+        let duration = self.clip.duration;
+        let events = &self.clip.events;
+        let mut remaining = delta;
+        // Loop at most a few times so that a huge delta on a short clip does not stall the frame
+        for _ in 0..16 {
+            let (start, target) = (self.time, self.time + remaining);
+            let end = target.max(0.0).min(duration);
+            let (low, high) = if end >= start {
+                (start, end)
+            } else {
+                (end, start)
+            };
+            let at_start = self.at_start;
+            fired.extend(
+                events
+                    .iter()
+                    .filter(|event| {
+                        let time = event.time;
+                        (time > low && time < high)
+                            || (time == end && end != start)
+                            || (time == start && at_start)
+                    })
+                    .map(|event| event.name.clone()),
+            );
+            self.time = end;
+            self.at_start = false;
+            if (0.0..=duration).contains(&target) {
+                return false;
+            }
+            if !looping || duration <= 0.0 {
+                return true;
+            }
+            // Wrap around to the other end of the clip
+            remaining = target - end;
+            self.time = if remaining > 0.0 { 0.0 } else { duration };
+            self.at_start = true;
+        }
+        false
+    }
+}
+
+/// Component that plays [`AnimationClip`]s on the transform components of the entity it is attached to
+///
+/// The player can blend a second clip into the main one, either with a fixed weight using [`blend_with`], or by fading
+/// out the previous clip using [`cross_fade`]. Only the main clip fires events.
+///
+/// [`AnimationClip`]: ./struct.AnimationClip.html
+/// [`blend_with`]: #method.blend_with
+/// [`cross_fade`]: #method.cross_fade
+#[derive(Clone, Debug)]
+pub struct AnimationPlayer {
+    main: Option<ClipPlayback>,
+    blended: Option<ClipPlayback>,
+    /// How much of the blended clip is used
+    blend_weight: f32,
+    /// Remaining and total time of the cross fade
+    fade: Option<(f32, f32)>,
+    playing: bool,
+    looping: bool,
+    speed: f32,
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        AnimationPlayer {
+            main: None,
+            blended: None,
+            blend_weight: 0.0,
+            fade: None,
+            playing: false,
+            looping: false,
+            speed: 1.0,
+        }
+    }
+}
+
+impl AnimationPlayer {
+    /// Create a new instance that plays nothing
+    pub fn new() -> AnimationPlayer {
+        AnimationPlayer::default()
+    }
+
+    /// Set whether the clips start over when they reach the end
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Set the playback speed, where negative values play the clips backwards
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Play the given clip from the start, replacing all the clips being played
+    pub fn play(&mut self, clip: Arc<AnimationClip>) {
+        self.main = Some(ClipPlayback::new(clip));
+        self.blended = None;
+        self.fade = None;
+        self.playing = true;
+    }
+
+    /// Play the given clip from the start, fading out the current clip over the given time in seconds
+    pub fn cross_fade(&mut self, clip: Arc<AnimationClip>, duration: f32) {
+        self.blended = self.main.take();
+        self.main = Some(ClipPlayback::new(clip));
+        if self.blended.is_some() && duration > 0.0 {
+            self.blend_weight = 1.0;
+            self.fade = Some((duration, duration));
+        } else {
+            self.blended = None;
+            self.fade = None;
+        }
+        self.playing = true;
+    }
+
+    /// Play the given clip along with the main clip, blending the given weight of it into the main clip
+    pub fn blend_with(&mut self, clip: Arc<AnimationClip>, weight: f32) {
+        self.blended = Some(ClipPlayback::new(clip));
+        self.blend_weight = weight.clamp(0.0, 1.0);
+        self.fade = None;
+    }
+
+    /// Change the weight of the blended clip
+    pub fn set_blend_weight(&mut self, weight: f32) {
+        self.blend_weight = weight.clamp(0.0, 1.0);
+    }
+
+    /// Get the weight of the blended clip
+    pub fn blend_weight(&self) -> f32 {
+        self.blend_weight
+    }
+
+    /// Pause the playback
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Resume the paused playback
+    pub fn resume(&mut self) {
+        self.playing = self.main.is_some();
+    }
+
+    /// Stop the playback and remove all the clips
+    pub fn stop(&mut self) {
+        *self = AnimationPlayer {
+            looping: self.looping,
+            speed: self.speed,
+            ..AnimationPlayer::default()
+        };
+    }
+
+    /// Check if the player is playing a clip, i.e. the playback is not paused, stopped, or finished
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Check if the clips start over when they reach the end
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Set whether the clips start over when they reach the end
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Get the playback speed
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Set the playback speed, where negative values play the clips backwards
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Get the main clip
+    pub fn clip(&self) -> Option<&Arc<AnimationClip>> {
+        self.main.as_ref().map(|main| &main.clip)
+    }
+
+    /// Get the playback time of the main clip in seconds
+    pub fn time(&self) -> f32 {
+        self.main.as_ref().map_or(0.0, |main| main.time)
+    }
+
+    /// Move the playback of the main clip to the given time in seconds without firing any events
+    pub fn seek(&mut self, time: f32) {
+        if let Some(main) = &mut self.main {
+            main.time = time.max(0.0).min(main.clip.duration);
+            main.at_start = false;
+        }
+    }
+
+    /// Advance the playback by the given time in seconds, pushing the names of the events passed by the main clip
+    pub fn advance(&mut self, delta: f32, fired: &mut Vec<String>) {
+        if !self.playing {
+            return;
+        }
+        let delta = delta * self.speed;
+        if let Some(main) = &mut self.main {
+            if main.advance(delta, self.looping, fired) {
+                self.playing = false;
+            }
+        }
+        if let Some(blended) = &mut self.blended {
+            blended.advance(delta, self.looping, &mut Vec::new());
+        }
+        if let Some((remaining, duration)) = &mut self.fade {
+            *remaining -= delta.abs();
+            self.blend_weight = (*remaining / *duration).max(0.0);
+            if *remaining <= 0.0 {
+                self.blended = None;
+                self.fade = None;
+            }
+        }
+    }
+
+    /// Get the blended values of the animated properties at the current time, or `None` if there is no clip
+    pub fn sample(&self) -> Option<AnimationPose> {
+        let main = self.main.as_ref()?.sample();
+        Some(match &self.blended {
+            Some(blended) => main.blend(&blended.sample(), self.blend_weight),
+            None => main,
+        })
+    }
+}
+
+impl ClipPlayback {
+    fn sample(&self) -> AnimationPose {
+        self.clip.sample(self.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip() -> AnimationClip {
+        AnimationClip::from_ron(
+            r#"(
+                position: (
+                    interpolation: Step,
+                    keyframes: [(time: 1, value: (1, 0, 0)), (time: 0, value: (0, 0, 0)), (time: 2, value: (2, 0, 0))],
+                ),
+                scale: (interpolation: Cubic, keyframes: [(time: 0, value: 1), (time: 1, value: 2), (time: 2, value: 1)]),
+                rotation: (keyframes: [(time: 0, value: Euler(0, 0, 0)), (time: 2, value: Euler(0, 0, 1))]),
+                events: [(time: 1, name: "middle"), (time: 0, name: "start"), (time: 2, name: "end")],
+            )"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sample_tracks() {
+        let clip = clip();
+        assert_eq!(clip.duration(), 2.0);
+        assert_eq!(
+            clip.events()
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["start", "middle", "end"]
+        );
+
+        let pose = clip.sample(1.5);
+        assert_eq!(pose.position, Some(Position::from_x(1.0)));
+        assert!((pose.rotation.unwrap().angle() - 0.75).abs() < 1e-5);
+        // The spline passes through the keyframes, and is symmetric around the peak
+        assert_eq!(clip.sample(1.0).scale, Some(Scale::uniform(2.0)));
+        let (before, after) = (
+            clip.sample(0.5).scale.unwrap(),
+            clip.sample(1.5).scale.unwrap(),
+        );
+        assert!((before.x - after.x).abs() < 1e-5 && before.x > 1.5);
+        // Clamped outside the keyframes
+        assert_eq!(clip.sample(-1.0).position, Some(Position::from_x(0.0)));
+        assert_eq!(clip.sample(3.0).position, Some(Position::from_x(2.0)));
+    }
+
+    #[test]
+    fn play_and_fire_events() {
+        let clip = Arc::new(clip());
+        let mut fired = Vec::new();
+        let mut player = AnimationPlayer::new();
+        player.play(Arc::clone(&clip));
+
+        player.advance(0.5, &mut fired);
+        assert_eq!(fired, vec!["start"]);
+        player.advance(1.0, &mut fired);
+        assert_eq!(fired, vec!["start", "middle"]);
+        player.advance(1.0, &mut fired);
+        assert_eq!(fired, vec!["start", "middle", "end"]);
+        assert_eq!(player.time(), 2.0);
+        assert!(!player.is_playing());
+
+        // Looping fires the events of the next round as well
+        fired.clear();
+        player.set_looping(true);
+        player.play(Arc::clone(&clip));
+        player.advance(2.5, &mut fired);
+        assert_eq!(fired, vec!["start", "middle", "end", "start"]);
+        assert!((player.time() - 0.5).abs() < 1e-5);
+
+        // Backwards, while paused the time does not move
+        fired.clear();
+        player.set_speed(-1.0);
+        player.advance(1.0, &mut fired);
+        assert_eq!(fired, vec!["start", "end"]);
+        assert!((player.time() - 1.5).abs() < 1e-5);
+        player.pause();
+        player.advance(1.0, &mut fired);
+        assert!((player.time() - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blend_clips() {
+        let still = Arc::new(AnimationClip::new().with_position_track(Track::new(
+            Interpolation::Linear,
+            vec![
+                Keyframe::new(0.0, Position::from_y(4.0)),
+                Keyframe::new(10.0, Position::from_y(4.0)),
+            ],
+        )));
+        let mut player = AnimationPlayer::new();
+        player.play(Arc::new(clip()));
+        player.blend_with(Arc::clone(&still), 0.25);
+        player.advance(1.0, &mut Vec::new());
+        let pose = player.sample().unwrap();
+        assert_eq!(pose.position, Some(Position::from_xyz(0.75, 1.0, 0.0)));
+        assert_eq!(pose.scale, Some(Scale::uniform(2.0)));
+
+        // The previous clip fades out
+        player.cross_fade(still, 1.0);
+        player.advance(0.5, &mut Vec::new());
+        let pose = player.sample().unwrap();
+        assert_eq!(pose.position, Some(Position::from_xyz(0.5, 2.0, 0.0)));
+        player.advance(0.5, &mut Vec::new());
+        assert_eq!(
+            player.sample().unwrap().position,
+            Some(Position::from_y(4.0))
+        );
+    }
+}
@@ -1,26 +1,237 @@
 //! Components used for tracking transforms and hierarchy
 //!
-//! The users should only modify [`Position`], [`Rotation`], and [`Parent`] components unless they know what they are
-//! doing.
+//! The users should only modify [`Position`], [`Rotation`], [`Scale`], and [`Parent`] components unless they know what
+//! they are doing. 2D games use [`Position2`], [`Rotation2`], and [`Scale2`] instead, which share the same [`Parent`]
+//! component and hierarchy.
+//!
+//! All components are registered to the [`ComponentRegistry`] by [`register_components`], which
+//! [`TransformBundle`] and [`Transform2dBundle`] do automatically.
 //!
 //! [`Position`]: ./struct.Position.html
 //! [`Rotation`]: ./struct.Rotation.html
+//! [`Scale`]: ./struct.Scale.html
+//! [`Position2`]: ./struct.Position2.html
+//! [`Rotation2`]: ./struct.Rotation2.html
+//! [`Scale2`]: ./struct.Scale2.html
 //! [`Parent`]: ./struct.Parent.html
+//! [`ComponentRegistry`]: ../../game_engine_core/registry/struct.ComponentRegistry.html
+//! [`register_components`]: ./fn.register_components.html
+//! [`TransformBundle`]: ../systems/struct.TransformBundle.html
+//! [`Transform2dBundle`]: ../systems/struct.Transform2dBundle.html
 
+mod aabb;
+mod bounding_sphere;
 mod children;
+mod floating_origin_target;
+mod hierarchical_bounds;
+mod interpolated_transform;
 mod math_traits_impl;
+mod matrix3_wrapper;
 mod matrix4_wrapper;
+mod orphan_policy;
 mod parent;
 mod parent_transform;
+mod parent_transform2;
 mod position;
+mod position2;
 mod previous_parent;
+mod previous_world_transform;
 mod rotation;
+mod rotation2;
+mod scale;
+mod scale2;
+mod sibling_index;
+mod world_aabb;
+mod world_bounding_sphere;
 mod world_transform;
+mod world_transform2;
 
+pub use self::aabb::Aabb;
+pub use self::bounding_sphere::BoundingSphere;
 pub use self::children::Children;
+pub use self::floating_origin_target::FloatingOriginTarget;
+pub use self::hierarchical_bounds::HierarchicalBounds;
+pub use self::interpolated_transform::InterpolatedTransform;
+pub use self::orphan_policy::OrphanPolicy;
 pub use self::parent::Parent;
 pub use self::parent_transform::ParentTransform;
+pub use self::parent_transform2::ParentTransform2;
 pub use self::position::Position;
+pub use self::position2::Position2;
 pub use self::previous_parent::PreviousParent;
+pub use self::previous_world_transform::PreviousWorldTransform;
 pub use self::rotation::{Rotation, SerializedRotation};
+pub use self::rotation2::Rotation2;
+pub use self::scale::Scale;
+pub use self::scale2::Scale2;
+pub use self::sibling_index::SiblingIndex;
+pub use self::world_aabb::WorldAabb;
+pub use self::world_bounding_sphere::WorldBoundingSphere;
 pub use self::world_transform::WorldTransform;
+pub use self::world_transform2::WorldTransform2;
+use crate::core::registry::{ComponentRegistration, ComponentRegistry};
+
+/// Register all components of this crate to the given registry
+///
+/// The components are registered with their type names prefixed with `transform::`, e.g.
+/// `transform::Position`. The components that the systems compute from the others are registered
+/// as derived components, like [`Children`] and [`PreviousParent`] that [`HierarchySync`] system
+/// rebuilds from [`Parent`], and [`WorldTransform`] and [`ParentTransform`] that the transform
+/// systems compute from the local transforms. [`Parent`], [`Children`], and [`PreviousParent`]
+/// refer to other entities, so they are (de)serialized with an [`EntityMapper`].
+///
+/// [`Children`]: ./struct.Children.html
+/// [`PreviousParent`]: ./struct.PreviousParent.html
+/// [`Parent`]: ./struct.Parent.html
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+/// [`WorldTransform`]: ./struct.WorldTransform.html
+/// [`ParentTransform`]: ./struct.ParentTransform.html
+/// [`EntityMapper`]: ../../game_engine_core/entity_serde/trait.EntityMapper.html
+pub fn register_components(registry: &mut ComponentRegistry) {
+    registry
+        .register::<Position>("transform::Position")
+        .register::<Rotation>("transform::Rotation")
+        .register::<Scale>("transform::Scale")
+        .register_mapped::<Parent>("transform::Parent")
+        .add_registration(
+            ComponentRegistration::of_mapped::<Children>("transform::Children").derived(),
+        )
+        .add_registration(
+            ComponentRegistration::of_mapped::<PreviousParent>("transform::PreviousParent")
+                .derived(),
+        )
+        .register::<SiblingIndex>("transform::SiblingIndex")
+        .register::<OrphanPolicy>("transform::OrphanPolicy")
+        .register::<FloatingOriginTarget>("transform::FloatingOriginTarget")
+        .register_derived::<ParentTransform>("transform::ParentTransform")
+        .register_derived::<WorldTransform>("transform::WorldTransform")
+        .register_derived::<PreviousWorldTransform>("transform::PreviousWorldTransform")
+        .register_derived::<InterpolatedTransform>("transform::InterpolatedTransform")
+        .register::<Aabb>("transform::Aabb")
+        .register::<BoundingSphere>("transform::BoundingSphere")
+        .register::<HierarchicalBounds>("transform::HierarchicalBounds")
+        .register_derived::<WorldAabb>("transform::WorldAabb")
+        .register_derived::<WorldBoundingSphere>("transform::WorldBoundingSphere")
+        .register::<Position2>("transform::Position2")
+        .register::<Rotation2>("transform::Rotation2")
+        .register::<Scale2>("transform::Scale2")
+        .register_derived::<ParentTransform2>("transform::ParentTransform2")
+        .register_derived::<WorldTransform2>("transform::WorldTransform2");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene;
+    use crate::ecs::world::World;
+
+    #[test]
+    fn all_components_registered() {
+        let mut registry = ComponentRegistry::new();
+        register_components(&mut registry);
+        register_components(&mut registry);
+
+        assert_eq!(registry.len(), 23);
+        assert_eq!(
+            registry.get_by_type::<WorldTransform>().unwrap().name(),
+            "transform::WorldTransform"
+        );
+
+        let derived = registry
+            .iter()
+            .filter(|r| r.is_derived())
+            .map(|r| r.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            derived,
+            [
+                "transform::Children",
+                "transform::PreviousParent",
+                "transform::ParentTransform",
+                "transform::WorldTransform",
+                "transform::PreviousWorldTransform",
+                "transform::InterpolatedTransform",
+                "transform::WorldAabb",
+                "transform::WorldBoundingSphere",
+                "transform::ParentTransform2",
+                "transform::WorldTransform2",
+            ]
+        );
+    }
+
+    #[test]
+    fn hierarchy_components_use_entity_mapper() {
+        let mut registry = ComponentRegistry::new();
+        register_components(&mut registry);
+        let parent = registry.get("transform::Parent").unwrap();
+
+        let mut world = World::new();
+        let root = world.insert((), vec![(Position::zero(), PreviousParent::new())])[0];
+        let child = world.insert((), vec![(Parent::new(root),)])[0];
+        world.add_component(root, Children::from_single(child));
+
+        // Not allowed without a mapper
+        let serialized = parent.serialize(&world, child).unwrap();
+        assert!(serde_json::to_string(&serialized).is_err());
+        assert!(parent
+            .deserialize(&mut serde_json::Deserializer::from_str("1"))
+            .is_err());
+
+        // The derived components are registered on their own, since scenes do not save them otherwise
+        let mut hierarchy = ComponentRegistry::new();
+        hierarchy
+            .register_mapped::<Parent>("transform::Parent")
+            .register_mapped::<Children>("transform::Children")
+            .register_mapped::<PreviousParent>("transform::PreviousParent");
+        let json = scene::save_json(&world, &hierarchy, &[root, child]).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let components = |index: usize| saved["entities"][index]["components"].clone();
+        assert_eq!(components(0)["transform::Children"], serde_json::json!([1]));
+        assert_eq!(
+            components(0)["transform::PreviousParent"],
+            serde_json::Value::Null
+        );
+        assert_eq!(components(1)["transform::Parent"], serde_json::json!(0));
+
+        let loaded = scene::load_json(&mut world, &hierarchy, &json).unwrap();
+        assert_eq!(
+            *world.get_component::<Parent>(loaded[1]).unwrap(),
+            Parent::new(loaded[0])
+        );
+        assert_eq!(
+            world
+                .get_component::<Children>(loaded[0])
+                .unwrap()
+                .as_slice(),
+            &[loaded[1]]
+        );
+    }
+
+    #[test]
+    fn children_rebuilt_after_scene_load() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::TransformBundle;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let root = world.insert((), vec![(Position::zero(),)])[0];
+        let child = world.insert((), vec![(Position::zero(), Parent::new(root))])[0];
+        schedule.execute(&mut world);
+        assert!(world.get_component::<Children>(root).is_some());
+
+        let registry = world.resources.get::<ComponentRegistry>().unwrap().clone();
+        let ron = scene::save_ron(&world, &registry, &[root, child]).unwrap();
+        assert!(!ron.contains("transform::Children"));
+
+        let loaded = scene::load_ron(&mut world, &registry, &ron).unwrap();
+        schedule.execute(&mut world);
+
+        let parent = *world.get_component::<Parent>(loaded[1]).unwrap();
+        assert_eq!(parent.entity(), loaded[0]);
+        let children = world.get_component::<Children>(loaded[0]).unwrap().clone();
+        assert_eq!(children.as_slice(), &[loaded[1]]);
+    }
+}
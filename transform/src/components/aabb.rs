@@ -0,0 +1,174 @@
+use crate::math::{Matrix3, Matrix4, Point3, Vector3, U3};
+use serde::{Deserialize, Serialize};
+
+/// Component for the axis-aligned bounding box of this entity in its local space
+///
+/// The world-space box is written to [`WorldAabb`] component by [`BoundsUpdate`] system.
+///
+/// # Serialization
+/// This struct is represented as `(min: [f32; 3], max: [f32; 3])` when being (de)serialized.
+///
+/// [`WorldAabb`]: ./struct.WorldAabb.html
+/// [`BoundsUpdate`]: ../systems/fn.build_bounds_update_system.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "aabb_serde::SerializedAabb",
+    into = "aabb_serde::SerializedAabb"
+)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates
+    pub min: Point3<f32>,
+    /// The corner with the largest coordinates
+    pub max: Point3<f32>,
+}
+
+impl Aabb {
+    /// Create a new instance spanning the given two corners, which can be in any order
+    pub fn new(a: Point3<f32>, b: Point3<f32>) -> Aabb {
+        Aabb {
+            min: a.inf(&b),
+            max: a.sup(&b),
+        }
+    }
+
+    /// Create a new instance from its center and the half of its size along each axis
+    pub fn from_center_half_extents(center: Point3<f32>, half_extents: Vector3<f32>) -> Aabb {
+        let half_extents = half_extents.abs();
+        Aabb {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    /// Create the smallest instance that contains all the given points, or `None` if there are no points
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Point3<f32>>>(points: I) -> Option<Aabb> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb::new(*first, *first), |aabb, point| {
+            aabb.union(&Aabb::new(*point, *point))
+        }))
+    }
+
+    /// Get the center of the box
+    pub fn center(&self) -> Point3<f32> {
+        crate::math::center(&self.min, &self.max)
+    }
+
+    /// Get the half of the size of the box along each axis
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) / 2.0
+    }
+
+    /// Get the size of the box along each axis
+    pub fn size(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+
+    /// Check whether the given point is inside the box or on its surface
+    pub fn contains_point(&self, point: &Point3<f32>) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Check whether the two boxes overlap or touch each other
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// Get the smallest box that contains both boxes
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// Get the smallest axis-aligned box that contains this box transformed by the given matrix
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> Aabb {
+        let basis = matrix.fixed_slice::<U3, U3>(0, 0);
+        let abs_basis = Matrix3::from_fn(|row, column| basis[(row, column)].abs());
+        Aabb::from_center_half_extents(
+            matrix.transform_point(&self.center()),
+            abs_basis * self.half_extents(),
+        )
+    }
+}
+
+mod aabb_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub struct SerializedAabb {
+        min: [f32; 3],
+        max: [f32; 3],
+    }
+
+    impl From<SerializedAabb> for Aabb {
+        fn from(SerializedAabb { min, max }: SerializedAabb) -> Self {
+            Aabb::new(min.into(), max.into())
+        }
+    }
+
+    impl From<Aabb> for SerializedAabb {
+        fn from(Aabb { min, max }: Aabb) -> Self {
+            SerializedAabb {
+                min: [min.x, min.y, min.z],
+                max: [max.x, max.y, max.z],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aabb;
+    use crate::math::{Matrix4, Point3, Vector3};
+    use std::error::Error;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn aabb_operations() {
+        let aabb = Aabb::new(Point3::new(1.0, 2.0, 3.0), Point3::new(-1.0, 0.0, 1.0));
+        assert_eq!(aabb.min, Point3::new(-1.0, 0.0, 1.0));
+        assert_eq!(aabb.center(), Point3::new(0.0, 1.0, 2.0));
+        assert_eq!(aabb.half_extents(), Vector3::new(1.0, 1.0, 1.0));
+        assert!(aabb.contains_point(&Point3::new(1.0, 1.0, 1.0)));
+        assert!(!aabb.contains_point(&Point3::new(1.0, 1.0, 0.0)));
+
+        let other =
+            Aabb::from_center_half_extents(Point3::new(2.0, 1.0, 2.0), Vector3::new(1.0, 1.0, 1.0));
+        assert!(aabb.intersects(&other));
+        assert_eq!(
+            aabb.union(&other),
+            Aabb::new(Point3::new(-1.0, 0.0, 1.0), Point3::new(3.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            Aabb::from_points(&[Point3::new(3.0, 2.0, 3.0), Point3::new(-1.0, 0.0, 1.0)]),
+            Some(aabb.union(&other))
+        );
+
+        // Rotate a quarter turn around z axis, scale by 2, and move along x axis
+        let matrix = Matrix4::new_translation(&Vector3::new(10.0, 0.0, 0.0))
+            * Matrix4::new_rotation(Vector3::z() * FRAC_PI_2)
+            * Matrix4::new_scaling(2.0);
+        let transformed =
+            Aabb::from_center_half_extents(Point3::origin(), Vector3::new(1.0, 2.0, 3.0))
+                .transformed(&matrix);
+        assert!((transformed.center() - Point3::new(10.0, 0.0, 0.0)).norm() < 1e-5);
+        assert!((transformed.half_extents() - Vector3::new(4.0, 2.0, 6.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn aabb_serde() -> Result<(), Box<dyn Error>> {
+        let aabb: Aabb = ron::de::from_str("(min: (-1, -1, -1), max: (1, 2, 3))")?;
+        assert_eq!(
+            aabb,
+            Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            serde_json::to_string(&aabb)?,
+            r#"{"min":[-1.0,-1.0,-1.0],"max":[1.0,2.0,3.0]}"#
+        );
+        Ok(())
+    }
+}
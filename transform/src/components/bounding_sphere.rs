@@ -0,0 +1,137 @@
+use crate::math::{Matrix4, Point3, Vector3};
+use serde::{Deserialize, Serialize};
+
+/// Component for the bounding sphere of this entity in its local space
+///
+/// The world-space sphere is written to [`WorldBoundingSphere`] component by [`BoundsUpdate`] system.
+///
+/// # Serialization
+/// This struct is represented as `(center: [f32; 3], radius: f32)` when being (de)serialized.
+///
+/// [`WorldBoundingSphere`]: ./struct.WorldBoundingSphere.html
+/// [`BoundsUpdate`]: ../systems/fn.build_bounds_update_system.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "bounding_sphere_serde::SerializedBoundingSphere",
+    into = "bounding_sphere_serde::SerializedBoundingSphere"
+)]
+pub struct BoundingSphere {
+    /// The center of the sphere
+    pub center: Point3<f32>,
+    /// The radius of the sphere, which is never negative
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    /// Create a new instance with the given center and radius
+    pub fn new(center: Point3<f32>, radius: f32) -> BoundingSphere {
+        BoundingSphere {
+            center,
+            radius: radius.abs(),
+        }
+    }
+
+    /// Check whether the given point is inside the sphere or on its surface
+    pub fn contains_point(&self, point: &Point3<f32>) -> bool {
+        (point - self.center).norm_squared() <= self.radius * self.radius
+    }
+
+    /// Check whether the two spheres overlap or touch each other
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let radii = self.radius + other.radius;
+        (other.center - self.center).norm_squared() <= radii * radii
+    }
+
+    /// Get the smallest sphere that contains both spheres
+    pub fn union(&self, other: &BoundingSphere) -> BoundingSphere {
+        let offset = other.center - self.center;
+        let distance = offset.norm();
+        if distance + other.radius <= self.radius {
+            *self
+        } else if distance + self.radius <= other.radius {
+            *other
+        } else {
+            let radius = (distance + self.radius + other.radius) / 2.0;
+            BoundingSphere {
+                center: self.center + offset * ((radius - self.radius) / distance),
+                radius,
+            }
+        }
+    }
+
+    /// Get the sphere that contains this sphere transformed by the given matrix
+    ///
+    /// The radius is scaled by the largest scale along the axes, so the sphere is loose for non-uniform scales.
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> BoundingSphere {
+        let scale = (0..3)
+            .map(|column| {
+                Vector3::new(
+                    matrix[(0, column)],
+                    matrix[(1, column)],
+                    matrix[(2, column)],
+                )
+                .norm()
+            })
+            .fold(0.0, f32::max);
+        BoundingSphere {
+            center: matrix.transform_point(&self.center),
+            radius: self.radius * scale,
+        }
+    }
+}
+
+mod bounding_sphere_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    pub struct SerializedBoundingSphere {
+        center: [f32; 3],
+        radius: f32,
+    }
+
+    impl From<SerializedBoundingSphere> for BoundingSphere {
+        fn from(SerializedBoundingSphere { center, radius }: SerializedBoundingSphere) -> Self {
+            BoundingSphere::new(center.into(), radius)
+        }
+    }
+
+    impl From<BoundingSphere> for SerializedBoundingSphere {
+        fn from(BoundingSphere { center, radius }: BoundingSphere) -> Self {
+            SerializedBoundingSphere {
+                center: [center.x, center.y, center.z],
+                radius,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundingSphere;
+    use crate::math::{Matrix4, Point3, Vector3};
+
+    #[test]
+    fn bounding_sphere_operations() {
+        let sphere = BoundingSphere::new(Point3::origin(), 1.0);
+        assert!(sphere.contains_point(&Point3::new(0.0, 1.0, 0.0)));
+        assert!(!sphere.contains_point(&Point3::new(1.0, 1.0, 0.0)));
+
+        let other = BoundingSphere::new(Point3::new(3.0, 0.0, 0.0), 1.0);
+        assert!(!sphere.intersects(&other));
+        assert_eq!(
+            sphere.union(&other),
+            BoundingSphere::new(Point3::new(1.5, 0.0, 0.0), 2.5)
+        );
+        let inner = BoundingSphere::new(Point3::new(0.5, 0.0, 0.0), 0.5);
+        assert_eq!(sphere.union(&inner), sphere);
+        assert_eq!(inner.union(&sphere), sphere);
+
+        let matrix = Matrix4::new_translation(&Vector3::new(0.0, 5.0, 0.0))
+            * Matrix4::new_nonuniform_scaling(&Vector3::new(1.0, 3.0, 2.0));
+        assert_eq!(
+            sphere.transformed(&matrix),
+            BoundingSphere::new(Point3::new(0.0, 5.0, 0.0), 3.0)
+        );
+    }
+}
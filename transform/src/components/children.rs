@@ -1,4 +1,6 @@
+use crate::core::entity_serde::{EntityMapper, MapEntities, Mapped};
 use crate::ecs::entity::Entity;
+use serde::{Deserializer, Serializer};
 use smallvec::{smallvec, SmallVec};
 use std::ops::{Deref, DerefMut};
 
@@ -9,10 +11,16 @@ const CHILDREN_SIZE: usize = 4;
 /// This component is not in sync when the user has made changes. It is only in sync when [`HierarchySync`] system is
 /// run. Thus, the data in this component should **NOT** be trusted by the end users.
 ///
-/// This component is used in [`HierarchicalTransformUpdate`] system.
+/// The children are kept in the order given by their [`SiblingIndex`] components, followed by those without one in the
+/// order they were attached.
+///
+/// This component is used in [`HierarchicalTransformUpdate`] system. To walk the hierarchy, use the functions in
+/// [`hierarchy`] module instead of reading this component directly.
 ///
 /// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
 /// [`HierarchicalTransformUpdate`]: ../systems/fn.build_hierarchical_transform_update_system.html
+/// [`hierarchy`]: ../hierarchy/index.html
+/// [`SiblingIndex`]: ./struct.SiblingIndex.html
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Children(SmallVec<[Entity; CHILDREN_SIZE]>);
 
@@ -52,6 +60,23 @@ impl From<&[Entity]> for Children {
     }
 }
 
+impl MapEntities for Children {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|entity| Mapped::new(entity, mapper)))
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Vec::<Entity>::deserialize_mapped(mapper, deserializer).map(|e| Children(e.into()))
+    }
+}
+
 impl Deref for Children {
     type Target = SmallVec<[Entity; CHILDREN_SIZE]>;
 
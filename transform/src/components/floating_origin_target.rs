@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Marker component for the entity that the floating origin follows, usually the camera or the player
+///
+/// When this entity gets farther from the origin than the threshold of [`FloatingOrigin`] resource, the system built by
+/// [`build_floating_origin_system`] moves the whole world so that this entity is at the origin again. If more than one
+/// entity has this component, only one of them is followed.
+///
+/// [`FloatingOrigin`]: ../floating_origin/struct.FloatingOrigin.html
+/// [`build_floating_origin_system`]: ../systems/fn.build_floating_origin_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct FloatingOriginTarget;
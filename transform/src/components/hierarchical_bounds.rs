@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Marker component for the entities whose world-space bounds also cover all of their descendants
+///
+/// [`BoundsUpdate`] system writes the union of the bounds of this entity and all of its descendants, found through
+/// [`Children`] components, to [`WorldAabb`] and [`WorldBoundingSphere`] components of this entity. The entity itself
+/// does not need to have its own [`Aabb`] or [`BoundingSphere`].
+///
+/// [`BoundsUpdate`]: ../systems/fn.build_bounds_update_system.html
+/// [`Children`]: ./struct.Children.html
+/// [`WorldAabb`]: ./struct.WorldAabb.html
+/// [`WorldBoundingSphere`]: ./struct.WorldBoundingSphere.html
+/// [`Aabb`]: ./struct.Aabb.html
+/// [`BoundingSphere`]: ./struct.BoundingSphere.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct HierarchicalBounds;
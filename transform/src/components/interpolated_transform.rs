@@ -0,0 +1,76 @@
+use super::matrix4_wrapper::SerializedMatrix4;
+use crate::math::Matrix4;
+use serde::{Deserialize, Serialize};
+
+/// Transform of this entity relative to the world origin, blended between the last two simulation steps
+///
+/// This is written by [`TransformInterpolation`] system, and is meant to be used for rendering instead of
+/// [`WorldTransform`] when the simulation runs at a lower rate than rendering.
+///
+/// [`TransformInterpolation`]: ../systems/fn.build_transform_interpolation_system.html
+/// [`WorldTransform`]: ./struct.WorldTransform.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "SerializedMatrix4", into = "SerializedMatrix4")]
+pub struct InterpolatedTransform(Matrix4<f32>);
+
+impl Default for InterpolatedTransform {
+    fn default() -> Self {
+        InterpolatedTransform(Matrix4::identity())
+    }
+}
+
+impl InterpolatedTransform {
+    /// Create a new instance with identity values
+    pub fn new() -> InterpolatedTransform {
+        InterpolatedTransform::default()
+    }
+
+    /// Create a new instance with identity values
+    pub fn identity() -> InterpolatedTransform {
+        InterpolatedTransform::new()
+    }
+
+    /// Get an immutable reference to the matrix that is stored inside
+    pub fn matrix(&self) -> &Matrix4<f32> {
+        &self.0
+    }
+}
+
+mod interpolated_transform_conversion {
+    use super::InterpolatedTransform;
+    use crate::components::WorldTransform;
+    use crate::math::Matrix4;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<Matrix4<f32>> for InterpolatedTransform {
+        fn from(matrix: Matrix4<f32>) -> Self {
+            InterpolatedTransform(matrix)
+        }
+    }
+
+    impl From<InterpolatedTransform> for Matrix4<f32> {
+        fn from(value: InterpolatedTransform) -> Self {
+            value.0
+        }
+    }
+
+    impl From<WorldTransform> for InterpolatedTransform {
+        fn from(value: WorldTransform) -> Self {
+            InterpolatedTransform(value.into())
+        }
+    }
+
+    impl Deref for InterpolatedTransform {
+        type Target = Matrix4<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for InterpolatedTransform {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use super::{ParentTransform, Position, Rotation, WorldTransform};
-use crate::math::Matrix4;
+use super::{
+    ParentTransform, ParentTransform2, Position, Position2, Rotation, Rotation2, Scale, Scale2,
+    WorldTransform, WorldTransform2,
+};
+use crate::math::{Matrix3, Matrix4};
 use std::ops::Mul;
 
 impl Mul<Position> for Rotation {
@@ -34,22 +37,61 @@ impl Mul<WorldTransform> for WorldTransform {
     }
 }
 
+impl Mul<Position2> for Rotation2 {
+    type Output = Matrix3<f32>;
+
+    fn mul(self, rhs: Position2) -> Self::Output {
+        self.to_homogeneous().append_translation(&rhs.vector)
+    }
+}
+
+impl Mul<ParentTransform2> for WorldTransform2 {
+    type Output = WorldTransform2;
+
+    fn mul(self, rhs: ParentTransform2) -> Self::Output {
+        (self.matrix() * rhs.matrix()).into()
+    }
+}
+
+impl Mul<ParentTransform2> for ParentTransform2 {
+    type Output = ParentTransform2;
+
+    fn mul(self, rhs: ParentTransform2) -> Self::Output {
+        ParentTransform2::from(self.matrix() * rhs.matrix())
+    }
+}
+
+impl Mul<WorldTransform2> for WorldTransform2 {
+    type Output = WorldTransform2;
+
+    fn mul(self, rhs: WorldTransform2) -> Self::Output {
+        WorldTransform2::from(self.matrix() * rhs.matrix())
+    }
+}
+
 macro_rules! impl_froms_for_transforms {
-    ($( $transform:ident ),* $(,)?) => {
+    ($position:ident, $rotation:ident, $scale:ident => $( $transform:ident ),* $(,)?) => {
         $(
-            impl From<Position> for $transform {
-                fn from(pos: Position) -> Self {
+            impl From<$position> for $transform {
+                fn from(pos: $position) -> Self {
                     $transform::from(pos.to_homogeneous())
                 }
             }
 
-            impl From<Rotation> for $transform {
-                fn from(rot: Rotation) -> Self {
+            impl From<$rotation> for $transform {
+                fn from(rot: $rotation) -> Self {
                     $transform::from(rot.to_homogeneous())
                 }
             }
+
+            impl From<$scale> for $transform {
+                fn from(scale: $scale) -> Self {
+                    $transform::from(scale.to_homogeneous())
+                }
+            }
         )*
     };
 }
 
-impl_froms_for_transforms!(ParentTransform, WorldTransform);
+impl_froms_for_transforms!(Position, Rotation, Scale => ParentTransform, WorldTransform);
+impl_froms_for_transforms!(Position2, Rotation2, Scale2 => ParentTransform2, WorldTransform2);
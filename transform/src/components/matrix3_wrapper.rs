@@ -0,0 +1,76 @@
+use super::{ParentTransform2, WorldTransform2};
+use crate::math::Matrix3;
+use serde::{Deserialize, Serialize};
+
+#[allow(missing_copy_implementations, missing_debug_implementations)]
+#[derive(Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct SerializedMatrix3([f32; 9]);
+
+impl From<[f32; 9]> for SerializedMatrix3 {
+    fn from(slice: [f32; 9]) -> Self {
+        SerializedMatrix3(slice)
+    }
+}
+
+impl From<SerializedMatrix3> for WorldTransform2 {
+    fn from(value: SerializedMatrix3) -> Self {
+        matrix3_from_slice(&value.0).into()
+    }
+}
+
+impl From<WorldTransform2> for SerializedMatrix3 {
+    fn from(transform: WorldTransform2) -> Self {
+        slice_from_matrix3(&transform).into()
+    }
+}
+
+impl From<SerializedMatrix3> for ParentTransform2 {
+    fn from(value: SerializedMatrix3) -> Self {
+        matrix3_from_slice(&value.0).into()
+    }
+}
+
+impl From<ParentTransform2> for SerializedMatrix3 {
+    fn from(transform: ParentTransform2) -> Self {
+        slice_from_matrix3(&transform).into()
+    }
+}
+
+fn matrix3_from_slice(slice: &[f32; 9]) -> Matrix3<f32> {
+    Matrix3::from_row_slice(slice)
+}
+
+#[rustfmt::skip]
+fn slice_from_matrix3(m: &Matrix3<f32>) -> [f32; 9] {
+    [
+        m.m11, m.m12, m.m13,
+        m.m21, m.m22, m.m23,
+        m.m31, m.m32, m.m33,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn matrix3_serde() -> Result<(), Box<dyn Error>> {
+        let world_transform = WorldTransform2::identity();
+        assert_eq!(
+            ron::ser::to_string(&world_transform)?,
+            "(1,0,0,0,1,0,0,0,1,)"
+        );
+        let ron_str = r#"(
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        )"#;
+        assert_eq!(
+            ron::de::from_str::<WorldTransform2>(ron_str)?,
+            world_transform
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ParentTransform, WorldTransform};
+use super::{InterpolatedTransform, ParentTransform, PreviousWorldTransform, WorldTransform};
 use crate::math::Matrix4;
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +37,35 @@ impl From<ParentTransform> for SerializedMatrix4 {
     }
 }
 
+impl From<SerializedMatrix4> for InterpolatedTransform {
+    fn from(value: SerializedMatrix4) -> Self {
+        matrix4_from_slice(&value.0).into()
+    }
+}
+
+impl From<InterpolatedTransform> for SerializedMatrix4 {
+    fn from(transform: InterpolatedTransform) -> Self {
+        slice_from_matrix4(&transform).into()
+    }
+}
+
+impl From<Option<SerializedMatrix4>> for PreviousWorldTransform {
+    fn from(value: Option<SerializedMatrix4>) -> Self {
+        match value {
+            Some(value) => matrix4_from_slice(&value.0).into(),
+            None => PreviousWorldTransform::invalid(),
+        }
+    }
+}
+
+impl From<PreviousWorldTransform> for Option<SerializedMatrix4> {
+    fn from(transform: PreviousWorldTransform) -> Self {
+        transform
+            .matrix()
+            .map(|matrix| slice_from_matrix4(matrix).into())
+    }
+}
+
 fn matrix4_from_slice(slice: &[f32; 16]) -> Matrix4<f32> {
     Matrix4::from_row_slice(slice)
 }
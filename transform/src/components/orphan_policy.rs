@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens to an entity when its parent is deleted
+///
+/// This is used both as a resource, which sets the policy for all entities, and as a component, which overrides the
+/// resource for the entity it is attached to. The resource is set by [`TransformBundle`], and [`DeleteRecursive`] is
+/// used if there is none.
+///
+/// The policy is applied by [`HierarchySync`] system. It is also applied to the children of entities that are deleted
+/// because of [`DeleteRecursive`], so each of them can decide on its own.
+///
+/// [`TransformBundle`]: ../systems/struct.TransformBundle.html
+/// [`DeleteRecursive`]: #variant.DeleteRecursive
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum OrphanPolicy {
+    /// Delete the entity
+    ///
+    /// Its children are orphaned in turn, and each of them applies its own policy, so they are only deleted as well if
+    /// their policy is also `DeleteRecursive`.
+    #[default]
+    DeleteRecursive,
+
+    /// Remove the [`Parent`] component so that the entity becomes a root, keeping its local transform components
+    ///
+    /// [`Parent`]: ./struct.Parent.html
+    Detach,
+
+    /// Same as [`Detach`], but also replace the local transform components so that the entity stays where it was in
+    /// the world
+    ///
+    /// [`Detach`]: #variant.Detach
+    DetachKeepWorld,
+
+    /// Attach the entity to the closest ancestor that is still alive, keeping its local transform components
+    ///
+    /// The entity is detached if there is no such ancestor.
+    AttachToGrandparent,
+}
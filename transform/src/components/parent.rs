@@ -1,9 +1,16 @@
+use crate::core::entity_serde::{EntityMapper, MapEntities};
 use crate::ecs::entity::Entity;
+use serde::{Deserializer, Serializer};
 
 /// Component that indicates the entity has a parent
 ///
 /// The entity that is stored in this component is the parent of the entity that has this component. Thus, this
 /// component indicates that the entity **HAS** a parent, not that the entity is a parent of some other entities.
+///
+/// The parent entity is (de)serialized with an [`EntityMapper`], through [`MapEntities`].
+///
+/// [`EntityMapper`]: ../../game_engine_core/entity_serde/trait.EntityMapper.html
+/// [`MapEntities`]: ../../game_engine_core/entity_serde/trait.MapEntities.html
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Parent(Entity);
 
@@ -23,3 +30,20 @@ impl Parent {
         std::mem::replace(&mut self.0, new_parent)
     }
 }
+
+impl MapEntities for Parent {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_mapped(mapper, serializer)
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Entity::deserialize_mapped(mapper, deserializer).map(Parent)
+    }
+}
@@ -0,0 +1,72 @@
+use super::matrix3_wrapper::SerializedMatrix3;
+use crate::math::Matrix3;
+use serde::{Deserialize, Serialize};
+
+/// Transform of this entity relative to its parent in the 2D world, calculated from any of [`Position2`],
+/// [`Rotation2`], and [`Scale2`] components this entity has
+///
+/// This is not in sync with the values from those components before [`ParentTransform2Update`] system is run. Thus,
+/// the end users should not trust this value unless that system is run before.
+///
+/// [`Position2`]: ./struct.Position2.html
+/// [`Rotation2`]: ./struct.Rotation2.html
+/// [`Scale2`]: ./struct.Scale2.html
+/// [`ParentTransform2Update`]: ../systems/fn.build_parent_transform2_update_system.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "SerializedMatrix3", into = "SerializedMatrix3")]
+pub struct ParentTransform2(Matrix3<f32>);
+
+impl Default for ParentTransform2 {
+    fn default() -> Self {
+        ParentTransform2(Matrix3::identity())
+    }
+}
+
+impl ParentTransform2 {
+    /// Create a new instance with identity values
+    pub fn new() -> ParentTransform2 {
+        ParentTransform2::default()
+    }
+
+    /// Create a new instance with identity values
+    pub fn identity() -> ParentTransform2 {
+        ParentTransform2::new()
+    }
+
+    /// Get an immutable reference to the matrix that is stored inside
+    pub fn matrix(&self) -> &Matrix3<f32> {
+        &self.0
+    }
+}
+
+mod parent_transform2_conversion {
+    use super::ParentTransform2;
+    use crate::math::Matrix3;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<Matrix3<f32>> for ParentTransform2 {
+        fn from(matrix: Matrix3<f32>) -> Self {
+            ParentTransform2(matrix)
+        }
+    }
+
+    impl From<ParentTransform2> for Matrix3<f32> {
+        fn from(value: ParentTransform2) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for ParentTransform2 {
+        type Target = Matrix3<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for ParentTransform2 {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::math::Translation2;
+use serde::{Deserialize, Serialize};
+
+/// Component for entities that are located somewhere in the 2D world at the given position
+///
+/// # Serialization
+/// This struct is represented as `[f32; 2]` when being (de)serialized.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "position2_serde::SerializedPosition2",
+    into = "position2_serde::SerializedPosition2"
+)]
+pub struct Position2(Translation2<f32>);
+
+impl Default for Position2 {
+    fn default() -> Self {
+        Position2(Translation2::new(0.0, 0.0))
+    }
+}
+
+impl Position2 {
+    /// Create a new instance with identity value
+    pub fn new() -> Position2 {
+        Position2::default()
+    }
+
+    /// Create a new instance with identity value
+    pub fn zero() -> Position2 {
+        Position2::new()
+    }
+
+    /// Create a new instance with identity value
+    pub fn identity() -> Position2 {
+        Position2::new()
+    }
+
+    /// Create a new instance with the given coordinate values
+    pub fn from_xy(x: f32, y: f32) -> Position2 {
+        Position2(Translation2::new(x, y))
+    }
+
+    /// Create a new instance with the given x coordinate value and the y coordinate value set to zero
+    pub fn from_x(x: f32) -> Position2 {
+        Position2::from_xy(x, 0.0)
+    }
+
+    /// Create a new instance with the given y coordinate value and the x coordinate value set to zero
+    pub fn from_y(y: f32) -> Position2 {
+        Position2::from_xy(0.0, y)
+    }
+}
+
+mod position2_conversion {
+    use super::Position2;
+    use crate::math::{Point2, Translation2, Vector2};
+    use std::ops::{Deref, DerefMut};
+
+    impl From<Vector2<f32>> for Position2 {
+        fn from(value: Vector2<f32>) -> Self {
+            Position2(value.into())
+        }
+    }
+
+    impl From<Position2> for Vector2<f32> {
+        fn from(value: Position2) -> Self {
+            value.0.vector
+        }
+    }
+
+    impl From<Point2<f32>> for Position2 {
+        fn from(value: Point2<f32>) -> Self {
+            Position2(value.coords.into())
+        }
+    }
+
+    impl From<Position2> for Translation2<f32> {
+        fn from(value: Position2) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for Position2 {
+        type Target = Translation2<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for Position2 {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
+
+mod position2_math {
+    use super::Position2;
+    use crate::math::Vector2;
+    use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+    impl Add<Position2> for Position2 {
+        type Output = Position2;
+
+        fn add(self, rhs: Position2) -> Self::Output {
+            self + rhs.0.vector
+        }
+    }
+
+    impl AddAssign<Position2> for Position2 {
+        fn add_assign(&mut self, rhs: Position2) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl Add<Vector2<f32>> for Position2 {
+        type Output = Position2;
+
+        fn add(self, rhs: Vector2<f32>) -> Self::Output {
+            Position2((self.0.vector + rhs).into())
+        }
+    }
+
+    impl AddAssign<Vector2<f32>> for Position2 {
+        fn add_assign(&mut self, rhs: Vector2<f32>) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl Add<[f32; 2]> for Position2 {
+        type Output = Position2;
+
+        fn add(self, [x, y]: [f32; 2]) -> Self::Output {
+            self + Vector2::new(x, y)
+        }
+    }
+
+    impl AddAssign<[f32; 2]> for Position2 {
+        fn add_assign(&mut self, rhs: [f32; 2]) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl Sub<Position2> for Position2 {
+        type Output = Position2;
+
+        fn sub(self, rhs: Position2) -> Self::Output {
+            self - rhs.0.vector
+        }
+    }
+
+    impl SubAssign<Position2> for Position2 {
+        fn sub_assign(&mut self, rhs: Position2) {
+            *self = *self - rhs;
+        }
+    }
+
+    impl Sub<Vector2<f32>> for Position2 {
+        type Output = Position2;
+
+        fn sub(self, rhs: Vector2<f32>) -> Self::Output {
+            Position2((self.0.vector - rhs).into())
+        }
+    }
+
+    impl SubAssign<Vector2<f32>> for Position2 {
+        fn sub_assign(&mut self, rhs: Vector2<f32>) {
+            *self = *self - rhs;
+        }
+    }
+
+    impl Sub<[f32; 2]> for Position2 {
+        type Output = Position2;
+
+        fn sub(self, [x, y]: [f32; 2]) -> Self::Output {
+            self - Vector2::new(x, y)
+        }
+    }
+
+    impl SubAssign<[f32; 2]> for Position2 {
+        fn sub_assign(&mut self, rhs: [f32; 2]) {
+            *self = *self - rhs;
+        }
+    }
+}
+
+mod position2_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    #[serde(transparent)]
+    pub struct SerializedPosition2([f32; 2]);
+
+    impl From<SerializedPosition2> for Position2 {
+        fn from(SerializedPosition2([x, y]): SerializedPosition2) -> Self {
+            Position2::from_xy(x, y)
+        }
+    }
+
+    impl From<Position2> for SerializedPosition2 {
+        fn from(Position2(vec): Position2) -> Self {
+            SerializedPosition2([vec.x, vec.y])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Position2;
+    use std::error::Error;
+
+    #[test]
+    fn position2_serde() -> Result<(), Box<dyn Error>> {
+        let position = Position2::from_xy(1.0, -2.0);
+        assert_eq!(serde_json::to_string(&position)?, "[1.0,-2.0]");
+        assert_eq!(ron::de::from_str::<Position2>("(1.0, -2.0)")?, position);
+        Ok(())
+    }
+}
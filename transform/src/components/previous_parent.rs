@@ -1,4 +1,6 @@
+use crate::core::entity_serde::{EntityMapper, MapEntities};
 use crate::ecs::entity::Entity;
+use serde::{Deserializer, Serializer};
 use std::ops::{Deref, DerefMut};
 
 /// Component that stores the entity that was previously set as the parent of this entity
@@ -48,6 +50,23 @@ impl From<PreviousParent> for Option<Entity> {
     }
 }
 
+impl MapEntities for PreviousParent {
+    fn serialize_mapped<S: Serializer>(
+        &self,
+        mapper: &dyn EntityMapper,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_mapped(mapper, serializer)
+    }
+
+    fn deserialize_mapped<'de, D: Deserializer<'de>>(
+        mapper: &mut dyn EntityMapper,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Option::<Entity>::deserialize_mapped(mapper, deserializer).map(PreviousParent)
+    }
+}
+
 impl Deref for PreviousParent {
     type Target = Option<Entity>;
 
@@ -0,0 +1,67 @@
+use super::matrix4_wrapper::SerializedMatrix4;
+use super::WorldTransform;
+use crate::math::Matrix4;
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of [`WorldTransform`] taken before the last simulation step
+///
+/// This is written by [`PreviousWorldTransformUpdate`] system and used by [`TransformInterpolation`] system to blend
+/// between the last two simulation steps.
+///
+/// A snapshot can be invalid, which makes the entity and its descendants show their current [`WorldTransform`] until
+/// the next snapshot is taken. Replace the snapshot with [`PreviousWorldTransform::invalid`] when an entity is
+/// teleported, so that it does not visibly slide from where it was.
+///
+/// [`WorldTransform`]: ./struct.WorldTransform.html
+/// [`PreviousWorldTransformUpdate`]: ../systems/fn.build_previous_world_transform_update_system.html
+/// [`TransformInterpolation`]: ../systems/fn.build_transform_interpolation_system.html
+/// [`PreviousWorldTransform::invalid`]: #method.invalid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(from = "Option<SerializedMatrix4>", into = "Option<SerializedMatrix4>")]
+pub struct PreviousWorldTransform(Option<Matrix4<f32>>);
+
+impl PreviousWorldTransform {
+    /// Create a new instance with the given snapshot
+    pub fn new(world_transform: WorldTransform) -> PreviousWorldTransform {
+        PreviousWorldTransform(Some(world_transform.into()))
+    }
+
+    /// Create a new instance without a valid snapshot
+    pub fn invalid() -> PreviousWorldTransform {
+        PreviousWorldTransform(None)
+    }
+
+    /// Check if this instance has a valid snapshot
+    pub fn is_valid(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Discard the snapshot, e.g. when the entity is teleported
+    pub fn invalidate(&mut self) {
+        self.0 = None;
+    }
+
+    /// Get an immutable reference to the matrix of the snapshot if it is valid
+    pub fn matrix(&self) -> Option<&Matrix4<f32>> {
+        self.0.as_ref()
+    }
+
+    /// Get the snapshot as a [`WorldTransform`] if it is valid
+    ///
+    /// [`WorldTransform`]: ./struct.WorldTransform.html
+    pub fn world_transform(&self) -> Option<WorldTransform> {
+        self.0.map(WorldTransform::from)
+    }
+}
+
+impl From<Matrix4<f32>> for PreviousWorldTransform {
+    fn from(matrix: Matrix4<f32>) -> Self {
+        PreviousWorldTransform(Some(matrix))
+    }
+}
+
+impl From<WorldTransform> for PreviousWorldTransform {
+    fn from(world_transform: WorldTransform) -> Self {
+        PreviousWorldTransform::new(world_transform)
+    }
+}
@@ -53,6 +53,15 @@ impl Rotation {
     pub fn from_euler(roll: f32, pitch: f32, yaw: f32) -> Rotation {
         Rotation(UnitQuaternion::from_euler_angles(roll, pitch, yaw))
     }
+
+    /// Create a new instance that makes an entity face the given direction
+    ///
+    /// Entities face their local `-Z` axis with `+Y` axis pointing upwards, so the result rotates `-Z` to `direction`
+    /// and `+Y` as close to `up` as possible. `direction` and `up` do not have to be normalized, but they must not be
+    /// collinear.
+    pub fn look_at(direction: Vector3<f32>, up: Vector3<f32>) -> Rotation {
+        Rotation(UnitQuaternion::face_towards(&-direction, &up))
+    }
 }
 
 mod rotation_conversion {
@@ -208,4 +217,15 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn rotation_look_at() {
+        use crate::math::Vector3;
+
+        let rotation = Rotation::look_at(Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 1.0));
+        let forward = rotation.transform_vector(&-Vector3::z());
+        let up = rotation.transform_vector(&Vector3::y());
+        assert!((forward - Vector3::x()).norm() < 1e-6);
+        assert!((up - Vector3::new(0.0, 1.0, 1.0).normalize()).norm() < 1e-6);
+    }
 }
@@ -0,0 +1,130 @@
+use crate::math::{Matrix3, UnitComplex, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// Component for entities that have rotating properties in the 2D world
+///
+/// The rotation is stored as a counterclockwise angle in radians, without being wrapped into any range.
+///
+/// # Serialization
+/// This struct is represented as the angle in radians, a single `f32`, when being (de)serialized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Rotation2(f32);
+
+impl Rotation2 {
+    /// Create a new instance with the identity value
+    pub fn new() -> Rotation2 {
+        Rotation2::default()
+    }
+
+    /// Create a new instance with the identity value
+    pub fn identity() -> Rotation2 {
+        Rotation2::new()
+    }
+
+    /// Create a new instance with the given counterclockwise angle in radians
+    pub fn from_angle(angle: f32) -> Rotation2 {
+        Rotation2(angle)
+    }
+
+    /// Get the counterclockwise angle in radians
+    pub fn angle(&self) -> f32 {
+        self.0
+    }
+
+    /// Set the counterclockwise angle in radians
+    pub fn set_angle(&mut self, angle: f32) {
+        self.0 = angle;
+    }
+
+    /// Get the unit complex number that represents this rotation
+    pub fn to_unit_complex(&self) -> UnitComplex<f32> {
+        UnitComplex::new(self.0)
+    }
+
+    /// Get the homogeneous matrix that represents this rotation
+    pub fn to_homogeneous(&self) -> Matrix3<f32> {
+        self.to_unit_complex().to_homogeneous()
+    }
+
+    /// Rotate the given vector by this rotation
+    pub fn transform_vector(&self, vector: &Vector2<f32>) -> Vector2<f32> {
+        self.to_unit_complex() * vector
+    }
+}
+
+mod rotation2_conversion {
+    use super::Rotation2;
+    use crate::math::UnitComplex;
+
+    impl From<f32> for Rotation2 {
+        fn from(angle: f32) -> Self {
+            Rotation2(angle)
+        }
+    }
+
+    impl From<Rotation2> for f32 {
+        fn from(rotation: Rotation2) -> Self {
+            rotation.0
+        }
+    }
+
+    impl From<UnitComplex<f32>> for Rotation2 {
+        fn from(complex: UnitComplex<f32>) -> Self {
+            Rotation2(complex.angle())
+        }
+    }
+
+    impl From<Rotation2> for UnitComplex<f32> {
+        fn from(rotation: Rotation2) -> Self {
+            rotation.to_unit_complex()
+        }
+    }
+}
+
+mod rotation2_math {
+    use super::Rotation2;
+    use std::ops::{Mul, MulAssign};
+
+    impl Mul<Rotation2> for Rotation2 {
+        type Output = Rotation2;
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        fn mul(self, rhs: Rotation2) -> Self::Output {
+            Rotation2(self.0 + rhs.0)
+        }
+    }
+
+    impl MulAssign<Rotation2> for Rotation2 {
+        fn mul_assign(&mut self, rhs: Rotation2) {
+            *self = *self * rhs;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rotation2;
+    use crate::math::Vector2;
+    use std::error::Error;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn rotation2_serde() -> Result<(), Box<dyn Error>> {
+        assert_eq!(serde_json::to_string(&Rotation2::from_angle(0.5))?, "0.5");
+        assert_eq!(
+            ron::de::from_str::<Rotation2>("0.5")?,
+            Rotation2::from_angle(0.5)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rotation2_math() {
+        let rotation =
+            Rotation2::from_angle(FRAC_PI_2 / 2.0) * Rotation2::from_angle(FRAC_PI_2 / 2.0);
+        assert_eq!(rotation.angle(), FRAC_PI_2);
+        let rotated = rotation.transform_vector(&Vector2::x());
+        assert!((rotated - Vector2::y()).norm() < 1e-6);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::math::{Matrix4, Vector3};
+use serde::{Deserialize, Serialize};
+
+/// Component for entities that are scaled along each of the axes
+///
+/// The scale is applied before [`Rotation`] and [`Position`], i.e. along the local axes of the entity.
+///
+/// # Serialization
+/// This struct is represented as a single `f32` when the scale is uniform, and as `[f32; 3]` otherwise. Both
+/// representations are accepted when being deserialized.
+///
+/// [`Rotation`]: ./struct.Rotation.html
+/// [`Position`]: ./struct.Position.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "scale_serde::SerializedScale",
+    into = "scale_serde::SerializedScale"
+)]
+pub struct Scale(Vector3<f32>);
+
+impl Default for Scale {
+    fn default() -> Self {
+        Scale(Vector3::new(1.0, 1.0, 1.0))
+    }
+}
+
+impl Scale {
+    /// Create a new instance with identity value
+    pub fn new() -> Scale {
+        Scale::default()
+    }
+
+    /// Create a new instance with identity value
+    pub fn identity() -> Scale {
+        Scale::new()
+    }
+
+    /// Create a new instance that scales all of the axes by the given factor
+    pub fn uniform(factor: f32) -> Scale {
+        Scale::from_xyz(factor, factor, factor)
+    }
+
+    /// Create a new instance with the given factors for each of the axes
+    pub fn from_xyz(x: f32, y: f32, z: f32) -> Scale {
+        Scale(Vector3::new(x, y, z))
+    }
+
+    /// Check if all of the axes are scaled by the same factor
+    pub fn is_uniform(&self) -> bool {
+        self.0.x == self.0.y && self.0.y == self.0.z
+    }
+
+    /// Get the homogeneous matrix that represents this scale
+    pub fn to_homogeneous(&self) -> Matrix4<f32> {
+        Matrix4::new_nonuniform_scaling(&self.0)
+    }
+}
+
+mod scale_conversion {
+    use super::Scale;
+    use crate::math::Vector3;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<f32> for Scale {
+        fn from(factor: f32) -> Self {
+            Scale::uniform(factor)
+        }
+    }
+
+    impl From<Vector3<f32>> for Scale {
+        fn from(value: Vector3<f32>) -> Self {
+            Scale(value)
+        }
+    }
+
+    impl From<[f32; 3]> for Scale {
+        fn from([x, y, z]: [f32; 3]) -> Self {
+            Scale::from_xyz(x, y, z)
+        }
+    }
+
+    impl From<Scale> for Vector3<f32> {
+        fn from(value: Scale) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for Scale {
+        type Target = Vector3<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for Scale {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
+
+mod scale_math {
+    use super::Scale;
+    use crate::math::Vector3;
+    use std::ops::{Mul, MulAssign};
+
+    impl Mul<Scale> for Scale {
+        type Output = Scale;
+
+        fn mul(self, rhs: Scale) -> Self::Output {
+            Scale(self.0.component_mul(&rhs.0))
+        }
+    }
+
+    impl MulAssign<Scale> for Scale {
+        fn mul_assign(&mut self, rhs: Scale) {
+            *self = *self * rhs;
+        }
+    }
+
+    impl Mul<f32> for Scale {
+        type Output = Scale;
+
+        fn mul(self, rhs: f32) -> Self::Output {
+            Scale(self.0 * rhs)
+        }
+    }
+
+    impl MulAssign<f32> for Scale {
+        fn mul_assign(&mut self, rhs: f32) {
+            *self = *self * rhs;
+        }
+    }
+
+    impl Mul<Vector3<f32>> for Scale {
+        type Output = Vector3<f32>;
+
+        fn mul(self, rhs: Vector3<f32>) -> Self::Output {
+            self.0.component_mul(&rhs)
+        }
+    }
+}
+
+mod scale_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum SerializedScale {
+        Uniform(f32),
+        NonUniform([f32; 3]),
+    }
+
+    impl From<SerializedScale> for Scale {
+        fn from(ser: SerializedScale) -> Self {
+            match ser {
+                SerializedScale::Uniform(factor) => Scale::uniform(factor),
+                SerializedScale::NonUniform([x, y, z]) => Scale::from_xyz(x, y, z),
+            }
+        }
+    }
+
+    impl From<Scale> for SerializedScale {
+        fn from(scale: Scale) -> Self {
+            if scale.is_uniform() {
+                SerializedScale::Uniform(scale.x)
+            } else {
+                SerializedScale::NonUniform([scale.x, scale.y, scale.z])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scale;
+    use crate::math::Vector3;
+    use std::error::Error;
+
+    #[test]
+    fn scale_serialize() -> Result<(), Box<dyn Error>> {
+        assert_eq!(ron::ser::to_string(&Scale::uniform(2.0))?, "2");
+        assert_eq!(
+            serde_json::to_string(&Scale::from_xyz(1.0, 2.0, 3.0))?,
+            "[1.0,2.0,3.0]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scale_deserialize() -> Result<(), Box<dyn Error>> {
+        assert_eq!(ron::de::from_str::<Scale>("2.0")?, Scale::uniform(2.0));
+        assert_eq!(
+            ron::de::from_str::<Scale>("(1.0, 2.0, 3.0)")?,
+            Scale::from_xyz(1.0, 2.0, 3.0)
+        );
+        assert_eq!(serde_json::from_str::<Scale>("1")?, Scale::identity());
+        assert_eq!(
+            serde_json::from_str::<Scale>("[2, 2, 2]")?,
+            Scale::uniform(2.0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scale_math() {
+        let mut scale = Scale::from_xyz(1.0, 2.0, 3.0) * Scale::uniform(2.0);
+        assert_eq!(scale, Scale::from_xyz(2.0, 4.0, 6.0));
+        scale *= 0.5;
+        assert_eq!(scale, Scale::from([1.0, 2.0, 3.0]));
+        assert_eq!(
+            scale * Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+    }
+}
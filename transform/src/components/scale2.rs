@@ -0,0 +1,195 @@
+use crate::math::{Matrix3, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// Component for entities that are scaled along each of the axes in the 2D world
+///
+/// The scale is applied before [`Rotation2`] and [`Position2`], i.e. along the local axes of the entity.
+///
+/// # Serialization
+/// This struct is represented as a single `f32` when the scale is uniform, and as `[f32; 2]` otherwise. Both
+/// representations are accepted when being deserialized.
+///
+/// [`Rotation2`]: ./struct.Rotation2.html
+/// [`Position2`]: ./struct.Position2.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "scale2_serde::SerializedScale2",
+    into = "scale2_serde::SerializedScale2"
+)]
+pub struct Scale2(Vector2<f32>);
+
+impl Default for Scale2 {
+    fn default() -> Self {
+        Scale2(Vector2::new(1.0, 1.0))
+    }
+}
+
+impl Scale2 {
+    /// Create a new instance with identity value
+    pub fn new() -> Scale2 {
+        Scale2::default()
+    }
+
+    /// Create a new instance with identity value
+    pub fn identity() -> Scale2 {
+        Scale2::new()
+    }
+
+    /// Create a new instance that scales both of the axes by the given factor
+    pub fn uniform(factor: f32) -> Scale2 {
+        Scale2::from_xy(factor, factor)
+    }
+
+    /// Create a new instance with the given factors for each of the axes
+    pub fn from_xy(x: f32, y: f32) -> Scale2 {
+        Scale2(Vector2::new(x, y))
+    }
+
+    /// Check if both of the axes are scaled by the same factor
+    pub fn is_uniform(&self) -> bool {
+        self.0.x == self.0.y
+    }
+
+    /// Get the homogeneous matrix that represents this scale
+    pub fn to_homogeneous(&self) -> Matrix3<f32> {
+        Matrix3::new_nonuniform_scaling(&self.0)
+    }
+}
+
+mod scale2_conversion {
+    use super::Scale2;
+    use crate::math::Vector2;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<f32> for Scale2 {
+        fn from(factor: f32) -> Self {
+            Scale2::uniform(factor)
+        }
+    }
+
+    impl From<Vector2<f32>> for Scale2 {
+        fn from(value: Vector2<f32>) -> Self {
+            Scale2(value)
+        }
+    }
+
+    impl From<[f32; 2]> for Scale2 {
+        fn from([x, y]: [f32; 2]) -> Self {
+            Scale2::from_xy(x, y)
+        }
+    }
+
+    impl From<Scale2> for Vector2<f32> {
+        fn from(value: Scale2) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for Scale2 {
+        type Target = Vector2<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for Scale2 {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
+
+mod scale2_math {
+    use super::Scale2;
+    use crate::math::Vector2;
+    use std::ops::{Mul, MulAssign};
+
+    impl Mul<Scale2> for Scale2 {
+        type Output = Scale2;
+
+        fn mul(self, rhs: Scale2) -> Self::Output {
+            Scale2(self.0.component_mul(&rhs.0))
+        }
+    }
+
+    impl MulAssign<Scale2> for Scale2 {
+        fn mul_assign(&mut self, rhs: Scale2) {
+            *self = *self * rhs;
+        }
+    }
+
+    impl Mul<f32> for Scale2 {
+        type Output = Scale2;
+
+        fn mul(self, rhs: f32) -> Self::Output {
+            Scale2(self.0 * rhs)
+        }
+    }
+
+    impl MulAssign<f32> for Scale2 {
+        fn mul_assign(&mut self, rhs: f32) {
+            *self = *self * rhs;
+        }
+    }
+
+    impl Mul<Vector2<f32>> for Scale2 {
+        type Output = Vector2<f32>;
+
+        fn mul(self, rhs: Vector2<f32>) -> Self::Output {
+            self.0.component_mul(&rhs)
+        }
+    }
+}
+
+mod scale2_serde {
+    use super::*;
+
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[derive(Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum SerializedScale2 {
+        Uniform(f32),
+        NonUniform([f32; 2]),
+    }
+
+    impl From<SerializedScale2> for Scale2 {
+        fn from(ser: SerializedScale2) -> Self {
+            match ser {
+                SerializedScale2::Uniform(factor) => Scale2::uniform(factor),
+                SerializedScale2::NonUniform([x, y]) => Scale2::from_xy(x, y),
+            }
+        }
+    }
+
+    impl From<Scale2> for SerializedScale2 {
+        fn from(scale: Scale2) -> Self {
+            if scale.is_uniform() {
+                SerializedScale2::Uniform(scale.x)
+            } else {
+                SerializedScale2::NonUniform([scale.x, scale.y])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scale2;
+    use std::error::Error;
+
+    #[test]
+    fn scale2_serde() -> Result<(), Box<dyn Error>> {
+        assert_eq!(ron::ser::to_string(&Scale2::uniform(2.0))?, "2");
+        assert_eq!(
+            serde_json::to_string(&Scale2::from_xy(1.0, 2.0))?,
+            "[1.0,2.0]"
+        );
+        assert_eq!(
+            ron::de::from_str::<Scale2>("(1.0, 2.0)")?,
+            Scale2::from_xy(1.0, 2.0)
+        );
+        assert_eq!(serde_json::from_str::<Scale2>("1")?, Scale2::identity());
+        Ok(())
+    }
+}
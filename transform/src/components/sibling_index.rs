@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Component that decides where this entity is placed among its siblings in the [`Children`] component of its parent
+///
+/// [`HierarchySync`] system keeps [`Children`] components sorted in ascending order of this component. Siblings with
+/// equal indices, and those without this component, keep the order in which they were attached, with the latter placed
+/// after the former. The index is kept when the entity is moved to another parent, where it is sorted the same way.
+///
+/// The indices do not have to be contiguous. Use [`move_child_to`], [`swap_siblings`], or [`sort_children_by_key`]
+/// to reorder children, which renumber all of the siblings.
+///
+/// [`Children`]: ./struct.Children.html
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+/// [`move_child_to`]: ../hierarchy/fn.move_child_to.html
+/// [`swap_siblings`]: ../hierarchy/fn.swap_siblings.html
+/// [`sort_children_by_key`]: ../hierarchy/fn.sort_children_by_key.html
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct SiblingIndex(pub usize);
+
+impl SiblingIndex {
+    /// Create a new instance with the given index
+    pub fn new(index: usize) -> SiblingIndex {
+        SiblingIndex(index)
+    }
+
+    /// Get the index
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for SiblingIndex {
+    fn from(index: usize) -> Self {
+        SiblingIndex(index)
+    }
+}
+
+impl From<SiblingIndex> for usize {
+    fn from(value: SiblingIndex) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,60 @@
+use super::Aabb;
+use serde::{Deserialize, Serialize};
+
+/// Axis-aligned bounding box of this entity in the world space
+///
+/// This is written by [`BoundsUpdate`] system from [`Aabb`] and [`WorldTransform`] components, and covers the
+/// descendants as well for the entities with [`HierarchicalBounds`] component. All changes to this component will be
+/// overwritten by the system.
+///
+/// [`BoundsUpdate`]: ../systems/fn.build_bounds_update_system.html
+/// [`Aabb`]: ./struct.Aabb.html
+/// [`WorldTransform`]: ./struct.WorldTransform.html
+/// [`HierarchicalBounds`]: ./struct.HierarchicalBounds.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct WorldAabb(Aabb);
+
+impl WorldAabb {
+    /// Create a new instance with the given box
+    pub fn new(aabb: Aabb) -> WorldAabb {
+        WorldAabb(aabb)
+    }
+
+    /// Get an immutable reference to the box that is stored inside
+    pub fn aabb(&self) -> &Aabb {
+        &self.0
+    }
+}
+
+mod world_aabb_conversion {
+    use super::WorldAabb;
+    use crate::components::Aabb;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<Aabb> for WorldAabb {
+        fn from(aabb: Aabb) -> Self {
+            WorldAabb(aabb)
+        }
+    }
+
+    impl From<WorldAabb> for Aabb {
+        fn from(value: WorldAabb) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for WorldAabb {
+        type Target = Aabb;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for WorldAabb {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
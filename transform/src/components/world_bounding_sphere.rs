@@ -0,0 +1,60 @@
+use super::BoundingSphere;
+use serde::{Deserialize, Serialize};
+
+/// Bounding sphere of this entity in the world space
+///
+/// This is written by [`BoundsUpdate`] system from [`BoundingSphere`] and [`WorldTransform`] components, and covers the
+/// descendants as well for the entities with [`HierarchicalBounds`] component. All changes to this component will be
+/// overwritten by the system.
+///
+/// [`BoundsUpdate`]: ../systems/fn.build_bounds_update_system.html
+/// [`BoundingSphere`]: ./struct.BoundingSphere.html
+/// [`WorldTransform`]: ./struct.WorldTransform.html
+/// [`HierarchicalBounds`]: ./struct.HierarchicalBounds.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct WorldBoundingSphere(BoundingSphere);
+
+impl WorldBoundingSphere {
+    /// Create a new instance with the given sphere
+    pub fn new(sphere: BoundingSphere) -> WorldBoundingSphere {
+        WorldBoundingSphere(sphere)
+    }
+
+    /// Get an immutable reference to the sphere that is stored inside
+    pub fn sphere(&self) -> &BoundingSphere {
+        &self.0
+    }
+}
+
+mod world_bounding_sphere_conversion {
+    use super::WorldBoundingSphere;
+    use crate::components::BoundingSphere;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<BoundingSphere> for WorldBoundingSphere {
+        fn from(sphere: BoundingSphere) -> Self {
+            WorldBoundingSphere(sphere)
+        }
+    }
+
+    impl From<WorldBoundingSphere> for BoundingSphere {
+        fn from(value: WorldBoundingSphere) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for WorldBoundingSphere {
+        type Target = BoundingSphere;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for WorldBoundingSphere {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
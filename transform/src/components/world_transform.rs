@@ -1,5 +1,6 @@
 use super::matrix4_wrapper::SerializedMatrix4;
-use crate::math::Matrix4;
+use super::{Position, Rotation, Scale};
+use crate::math::{Matrix3, Matrix4, Point3, UnitQuaternion, Vector3, U3};
 use serde::{Deserialize, Serialize};
 
 /// Transform of this entity relative to the world origin
@@ -36,6 +37,81 @@ impl WorldTransform {
     pub fn matrix(&self) -> &Matrix4<f32> {
         &self.0
     }
+
+    /// Get the position of this entity relative to the world origin
+    pub fn global_position(&self) -> Position {
+        Position::from_xyz(self.0.m14, self.0.m24, self.0.m34)
+    }
+
+    /// Get the rotation of this entity relative to the world axes
+    ///
+    /// If the transform contains a shear, e.g. from a non-uniformly scaled parent with a rotated child, the closest
+    /// rotation is returned.
+    pub fn global_rotation(&self) -> Rotation {
+        let scale = self.global_scale();
+        let mut basis = self.basis();
+        for (i, factor) in scale.iter().enumerate() {
+            if *factor != 0.0 {
+                basis.column_mut(i).unscale_mut(*factor);
+            }
+        }
+        UnitQuaternion::from_matrix(&basis).into()
+    }
+
+    /// Get the scale of this entity along each of its local axes, relative to the world
+    ///
+    /// A mirrored transform is represented by a negative scale along the x axis.
+    pub fn global_scale(&self) -> Scale {
+        let basis = self.basis();
+        let sign = if basis.determinant() < 0.0 { -1.0 } else { 1.0 };
+        Scale::from_xyz(
+            sign * basis.column(0).norm(),
+            basis.column(1).norm(),
+            basis.column(2).norm(),
+        )
+    }
+
+    /// Get the unit vector in world space that this entity is facing, i.e. its local `-Z` axis
+    pub fn forward(&self) -> Vector3<f32> {
+        -self.transform_vector(&Vector3::z()).normalize()
+    }
+
+    /// Get the unit vector in world space that points to the right of this entity, i.e. its local `+X` axis
+    pub fn right(&self) -> Vector3<f32> {
+        self.transform_vector(&Vector3::x()).normalize()
+    }
+
+    /// Get the unit vector in world space that points upwards from this entity, i.e. its local `+Y` axis
+    pub fn up(&self) -> Vector3<f32> {
+        self.transform_vector(&Vector3::y()).normalize()
+    }
+
+    /// Transform the given point from the local space of this entity to the world space
+    pub fn transform_point(&self, point: &Point3<f32>) -> Point3<f32> {
+        self.0.transform_point(point)
+    }
+
+    /// Transform the given vector from the local space of this entity to the world space
+    ///
+    /// Unlike [`transform_point`], the translation is not applied.
+    ///
+    /// [`transform_point`]: #method.transform_point
+    pub fn transform_vector(&self, vector: &Vector3<f32>) -> Vector3<f32> {
+        self.0.transform_vector(vector)
+    }
+
+    /// Transform the given point from the world space to the local space of this entity
+    ///
+    /// Returns `None` if the transform is not invertible, e.g. when the entity is scaled by zero.
+    pub fn inverse_transform_point(&self, point: &Point3<f32>) -> Option<Point3<f32>> {
+        self.0
+            .try_inverse()
+            .map(|inverse| inverse.transform_point(point))
+    }
+
+    fn basis(&self) -> Matrix3<f32> {
+        self.0.fixed_slice::<U3, U3>(0, 0).into_owned()
+    }
 }
 
 mod world_transform_conversion {
@@ -69,3 +145,138 @@ mod world_transform_conversion {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3};
+    use crate::systems::TransformBundle;
+    use std::f32::consts::FRAC_PI_2;
+
+    const EPSILON: f32 = 1e-5;
+    // `angle_to` goes through `acos`, which is imprecise near zero
+    const ANGLE_EPSILON: f32 = 1e-3;
+
+    fn assert_close(actual: Vector3<f32>, expected: Vector3<f32>) {
+        assert!(
+            (actual - expected).norm() < EPSILON,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// Build a root that faces `+X` and is scaled by 2 with a child one unit in front of it
+    fn build_hierarchy() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let root = world.insert(
+            (),
+            Some((
+                Position::from_xyz(1.0, 2.0, 3.0),
+                Rotation::look_at(Vector3::x(), Vector3::y()),
+                Scale::uniform(2.0),
+            )),
+        )[0];
+        let child = world.insert(
+            (),
+            Some((
+                Parent::new(root),
+                Position::from_z(-1.0),
+                Rotation::from_axis_angle(Vector3::y() * FRAC_PI_2),
+                Scale::from_xyz(1.0, 3.0, 1.0),
+            )),
+        )[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        (world, root, child)
+    }
+
+    #[test]
+    fn decompose_hierarchy() {
+        let (world, root, child) = build_hierarchy();
+        let root_transform = *world.get_component::<WorldTransform>(root).unwrap();
+        let child_transform = *world.get_component::<WorldTransform>(child).unwrap();
+
+        assert_close(
+            root_transform.global_position().vector,
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+        assert_close(*root_transform.global_scale(), Vector3::new(2.0, 2.0, 2.0));
+        assert!(
+            root_transform
+                .global_rotation()
+                .angle_to(&Rotation::look_at(Vector3::x(), Vector3::y()))
+                < ANGLE_EPSILON
+        );
+
+        // The child is 2 units (scaled by the root) in front of the root
+        assert_close(
+            child_transform.global_position().vector,
+            Vector3::new(3.0, 2.0, 3.0),
+        );
+        assert_close(*child_transform.global_scale(), Vector3::new(2.0, 6.0, 2.0));
+        // The root faces `+X`, then the child turns left by 90 degrees to face `-Z`
+        assert!(
+            child_transform
+                .global_rotation()
+                .angle_to(&Rotation::identity())
+                < ANGLE_EPSILON
+        );
+    }
+
+    #[test]
+    fn direction_vectors() {
+        let (world, root, child) = build_hierarchy();
+        let root_transform = *world.get_component::<WorldTransform>(root).unwrap();
+        let child_transform = *world.get_component::<WorldTransform>(child).unwrap();
+
+        assert_close(root_transform.forward(), Vector3::x());
+        assert_close(root_transform.right(), Vector3::z());
+        assert_close(root_transform.up(), Vector3::y());
+
+        assert_close(child_transform.forward(), -Vector3::z());
+        assert_close(child_transform.right(), Vector3::x());
+        assert_close(child_transform.up(), Vector3::y());
+    }
+
+    #[test]
+    fn transform_points_and_vectors() {
+        let (world, root, child) = build_hierarchy();
+        let root_transform = *world.get_component::<WorldTransform>(root).unwrap();
+        let child_transform = *world.get_component::<WorldTransform>(child).unwrap();
+
+        // One unit in front of the child in its local space
+        let local = Point3::new(0.0, 0.0, -1.0);
+        let global = child_transform.transform_point(&local);
+        assert_close(global.coords, Vector3::new(3.0, 2.0, 1.0));
+        assert_close(
+            child_transform.transform_vector(&Vector3::y()),
+            Vector3::new(0.0, 6.0, 0.0),
+        );
+        assert_close(
+            child_transform
+                .inverse_transform_point(&global)
+                .unwrap()
+                .coords,
+            local.coords,
+        );
+
+        // The position of the child in the local space of the root is its `Position` component
+        assert_close(
+            root_transform
+                .inverse_transform_point(&child_transform.global_position().vector.into())
+                .unwrap()
+                .coords,
+            Vector3::new(0.0, 0.0, -1.0),
+        );
+
+        let flat = WorldTransform::from(Scale::from_xyz(1.0, 0.0, 1.0));
+        assert_eq!(flat.inverse_transform_point(&Point3::origin()), None);
+    }
+}
@@ -0,0 +1,214 @@
+use super::matrix3_wrapper::SerializedMatrix3;
+use super::{Position2, Rotation2, Scale2};
+use crate::math::{Matrix2, Matrix3, Point2, Vector2, U2};
+use serde::{Deserialize, Serialize};
+
+/// Transform of this entity relative to the 2D world origin
+///
+/// The value of this component is calculated by either [`WorldTransform2Update`] system or
+/// [`HierarchicalTransform2Update`] system depending on whether the entity is at the root of the hierarchy or not.
+/// Thus, the end use should not trust its value before both of those systems are run. All changes to this component
+/// will be overwritten by the systems.
+///
+/// [`WorldTransform2Update`]: ../systems/fn.build_world_transform2_update_system.html
+/// [`HierarchicalTransform2Update`]: ../systems/fn.build_hierarchical_transform2_update_system.html
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "SerializedMatrix3", into = "SerializedMatrix3")]
+pub struct WorldTransform2(Matrix3<f32>);
+
+impl Default for WorldTransform2 {
+    fn default() -> Self {
+        WorldTransform2(Matrix3::identity())
+    }
+}
+
+impl WorldTransform2 {
+    /// Create a new instance with identity values
+    pub fn new() -> WorldTransform2 {
+        WorldTransform2::default()
+    }
+
+    /// Create a new instance with identity values
+    pub fn identity() -> WorldTransform2 {
+        WorldTransform2::new()
+    }
+
+    /// Get an immutable reference to the matrix that is stored inside
+    pub fn matrix(&self) -> &Matrix3<f32> {
+        &self.0
+    }
+
+    /// Get the position of this entity relative to the world origin
+    pub fn global_position(&self) -> Position2 {
+        Position2::from_xy(self.0.m13, self.0.m23)
+    }
+
+    /// Get the rotation of this entity relative to the world axes, from the direction of its local `+X` axis
+    pub fn global_rotation(&self) -> Rotation2 {
+        let x_axis = self.basis().column(0).into_owned();
+        let sign = if self.basis().determinant() < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        Rotation2::from_angle((sign * x_axis.y).atan2(sign * x_axis.x))
+    }
+
+    /// Get the scale of this entity along each of its local axes, relative to the world
+    ///
+    /// A mirrored transform is represented by a negative scale along the x axis.
+    pub fn global_scale(&self) -> Scale2 {
+        let basis = self.basis();
+        let sign = if basis.determinant() < 0.0 { -1.0 } else { 1.0 };
+        Scale2::from_xy(sign * basis.column(0).norm(), basis.column(1).norm())
+    }
+
+    /// Get the unit vector in world space that points to the right of this entity, i.e. its local `+X` axis
+    pub fn right(&self) -> Vector2<f32> {
+        self.transform_vector(&Vector2::x()).normalize()
+    }
+
+    /// Get the unit vector in world space that points upwards from this entity, i.e. its local `+Y` axis
+    pub fn up(&self) -> Vector2<f32> {
+        self.transform_vector(&Vector2::y()).normalize()
+    }
+
+    /// Transform the given point from the local space of this entity to the world space
+    pub fn transform_point(&self, point: &Point2<f32>) -> Point2<f32> {
+        self.0.transform_point(point)
+    }
+
+    /// Transform the given vector from the local space of this entity to the world space
+    ///
+    /// Unlike [`transform_point`], the translation is not applied.
+    ///
+    /// [`transform_point`]: #method.transform_point
+    pub fn transform_vector(&self, vector: &Vector2<f32>) -> Vector2<f32> {
+        self.0.transform_vector(vector)
+    }
+
+    /// Transform the given point from the world space to the local space of this entity
+    ///
+    /// Returns `None` if the transform is not invertible, e.g. when the entity is scaled by zero.
+    pub fn inverse_transform_point(&self, point: &Point2<f32>) -> Option<Point2<f32>> {
+        self.0
+            .try_inverse()
+            .map(|inverse| inverse.transform_point(point))
+    }
+
+    fn basis(&self) -> Matrix2<f32> {
+        self.0.fixed_slice::<U2, U2>(0, 0).into_owned()
+    }
+}
+
+mod world_transform2_conversion {
+    use super::WorldTransform2;
+    use crate::math::Matrix3;
+    use std::ops::{Deref, DerefMut};
+
+    impl From<Matrix3<f32>> for WorldTransform2 {
+        fn from(matrix: Matrix3<f32>) -> Self {
+            WorldTransform2(matrix)
+        }
+    }
+
+    impl From<WorldTransform2> for Matrix3<f32> {
+        fn from(value: WorldTransform2) -> Self {
+            value.0
+        }
+    }
+
+    impl Deref for WorldTransform2 {
+        type Target = Matrix3<f32>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for WorldTransform2 {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point2, Vector2};
+    use crate::systems::Transform2dBundle;
+    use std::f32::consts::FRAC_PI_2;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_close(actual: Vector2<f32>, expected: Vector2<f32>) {
+        assert!(
+            (actual - expected).norm() < EPSILON,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn decompose_hierarchy() {
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(Transform2dBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        // The root faces `+Y` and is scaled by 2, with a child one unit to its right
+        let root = world.insert(
+            (),
+            Some((
+                Position2::from_xy(1.0, 2.0),
+                Rotation2::from_angle(FRAC_PI_2),
+                Scale2::uniform(2.0),
+            )),
+        )[0];
+        let child = world.insert(
+            (),
+            Some((
+                Parent::new(root),
+                Position2::from_x(1.0),
+                Rotation2::from_angle(-FRAC_PI_2),
+                Scale2::from_xy(1.0, 3.0),
+            )),
+        )[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let root_transform = *world.get_component::<WorldTransform2>(root).unwrap();
+        let child_transform = *world.get_component::<WorldTransform2>(child).unwrap();
+
+        assert_close(
+            root_transform.global_position().vector,
+            Vector2::new(1.0, 2.0),
+        );
+        assert!((root_transform.global_rotation().angle() - FRAC_PI_2).abs() < EPSILON);
+        assert_close(*root_transform.global_scale(), Vector2::new(2.0, 2.0));
+        assert_close(root_transform.right(), Vector2::y());
+        assert_close(root_transform.up(), -Vector2::x());
+
+        assert_close(
+            child_transform.global_position().vector,
+            Vector2::new(1.0, 4.0),
+        );
+        assert!(child_transform.global_rotation().angle().abs() < EPSILON);
+        assert_close(*child_transform.global_scale(), Vector2::new(2.0, 6.0));
+
+        let local = Point2::new(0.0, 1.0);
+        let global = child_transform.transform_point(&local);
+        assert_close(global.coords, Vector2::new(1.0, 10.0));
+        assert_close(
+            child_transform
+                .inverse_transform_point(&global)
+                .unwrap()
+                .coords,
+            local.coords,
+        );
+    }
+}
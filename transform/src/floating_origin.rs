@@ -0,0 +1,105 @@
+//! Large world coordinates with a floating origin
+//!
+//! [`Position`] stores `f32` coordinates, which lose precision far away from the origin. The opt-in system built by
+//! [`build_floating_origin_system`] keeps the entity with [`FloatingOriginTarget`] component near the origin by
+//! shifting all root entities when it moves too far. The total shift is kept in [`FloatingOrigin`] resource as `f64`,
+//! so absolute coordinates can be converted to and from local [`Position`]s with [`FloatingOrigin::to_absolute`] and
+//! [`FloatingOrigin::to_local`].
+//!
+//! Each shift writes an [`OriginShifted`] event, so that other subsystems that keep world space coordinates outside of
+//! [`Position`] components, e.g. physics or audio, can move their data by the same amount.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::math::Vector3;
+//! use game_engine::transform::components::Position;
+//! use game_engine::transform::floating_origin::FloatingOrigin;
+//!
+//! let mut origin = FloatingOrigin::new(1000.0);
+//! origin.shift(Vector3::new(5000.0, 0.0, 0.0));
+//!
+//! let absolute = Vector3::new(5001.5, 2.0, 0.0);
+//! let local = origin.to_local(&absolute);
+//! assert_eq!(local, Position::from_xyz(1.5, 2.0, 0.0));
+//! assert_eq!(origin.to_absolute(&local), absolute);
+//! ```
+//!
+//! [`Position`]: ../components/struct.Position.html
+//! [`FloatingOrigin`]: ./struct.FloatingOrigin.html
+//! [`build_floating_origin_system`]: ../systems/fn.build_floating_origin_system.html
+//! [`FloatingOriginTarget`]: ../components/struct.FloatingOriginTarget.html
+//! [`FloatingOrigin::to_absolute`]: ./struct.FloatingOrigin.html#method.to_absolute
+//! [`FloatingOrigin::to_local`]: ./struct.FloatingOrigin.html#method.to_local
+//! [`OriginShifted`]: ./struct.OriginShifted.html
+
+use crate::components::Position;
+use crate::math::Vector3;
+
+/// The default distance from the origin that triggers a shift
+pub const DEFAULT_FLOATING_ORIGIN_THRESHOLD: f32 = 1000.0;
+
+/// ECS resource that keeps the absolute coordinates of the current origin
+///
+/// The resource is inserted with the default threshold by [`build_floating_origin_system`] if it does not exist.
+/// Insert it beforehand to use another threshold.
+///
+/// [`build_floating_origin_system`]: ../systems/fn.build_floating_origin_system.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FloatingOrigin {
+    /// How far the target entity can get from the origin before the world is shifted
+    pub threshold: f32,
+    offset: Vector3<f64>,
+}
+
+impl Default for FloatingOrigin {
+    fn default() -> Self {
+        FloatingOrigin::new(DEFAULT_FLOATING_ORIGIN_THRESHOLD)
+    }
+}
+
+impl FloatingOrigin {
+    /// Create a new instance with the given threshold, with the origin at the absolute origin
+    pub fn new(threshold: f32) -> Self {
+        FloatingOrigin {
+            threshold,
+            offset: Vector3::zeros(),
+        }
+    }
+
+    /// Get the absolute coordinates of the current origin
+    pub fn offset(&self) -> Vector3<f64> {
+        self.offset
+    }
+
+    /// Move the origin by the given local vector
+    ///
+    /// This only updates the offset. The system built by [`build_floating_origin_system`] calls this after moving the
+    /// root entities by the opposite vector.
+    ///
+    /// [`build_floating_origin_system`]: ../systems/fn.build_floating_origin_system.html
+    pub fn shift(&mut self, shift: Vector3<f32>) {
+        self.offset += shift.map(f64::from);
+    }
+
+    /// Convert the local position relative to the current origin to absolute coordinates
+    pub fn to_absolute(&self, position: &Position) -> Vector3<f64> {
+        self.offset + position.vector.map(f64::from)
+    }
+
+    /// Convert the absolute coordinates to the local position relative to the current origin
+    pub fn to_local(&self, absolute: &Vector3<f64>) -> Position {
+        Position::from((absolute - self.offset).map(|value| value as f32))
+    }
+}
+
+/// Event that is written when the origin is shifted by the system built by [`build_floating_origin_system`]
+///
+/// [`build_floating_origin_system`]: ../systems/fn.build_floating_origin_system.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OriginShifted {
+    /// The local vector that the origin moved by, i.e. the opposite of how the root entities moved
+    pub shift: Vector3<f32>,
+    /// The absolute coordinates of the new origin
+    pub offset: Vector3<f64>,
+}
@@ -0,0 +1,744 @@
+//! Traversal of the hierarchy formed by [`Parent`] and [`Children`] components
+//!
+//! The functions in this module work with both [`World`] and [`SubWorld`] through [`HierarchyView`]. When used inside a
+//! system, the system needs read access to [`Parent`] and/or [`Children`] components.
+//!
+//! Upward traversal, i.e. [`ancestors`], [`root_of`], [`depth`], and [`is_descendant_of`], follows [`Parent`]
+//! components, and is always up to date. Downward traversal, i.e. [`descendants`] and [`descendants_breadth_first`],
+//! follows [`Children`] components, which are only in sync after [`HierarchySync`] system is run.
+//!
+//! All traversals visit each entity at most once, so they terminate even if the hierarchy contains a cycle. Deleted
+//! entities are never yielded, and the traversal does not continue through them.
+//!
+//! [`reparent_keep_world`] and [`detach_keep_world`] change the hierarchy through a [`CommandBuffer`] while keeping the
+//! entity where it is in the world. [`move_child_to`], [`swap_siblings`], and [`sort_children_by_key`] change the order
+//! of children the same way, through [`SiblingIndex`] components.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::ecs::prelude::*;
+//! use game_engine::transform::components::*;
+//! use game_engine::transform::hierarchy;
+//!
+//! let mut world = World::new();
+//! let root = world.insert((), Some((Position::zero(),)))[0];
+//! let child = world.insert((), Some((Parent::new(root),)))[0];
+//! let grandchild = world.insert((), Some((Parent::new(child),)))[0];
+//!
+//! assert_eq!(hierarchy::ancestors(&world, grandchild).collect::<Vec<_>>(), vec![child, root]);
+//! assert_eq!(hierarchy::root_of(&world, grandchild), root);
+//! assert_eq!(hierarchy::depth(&world, grandchild), 2);
+//! assert!(hierarchy::is_descendant_of(&world, grandchild, root));
+//! ```
+//!
+//! [`Parent`]: ../components/struct.Parent.html
+//! [`Children`]: ../components/struct.Children.html
+//! [`World`]: ../../legion/world/struct.World.html
+//! [`SubWorld`]: ../../legion/system/struct.SubWorld.html
+//! [`HierarchyView`]: ./trait.HierarchyView.html
+//! [`ancestors`]: ./fn.ancestors.html
+//! [`root_of`]: ./fn.root_of.html
+//! [`depth`]: ./fn.depth.html
+//! [`is_descendant_of`]: ./fn.is_descendant_of.html
+//! [`descendants`]: ./fn.descendants.html
+//! [`descendants_breadth_first`]: ./fn.descendants_breadth_first.html
+//! [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+//! [`reparent_keep_world`]: ./fn.reparent_keep_world.html
+//! [`detach_keep_world`]: ./fn.detach_keep_world.html
+//! [`CommandBuffer`]: ../../legion/command/struct.CommandBuffer.html
+//! [`move_child_to`]: ./fn.move_child_to.html
+//! [`swap_siblings`]: ./fn.swap_siblings.html
+//! [`sort_children_by_key`]: ./fn.sort_children_by_key.html
+//! [`SiblingIndex`]: ../components/struct.SiblingIndex.html
+
+use crate::components::{
+    Children, Parent, Scale, Scale2, SiblingIndex, WorldTransform, WorldTransform2,
+};
+use crate::ecs::command::CommandBuffer;
+use crate::ecs::entity::Entity;
+use crate::ecs::system::SubWorld;
+use crate::ecs::world::World;
+use crate::math::Matrix4;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+/// Tolerance for treating a computed scale as the identity
+const SCALE_EPSILON: f32 = 1e-5;
+
+/// Error in the hierarchy detected by [`HierarchySync`] system, written to the [`Events`] resource
+///
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum HierarchyError {
+    /// Setting the parent of the entity would make it an ancestor of itself, so the change is rejected
+    Cycle {
+        /// The entity whose [`Parent`] component is changed
+        ///
+        /// [`Parent`]: ../components/struct.Parent.html
+        entity: Entity,
+        /// The rejected parent
+        parent: Entity,
+    },
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::Cycle { entity, parent } => write!(
+                f,
+                "setting the parent of {} to {} creates a cycle",
+                entity, parent
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+/// Change in the hierarchy applied by [`HierarchySync`] system, written to the [`EventChannel`] resource
+///
+/// Each event describes one child entity, so a consumer that tracks the children of a parent should treat
+/// [`Reparented`] as the child being removed from the previous parent and added to the new one. Changes that are
+/// rejected by the system, e.g. because they create cycles, do not write any events.
+///
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+/// [`EventChannel`]: ../../shrev/struct.EventChannel.html
+/// [`Reparented`]: #variant.Reparented
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum HierarchyEvent {
+    /// The entity that was a root has been attached to the parent
+    ChildAdded {
+        /// The new parent
+        parent: Entity,
+        /// The attached entity
+        child: Entity,
+    },
+    /// The entity has left the parent, either by becoming a root or by being deleted
+    ///
+    /// This is also written when the parent is deleted and the child is detached according to its [`OrphanPolicy`].
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    ChildRemoved {
+        /// The previous parent
+        parent: Entity,
+        /// The detached entity
+        child: Entity,
+    },
+    /// The entity has been moved from the previous parent to the new one
+    ///
+    /// This is also written when the parent is deleted and the child is attached to its grandparent according to its
+    /// [`OrphanPolicy`].
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    Reparented {
+        /// The previous parent
+        previous_parent: Entity,
+        /// The new parent
+        parent: Entity,
+        /// The moved entity
+        child: Entity,
+    },
+    /// The entity has been deleted because its parent was deleted, according to its [`OrphanPolicy`]
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    DeletedWithParent {
+        /// The deleted parent
+        parent: Entity,
+        /// The deleted entity
+        child: Entity,
+    },
+}
+
+/// Read access to the hierarchy components of entities
+pub trait HierarchyView {
+    /// Check if the given entity is alive
+    fn is_alive(&self, entity: Entity) -> bool;
+
+    /// Get the parent of the given entity from its [`Parent`] component
+    ///
+    /// [`Parent`]: ../components/struct.Parent.html
+    fn parent_of(&self, entity: Entity) -> Option<Entity>;
+
+    /// Get the children of the given entity from its [`Children`] component
+    ///
+    /// [`Children`]: ../components/struct.Children.html
+    fn children_of(&self, entity: Entity) -> Vec<Entity>;
+}
+
+macro_rules! impl_hierarchy_view {
+    ($( $world:ident ),* $(,)?) => {
+        $(
+            impl HierarchyView for $world {
+                fn is_alive(&self, entity: Entity) -> bool {
+                    $world::is_alive(self, entity)
+                }
+
+                fn parent_of(&self, entity: Entity) -> Option<Entity> {
+                    self.get_component::<Parent>(entity).map(|parent| parent.entity())
+                }
+
+                fn children_of(&self, entity: Entity) -> Vec<Entity> {
+                    self.get_component::<Children>(entity)
+                        .map(|children| children.to_vec())
+                        .unwrap_or_default()
+                }
+            }
+        )*
+    };
+}
+
+impl_hierarchy_view!(World, SubWorld);
+
+/// Iterator over the ancestors of an entity, created by [`ancestors`]
+///
+/// [`ancestors`]: ./fn.ancestors.html
+#[derive(Debug)]
+pub struct Ancestors<'a, W: HierarchyView + ?Sized> {
+    world: &'a W,
+    current: Entity,
+    visited: HashSet<Entity>,
+}
+
+impl<'a, W: HierarchyView + ?Sized> Iterator for Ancestors<'a, W> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let parent = self.world.parent_of(self.current)?;
+        if !self.world.is_alive(parent) || !self.visited.insert(parent) {
+            return None;
+        }
+        self.current = parent;
+        Some(parent)
+    }
+}
+
+/// Iterator over the descendants of an entity in depth-first pre-order, created by [`descendants`]
+///
+/// [`descendants`]: ./fn.descendants.html
+#[derive(Debug)]
+pub struct Descendants<'a, W: HierarchyView + ?Sized> {
+    world: &'a W,
+    stack: Vec<Entity>,
+    visited: HashSet<Entity>,
+}
+
+impl<'a, W: HierarchyView + ?Sized> Iterator for Descendants<'a, W> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entity) = self.stack.pop() {
+            if !self.world.is_alive(entity) || !self.visited.insert(entity) {
+                continue;
+            }
+            // Pushed in reverse so that the first child is visited first
+            self.stack
+                .extend(self.world.children_of(entity).into_iter().rev());
+            return Some(entity);
+        }
+        None
+    }
+}
+
+/// Iterator over the descendants of an entity in breadth-first order, created by [`descendants_breadth_first`]
+///
+/// [`descendants_breadth_first`]: ./fn.descendants_breadth_first.html
+#[derive(Debug)]
+pub struct DescendantsBreadthFirst<'a, W: HierarchyView + ?Sized> {
+    world: &'a W,
+    queue: VecDeque<Entity>,
+    visited: HashSet<Entity>,
+}
+
+impl<'a, W: HierarchyView + ?Sized> Iterator for DescendantsBreadthFirst<'a, W> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entity) = self.queue.pop_front() {
+            if !self.world.is_alive(entity) || !self.visited.insert(entity) {
+                continue;
+            }
+            self.queue.extend(self.world.children_of(entity));
+            return Some(entity);
+        }
+        None
+    }
+}
+
+/// Iterate over the ancestors of the given entity, starting from its parent and ending at the root
+pub fn ancestors<W: HierarchyView + ?Sized>(world: &W, entity: Entity) -> Ancestors<'_, W> {
+    let mut visited = HashSet::new();
+    visited.insert(entity);
+    Ancestors {
+        world,
+        current: entity,
+        visited,
+    }
+}
+
+/// Iterate over the descendants of the given entity in depth-first pre-order, not including the entity itself
+pub fn descendants<W: HierarchyView + ?Sized>(world: &W, entity: Entity) -> Descendants<'_, W> {
+    let mut visited = HashSet::new();
+    visited.insert(entity);
+    let mut stack = world.children_of(entity);
+    stack.reverse();
+    Descendants {
+        world,
+        stack,
+        visited,
+    }
+}
+
+/// Iterate over the descendants of the given entity in breadth-first order, not including the entity itself
+pub fn descendants_breadth_first<W: HierarchyView + ?Sized>(
+    world: &W,
+    entity: Entity,
+) -> DescendantsBreadthFirst<'_, W> {
+    let mut visited = HashSet::new();
+    visited.insert(entity);
+    DescendantsBreadthFirst {
+        world,
+        queue: world.children_of(entity).into(),
+        visited,
+    }
+}
+
+/// Get the root of the hierarchy the given entity is in, which is the entity itself if it has no parent
+pub fn root_of<W: HierarchyView + ?Sized>(world: &W, entity: Entity) -> Entity {
+    ancestors(world, entity).last().unwrap_or(entity)
+}
+
+/// Get the number of ancestors of the given entity, which is zero for the root
+pub fn depth<W: HierarchyView + ?Sized>(world: &W, entity: Entity) -> usize {
+    ancestors(world, entity).count()
+}
+
+/// Check if the given entity is a descendant of `ancestor`
+///
+/// An entity is not a descendant of itself.
+pub fn is_descendant_of<W: HierarchyView + ?Sized>(
+    world: &W,
+    entity: Entity,
+    ancestor: Entity,
+) -> bool {
+    ancestors(world, entity).any(|e| e == ancestor)
+}
+
+/// Attach the given entity to `new_parent` without moving it in the world
+///
+/// When the command buffer is written, the [`Position`], [`Rotation`], and [`Scale`] components of the entity are
+/// replaced with the ones that put the entity at its current [`WorldTransform`] relative to the [`WorldTransform`] of
+/// the new parent, and its [`Parent`] component is set to `new_parent`. The next run of [`HierarchySync`] system picks
+/// up the change as usual.
+///
+/// [`WorldTransform`] components are read as of the last run of the transform systems. If the entity has no
+/// [`WorldTransform`], only its [`Parent`] component is set. [`Scale`] component is only added when the entity already
+/// has one or the computed scale is not the identity.
+///
+/// [`Position`]: ../components/struct.Position.html
+/// [`Rotation`]: ../components/struct.Rotation.html
+/// [`Scale`]: ../components/struct.Scale.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`Parent`]: ../components/struct.Parent.html
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+pub fn reparent_keep_world(cmd: &CommandBuffer, entity: Entity, new_parent: Entity) {
+    cmd.exec_mut(move |world| {
+        let parent_transform = world
+            .get_component::<WorldTransform>(new_parent)
+            .map(|t| *t)
+            .unwrap_or_default();
+        match parent_transform.try_inverse() {
+            Some(inverse) => set_local_from_world(world, entity, &inverse),
+            None => log::warn!(
+                "Cannot keep the world transform of {} since its new parent {} has a singular transform",
+                entity,
+                new_parent
+            ),
+        }
+        world.add_component(entity, Parent::new(new_parent));
+    });
+}
+
+/// Detach the given entity from its parent without moving it in the world
+///
+/// When the command buffer is written, the [`Position`], [`Rotation`], and [`Scale`] components of the entity are
+/// replaced with the ones decomposed from its current [`WorldTransform`], and its [`Parent`] component is removed. See
+/// [`reparent_keep_world`] for the details. The [`Position2`], [`Rotation2`], and [`Scale2`] components of 2D entities
+/// are replaced in the same way from [`WorldTransform2`].
+///
+/// [`Position`]: ../components/struct.Position.html
+/// [`Rotation`]: ../components/struct.Rotation.html
+/// [`Scale`]: ../components/struct.Scale.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`Parent`]: ../components/struct.Parent.html
+/// [`reparent_keep_world`]: ./fn.reparent_keep_world.html
+/// [`Position2`]: ../components/struct.Position2.html
+/// [`Rotation2`]: ../components/struct.Rotation2.html
+/// [`Scale2`]: ../components/struct.Scale2.html
+/// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+pub fn detach_keep_world(cmd: &CommandBuffer, entity: Entity) {
+    cmd.exec_mut(move |world| {
+        set_local_from_world(world, entity, &Matrix4::identity());
+        set_local2_from_world2(world, entity);
+        world.remove_component::<Parent>(entity);
+    });
+}
+
+/// Set the local transform components of the entity to `parent_inverse * world_transform`
+fn set_local_from_world(world: &mut World, entity: Entity, parent_inverse: &Matrix4<f32>) {
+    let world_transform = match world.get_component::<WorldTransform>(entity) {
+        Some(t) => *t,
+        None => return,
+    };
+    let local = WorldTransform::from(parent_inverse * world_transform.matrix());
+    let scale = local.global_scale();
+
+    world.add_component(entity, local.global_position());
+    world.add_component(entity, local.global_rotation());
+    let identity_scale = (*scale - *Scale::identity()).norm() < SCALE_EPSILON;
+    if world.get_component::<Scale>(entity).is_some() || !identity_scale {
+        world.add_component(entity, scale);
+    }
+}
+
+/// Set the 2D local transform components of the entity to its `WorldTransform2`
+fn set_local2_from_world2(world: &mut World, entity: Entity) {
+    let world_transform = match world.get_component::<WorldTransform2>(entity) {
+        Some(t) => *t,
+        None => return,
+    };
+    let scale = world_transform.global_scale();
+
+    world.add_component(entity, world_transform.global_position());
+    world.add_component(entity, world_transform.global_rotation());
+    let identity_scale = (*scale - *Scale2::identity()).norm() < SCALE_EPSILON;
+    if world.get_component::<Scale2>(entity).is_some() || !identity_scale {
+        world.add_component(entity, scale);
+    }
+}
+
+/// Move the given entity to the given index among its siblings
+///
+/// When the command buffer is written, the children of the parent of the entity are renumbered with [`SiblingIndex`]
+/// components in their new order, and the [`Children`] component of the parent is updated right away. An index past
+/// the end moves the entity to the end. Nothing happens if the entity has no parent.
+///
+/// The current order is read from the [`Children`] component, so children attached after the last run of
+/// [`HierarchySync`] system are not taken into account.
+///
+/// [`SiblingIndex`]: ../components/struct.SiblingIndex.html
+/// [`Children`]: ../components/struct.Children.html
+/// [`HierarchySync`]: ../systems/fn.build_hierarchy_sync_system.html
+pub fn move_child_to(cmd: &CommandBuffer, entity: Entity, index: usize) {
+    cmd.exec_mut(move |world| {
+        let parent = match world.get_component::<Parent>(entity) {
+            Some(parent) => parent.entity(),
+            None => return,
+        };
+        reorder_children(world, parent, |_, children| {
+            if let Some(position) = children.iter().position(|child| *child == entity) {
+                children.remove(position);
+                children.insert(index.min(children.len()), entity);
+            }
+        });
+    });
+}
+
+/// Swap the places of the given entities among their siblings
+///
+/// This works the same way as [`move_child_to`]. Nothing happens if the entities do not have the same parent.
+///
+/// [`move_child_to`]: ./fn.move_child_to.html
+pub fn swap_siblings(cmd: &CommandBuffer, a: Entity, b: Entity) {
+    cmd.exec_mut(move |world| {
+        let parent_of = |entity| world.get_component::<Parent>(entity).map(|p| p.entity());
+        let parent = match (parent_of(a), parent_of(b)) {
+            (Some(parent_a), Some(parent_b)) if parent_a == parent_b => parent_a,
+            _ => {
+                log::warn!("Cannot swap {} and {} since they are not siblings", a, b);
+                return;
+            }
+        };
+        reorder_children(world, parent, |_, children| {
+            let position_a = children.iter().position(|child| *child == a);
+            let position_b = children.iter().position(|child| *child == b);
+            if let (Some(position_a), Some(position_b)) = (position_a, position_b) {
+                children.swap(position_a, position_b);
+            }
+        });
+    });
+}
+
+/// Sort the children of the given entity by the key extracted from each of them
+///
+/// The sort is stable, and works the same way as [`move_child_to`].
+///
+/// [`move_child_to`]: ./fn.move_child_to.html
+pub fn sort_children_by_key<K, F>(cmd: &CommandBuffer, parent: Entity, key: F)
+where
+    K: Ord,
+    F: Fn(&World, Entity) -> K + 'static,
+{
+    cmd.exec_mut(move |world| {
+        reorder_children(world, parent, |world, children| {
+            children.sort_by_key(|child| key(world, *child));
+        });
+    });
+}
+
+/// Reorder the children of the parent, then renumber their `SiblingIndex` components and update the `Children`
+/// component to match
+fn reorder_children<F>(world: &mut World, parent: Entity, reorder: F)
+where
+    F: FnOnce(&World, &mut Vec<Entity>),
+{
+    let mut children = match world.get_component::<Children>(parent) {
+        Some(children) => children.to_vec(),
+        None => return,
+    };
+    reorder(world, &mut children);
+
+    for (index, child) in children.iter().enumerate() {
+        let index = SiblingIndex::new(index);
+        if world.get_component::<SiblingIndex>(*child).as_deref() != Some(&index) {
+            world.add_component(*child, index);
+        }
+    }
+    if let Some(mut component) = world.get_component_mut::<Children>(parent) {
+        *component = Children::from(&children[..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::prelude::*;
+    use crate::systems::build_hierarchy_sync_system;
+
+    /// Build the following hierarchy and sync its `Children` components
+    ///
+    /// ```text
+    /// root
+    /// ├── a
+    /// │   ├── c
+    /// │   └── d
+    /// └── b
+    ///     └── e
+    /// ```
+    fn build_world() -> (World, [Entity; 6]) {
+        let mut world = World::new();
+        let root = world.insert((), vec![(0u8,)])[0];
+        let a = world.insert((), vec![(Parent::new(root),)])[0];
+        let b = world.insert((), vec![(Parent::new(root),)])[0];
+        let c = world.insert((), vec![(Parent::new(a),)])[0];
+        let d = world.insert((), vec![(Parent::new(a),)])[0];
+        let e = world.insert((), vec![(Parent::new(b),)])[0];
+
+        let system = build_hierarchy_sync_system(&mut world);
+        system.run(&world);
+        system.command_buffer_mut().write(&mut world);
+        (world, [root, a, b, c, d, e])
+    }
+
+    #[test]
+    fn traverse_up() {
+        let (world, [root, a, _, c, _, e]) = build_world();
+
+        assert_eq!(ancestors(&world, c).collect::<Vec<_>>(), vec![a, root]);
+        assert_eq!(ancestors(&world, root).count(), 0);
+        assert_eq!(root_of(&world, e), root);
+        assert_eq!(root_of(&world, root), root);
+        assert_eq!(depth(&world, c), 2);
+        assert!(is_descendant_of(&world, c, root));
+        assert!(!is_descendant_of(&world, e, a));
+        assert!(!is_descendant_of(&world, root, root));
+    }
+
+    #[test]
+    fn traverse_down() {
+        let (world, [root, a, b, c, d, e]) = build_world();
+
+        assert_eq!(
+            descendants(&world, root).collect::<Vec<_>>(),
+            vec![a, c, d, b, e]
+        );
+        assert_eq!(
+            descendants_breadth_first(&world, root).collect::<Vec<_>>(),
+            vec![a, b, c, d, e]
+        );
+        assert_eq!(descendants(&world, e).count(), 0);
+    }
+
+    #[test]
+    fn traverse_in_sub_world() {
+        let (mut world, [root, a, _, c, d, _]) = build_world();
+
+        let system = SystemBuilder::new("Traverse")
+            .read_component::<Parent>()
+            .read_component::<Children>()
+            .build(move |_, world, _, _| {
+                assert_eq!(root_of(world, d), root);
+                assert_eq!(descendants(world, a).collect::<Vec<_>>(), vec![c, d]);
+            });
+        system.run(&world);
+        system.command_buffer_mut().write(&mut world);
+    }
+
+    #[test]
+    fn cycles_and_deleted_entities() {
+        let (mut world, [root, a, b, c, _, e]) = build_world();
+
+        // root -> b -> e -> root
+        world.add_component(root, Parent::new(e));
+        world.add_component(e, Children::from_single(root));
+        assert_eq!(ancestors(&world, b).collect::<Vec<_>>(), vec![root, e]);
+        assert_eq!(root_of(&world, b), e);
+        assert_eq!(descendants(&world, b).collect::<Vec<_>>().len(), 5);
+        assert!(is_descendant_of(&world, root, b));
+
+        world.delete(a);
+        assert_eq!(ancestors(&world, c).count(), 0);
+        assert_eq!(root_of(&world, c), c);
+        assert!(!descendants(&world, root).any(|entity| entity == a || entity == c));
+    }
+
+    #[test]
+    fn reorder_siblings() {
+        let mut world = World::new();
+        let system = build_hierarchy_sync_system(&mut world);
+        let sync = |world: &mut World| {
+            system.run(world);
+            system.command_buffer_mut().write(world);
+        };
+        let children_of =
+            |world: &World, parent| world.get_component::<Children>(parent).unwrap().to_vec();
+
+        let root = world.insert((), vec![(0u8,)])[0];
+        let other = world.insert((), vec![(0u8,)])[0];
+        let x = world.insert((), vec![(Parent::new(root), SiblingIndex::new(2), 3u8)])[0];
+        let y = world.insert((), vec![(Parent::new(root), SiblingIndex::new(0), 1u8)])[0];
+        let z = world.insert((), vec![(Parent::new(root), 2u8)])[0];
+        let w = world.insert((), vec![(Parent::new(root), SiblingIndex::new(1), 0u8)])[0];
+        let p = world.insert((), vec![(Parent::new(other), SiblingIndex::new(0))])[0];
+        let q = world.insert((), vec![(Parent::new(other), SiblingIndex::new(5))])[0];
+        sync(&mut world);
+        assert_eq!(children_of(&world, root), vec![y, w, x, z]);
+
+        let cmd = CommandBuffer::default();
+        move_child_to(&cmd, z, 0);
+        cmd.write(&mut world);
+        assert_eq!(children_of(&world, root), vec![z, y, w, x]);
+        assert_eq!(
+            *world.get_component::<SiblingIndex>(z).unwrap(),
+            SiblingIndex::new(0)
+        );
+        assert_eq!(
+            *world.get_component::<SiblingIndex>(x).unwrap(),
+            SiblingIndex::new(3)
+        );
+
+        swap_siblings(&cmd, z, x);
+        sort_children_by_key(&cmd, other, |_, _| 0);
+        cmd.write(&mut world);
+        assert_eq!(children_of(&world, root), vec![x, y, w, z]);
+        assert_eq!(children_of(&world, other), vec![p, q]);
+        sync(&mut world);
+        assert_eq!(children_of(&world, root), vec![x, y, w, z]);
+
+        sort_children_by_key(&cmd, root, |world, child| {
+            *world.get_component::<u8>(child).unwrap()
+        });
+        cmd.write(&mut world);
+        sync(&mut world);
+        assert_eq!(children_of(&world, root), vec![w, y, z, x]);
+
+        // The index is kept when moved to another parent, and the remaining siblings keep their order
+        world.add_component(z, Parent::new(other));
+        sync(&mut world);
+        assert_eq!(children_of(&world, root), vec![w, y, x]);
+        assert_eq!(children_of(&world, other), vec![p, q, z]);
+        world.add_component(y, SiblingIndex::new(10));
+        sync(&mut world);
+        assert_eq!(children_of(&world, root), vec![w, x, y]);
+    }
+
+    fn assert_transform_close(world: &World, entity: Entity, expected: &WorldTransform) {
+        let actual = *world.get_component::<WorldTransform>(entity).unwrap();
+        assert!(
+            (actual.matrix() - expected.matrix()).norm() < 1e-5,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn reparent_and_detach_keep_world_transform() {
+        use crate::components::{Position, Rotation};
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::math::Vector3;
+        use crate::systems::TransformBundle;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let hand = world.insert(
+            (),
+            Some((
+                Position::from_xyz(1.0, 2.0, 3.0),
+                Rotation::from_axis_angle(Vector3::new(0.0, 1.0, 0.0)),
+                Scale::uniform(2.0),
+            )),
+        )[0];
+        let item = world.insert(
+            (),
+            Some((
+                Position::from_xyz(5.0, 0.0, 0.0),
+                Rotation::from_axis_angle(Vector3::new(0.5, 0.0, 0.0)),
+            )),
+        )[0];
+        schedule.execute(&mut world);
+        let item_transform = *world.get_component::<WorldTransform>(item).unwrap();
+
+        // Pick up the item
+        let cmd = CommandBuffer::default();
+        reparent_keep_world(&cmd, item, hand);
+        cmd.write(&mut world);
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        assert_eq!(
+            *world.get_component::<Parent>(item).unwrap(),
+            Parent::new(hand)
+        );
+        assert_eq!(descendants(&world, hand).collect::<Vec<_>>(), vec![item]);
+        assert!(
+            (**world.get_component::<Scale>(item).unwrap() - Vector3::repeat(0.5)).norm() < 1e-5
+        );
+        assert_transform_close(&world, item, &item_transform);
+
+        // Moving the hand moves the item
+        *world.get_component_mut::<Position>(hand).unwrap() = Position::from_xyz(1.0, 3.0, 3.0);
+        schedule.execute(&mut world);
+        let item_transform = *world.get_component::<WorldTransform>(item).unwrap();
+        assert!(
+            (item_transform.global_position().vector - Vector3::new(5.0, 1.0, 0.0)).norm() < 1e-5
+        );
+
+        // Drop the item
+        let cmd = CommandBuffer::default();
+        detach_keep_world(&cmd, item);
+        cmd.write(&mut world);
+        schedule.execute(&mut world);
+
+        assert!(world.get_component::<Parent>(item).is_none());
+        assert_eq!(descendants(&world, hand).count(), 0);
+        assert!(
+            (**world.get_component::<Scale>(item).unwrap() - Vector3::repeat(1.0)).norm() < 1e-5
+        );
+        assert_transform_close(&world, item, &item_transform);
+    }
+}
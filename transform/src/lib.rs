@@ -10,6 +10,12 @@
 use game_engine_core as core;
 use game_engine_core::{ecs, math};
 
+pub mod animation;
 pub mod components;
+pub mod floating_origin;
+pub mod hierarchy;
+pub mod prefab;
+pub mod spatial_index;
 pub mod systems;
+pub mod tween;
 pub mod utils;
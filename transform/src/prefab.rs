@@ -0,0 +1,720 @@
+//! Templates of entity hierarchies that can be spawned many times
+//!
+//! A [`Prefab`] is a tree of named nodes, each with components keyed by the names in the
+//! [`ComponentRegistry`]. Instantiating a prefab spawns an entity for each node, with [`Parent`]
+//! components following the tree, and returns the spawned entities by node names. Each instance
+//! can be placed under a parent entity with a root transform, and can override any nested field of
+//! any component through [`PrefabInstance`].
+//!
+//! A prefab is written in RON as below. `components` and `children` can be omitted.
+//!
+//! ```ron
+//! (
+//!     name: "car",
+//!     components: {"transform::Position": (0, 0, 0)},
+//!     children: [
+//!         (name: "left_wheel", components: {"transform::Position": (-1, 0, 0)}),
+//!         (name: "right_wheel", components: {"transform::Position": (1, 0, 0)}),
+//!     ],
+//! )
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::core::registry::ComponentRegistry;
+//! use game_engine::ecs::prelude::*;
+//! use game_engine::transform::components::*;
+//! use game_engine::transform::prefab::{Prefab, PrefabInstance};
+//!
+//! let mut registry = ComponentRegistry::new();
+//! register_components(&mut registry);
+//!
+//! let car = Prefab::from_ron(&registry, r#"(
+//!     name: "car",
+//!     children: [
+//!         (name: "left_wheel", components: {"transform::Position": (-1, 0, 0)}),
+//!         (name: "right_wheel", components: {"transform::Position": (1, 0, 0)}),
+//!     ],
+//! )"#).unwrap();
+//!
+//! let mut world = World::new();
+//! let entities = car
+//!     .instantiate(
+//!         &mut world,
+//!         &registry,
+//!         &PrefabInstance::new()
+//!             .with_position(Position::from_xyz(0.0, 0.0, 5.0))
+//!             .with_override("right_wheel", "transform::Position", serde_json::json!([2, 0, 0])),
+//!     )
+//!     .unwrap();
+//!
+//! let car = entities["car"];
+//! let wheel = entities["right_wheel"];
+//! assert_eq!(world.get_component::<Parent>(wheel).unwrap().entity(), car);
+//! assert_eq!(*world.get_component::<Position>(wheel).unwrap(), Position::from_xyz(2.0, 0.0, 0.0));
+//! assert_eq!(*world.get_component::<Position>(car).unwrap(), Position::from_xyz(0.0, 0.0, 5.0));
+//! ```
+//!
+//! [`Prefab`]: ./struct.Prefab.html
+//! [`PrefabInstance`]: ./struct.PrefabInstance.html
+//! [`ComponentRegistry`]: ../../game_engine_core/registry/struct.ComponentRegistry.html
+//! [`Parent`]: ../components/struct.Parent.html
+
+use crate::components::{Parent, Position, Rotation, Scale};
+use crate::core::registry::{BoxedComponent, ComponentRegistration, ComponentRegistry};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Error from loading or instantiating a [`Prefab`]
+///
+/// [`Prefab`]: ./struct.Prefab.html
+#[derive(Debug)]
+pub enum PrefabError {
+    /// The prefab could not be parsed
+    Parse(String),
+    /// More than one node has the same name
+    DuplicateName(String),
+    /// The component is not registered
+    UnknownComponent(String),
+    /// An override refers to a node that does not exist
+    UnknownNode(String),
+    /// The parent entity of the instance is not alive
+    NoSuchParent(Entity),
+    /// A component of a node could not be built from its value
+    InvalidComponent {
+        /// The name of the node
+        node: String,
+        /// The name of the component
+        component: String,
+        /// Why the component could not be built
+        reason: String,
+    },
+}
+
+impl fmt::Display for PrefabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefabError::Parse(reason) => write!(f, "failed to parse the prefab: {}", reason),
+            PrefabError::DuplicateName(name) => write!(f, "duplicate node name `{}`", name),
+            PrefabError::UnknownComponent(name) => write!(f, "unknown component `{}`", name),
+            PrefabError::UnknownNode(name) => write!(f, "unknown node `{}`", name),
+            PrefabError::NoSuchParent(entity) => write!(f, "parent entity {} is not alive", entity),
+            PrefabError::InvalidComponent {
+                node,
+                component,
+                reason,
+            } => write!(f, "invalid `{}` of node `{}`: {}", component, node, reason),
+        }
+    }
+}
+
+impl std::error::Error for PrefabError {}
+
+/// Template of an entity hierarchy
+///
+/// The component values are checked against the registry when the prefab is loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prefab {
+    root: PrefabNode,
+}
+
+/// Node of a [`Prefab`] that becomes an entity when instantiated
+///
+/// [`Prefab`]: ./struct.Prefab.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrefabNode {
+    name: String,
+    components: Vec<(&'static str, Value)>,
+    children: Vec<PrefabNode>,
+}
+
+impl PrefabNode {
+    /// Get the name of this node
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the names of the components of this node
+    pub fn component_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.components.iter().map(|(name, _)| *name)
+    }
+
+    /// Get the child nodes of this node
+    pub fn children(&self) -> &[PrefabNode] {
+        &self.children
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a PrefabNode)) {
+        f(self);
+        for child in &self.children {
+            child.visit(f);
+        }
+    }
+}
+
+impl Prefab {
+    /// Load a prefab from the given RON string
+    pub fn from_ron(registry: &ComponentRegistry, ron: &str) -> Result<Self, PrefabError> {
+        let parse_error = |e: ron::de::Error| PrefabError::Parse(e.to_string());
+        let mut deserializer = ron::de::Deserializer::from_str(ron).map_err(parse_error)?;
+        let root = NodeSeed(registry)
+            .deserialize(&mut deserializer)
+            .map_err(parse_error)?;
+        deserializer.end().map_err(parse_error)?;
+        let prefab = Prefab { root };
+        prefab.check_names()?;
+        Ok(prefab)
+    }
+
+    /// Load a prefab from the given deserializer
+    ///
+    /// Like [`from_ron`], this fails if more than one node has the same name.
+    ///
+    /// [`from_ron`]: #method.from_ron
+    pub fn deserialize_seed<'de, D: Deserializer<'de>>(
+        registry: &ComponentRegistry,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let prefab = Prefab {
+            root: NodeSeed(registry).deserialize(deserializer)?,
+        };
+        prefab.check_names().map_err(de::Error::custom)?;
+        Ok(prefab)
+    }
+
+    /// Get the root node
+    pub fn root(&self) -> &PrefabNode {
+        &self.root
+    }
+
+    fn check_names(&self) -> Result<(), PrefabError> {
+        let mut names = HashSet::new();
+        let mut duplicate = None;
+        self.root.visit(&mut |node| {
+            if !names.insert(node.name.as_str()) && duplicate.is_none() {
+                duplicate = Some(node.name.clone());
+            }
+        });
+        duplicate.map_or(Ok(()), |name| Err(PrefabError::DuplicateName(name)))
+    }
+
+    /// Spawn the hierarchy into the world, and return the spawned entities by node names
+    ///
+    /// If anything goes wrong, no entities are left in the world.
+    pub fn instantiate(
+        &self,
+        world: &mut World,
+        registry: &ComponentRegistry,
+        instance: &PrefabInstance,
+    ) -> Result<HashMap<String, Entity>, PrefabError> {
+        let mut nodes = Vec::new();
+        self.root.visit(&mut |node| nodes.push(node));
+
+        if let Some(parent) = instance.parent {
+            if !world.is_alive(parent) {
+                return Err(PrefabError::NoSuchParent(parent));
+            }
+        }
+        if let Some(node) = instance
+            .overrides
+            .keys()
+            .find(|name| !nodes.iter().any(|node| node.name == **name))
+        {
+            return Err(PrefabError::UnknownNode(node.clone()));
+        }
+
+        // Build every component before spawning anything
+        let mut built = Vec::with_capacity(nodes.len());
+        for node in &nodes {
+            built.push(build_components(
+                registry,
+                node,
+                instance.overrides.get(&node.name),
+            )?);
+        }
+
+        let entities = world.insert((), vec![(); nodes.len()]).to_vec();
+        let by_name = nodes
+            .iter()
+            .zip(entities.iter())
+            .map(|(node, entity)| (node.name.clone(), *entity))
+            .collect::<HashMap<_, _>>();
+
+        for (node, (entity, components)) in nodes.iter().zip(entities.iter().zip(built)) {
+            for (registration, component) in components {
+                registration
+                    .add(world, *entity, component)
+                    .expect("the entity is just spawned");
+            }
+            for child in &node.children {
+                world.add_component(by_name[&child.name], Parent::new(*entity));
+            }
+        }
+
+        let root = entities[0];
+        if let Some(parent) = instance.parent {
+            world.add_component(root, Parent::new(parent));
+        }
+        if let Some(position) = instance.position {
+            world.add_component(root, position);
+        }
+        if let Some(rotation) = instance.rotation {
+            world.add_component(root, rotation);
+        }
+        if let Some(scale) = instance.scale {
+            world.add_component(root, scale);
+        }
+
+        Ok(by_name)
+    }
+}
+
+type Overrides = Vec<(String, Value)>;
+
+fn build_components<'r>(
+    registry: &'r ComponentRegistry,
+    node: &PrefabNode,
+    overrides: Option<&Overrides>,
+) -> Result<Vec<(&'r ComponentRegistration, BoxedComponent)>, PrefabError> {
+    let mut values = node
+        .components
+        .iter()
+        .map(|(name, value)| ((*name).to_owned(), value.clone()))
+        .collect::<Vec<_>>();
+
+    for (name, patch) in overrides.into_iter().flatten() {
+        match values.iter_mut().find(|(n, _)| n == name) {
+            Some((_, value)) => merge(value, patch),
+            None => values.push((name.clone(), patch.clone())),
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|(name, value)| {
+            let registration = registry
+                .get(&name)
+                .ok_or_else(|| PrefabError::UnknownComponent(name.clone()))?;
+            let component =
+                registration
+                    .deserialize(value)
+                    .map_err(|e| PrefabError::InvalidComponent {
+                        node: node.name.clone(),
+                        component: name,
+                        reason: e.to_string(),
+                    })?;
+            Ok((registration, component))
+        })
+        .collect()
+}
+
+/// Merge the patch into the value, recursing into objects and replacing anything else
+fn merge(value: &mut Value, patch: &Value) {
+    match (value, patch) {
+        (Value::Object(value), Value::Object(patch)) => {
+            for (key, patch) in patch {
+                match value.get_mut(key) {
+                    Some(value) => merge(value, patch),
+                    None => {
+                        value.insert(key.clone(), patch.clone());
+                    }
+                }
+            }
+        }
+        (value, patch) => *value = patch.clone(),
+    }
+}
+
+/// Options for instantiating a [`Prefab`]
+///
+/// Overrides are given as JSON values in the serialized form of the components. Objects are
+/// merged field by field, so overriding a nested field leaves the other fields as they are, and
+/// anything else replaces the value. Overriding a component the node does not have adds it.
+///
+/// [`Prefab`]: ./struct.Prefab.html
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PrefabInstance {
+    parent: Option<Entity>,
+    position: Option<Position>,
+    rotation: Option<Rotation>,
+    scale: Option<Scale>,
+    overrides: HashMap<String, Overrides>,
+}
+
+impl PrefabInstance {
+    /// Create a new instance with no parent, no root transform, and no overrides
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Chain method to set the parent entity of the root
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Chain method to set the position of the root
+    pub fn with_position(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Chain method to set the rotation of the root
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Chain method to set the scale of the root
+    pub fn with_scale(mut self, scale: Scale) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Chain method to override a component of a node
+    pub fn with_override(
+        mut self,
+        node: impl Into<String>,
+        component: impl Into<String>,
+        value: Value,
+    ) -> Self {
+        self.add_override(node, component, value);
+        self
+    }
+
+    /// Override a component of a node
+    ///
+    /// Overriding the same component twice merges both overrides in order.
+    pub fn add_override(
+        &mut self,
+        node: impl Into<String>,
+        component: impl Into<String>,
+        value: Value,
+    ) {
+        let overrides = self.overrides.entry(node.into()).or_default();
+        let component = component.into();
+        match overrides.iter_mut().find(|(name, _)| *name == component) {
+            Some((_, existing)) => merge(existing, &value),
+            None => overrides.push((component, value)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum NodeField {
+    Name,
+    Components,
+    Children,
+}
+
+const NODE_FIELDS: &[&str] = &["name", "components", "children"];
+
+struct NodeSeed<'r>(&'r ComponentRegistry);
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+    type Value = PrefabNode;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("PrefabNode", NODE_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+    type Value = PrefabNode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("struct PrefabNode")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut name = None;
+        let mut components = None;
+        let mut children = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                NodeField::Name if name.is_none() => name = Some(map.next_value()?),
+                NodeField::Components if components.is_none() => {
+                    components = Some(map.next_value_seed(ComponentsSeed(self.0))?);
+                }
+                NodeField::Children if children.is_none() => {
+                    children = Some(map.next_value_seed(ChildrenSeed(self.0))?);
+                }
+                NodeField::Name => return Err(de::Error::duplicate_field("name")),
+                NodeField::Components => return Err(de::Error::duplicate_field("components")),
+                NodeField::Children => return Err(de::Error::duplicate_field("children")),
+            }
+        }
+
+        Ok(PrefabNode {
+            name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+            components: components.unwrap_or_default(),
+            children: children.unwrap_or_default(),
+        })
+    }
+}
+
+struct ChildrenSeed<'r>(&'r ComponentRegistry);
+
+impl<'de> DeserializeSeed<'de> for ChildrenSeed<'_> {
+    type Value = Vec<PrefabNode>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ChildrenSeed<'_> {
+    type Value = Vec<PrefabNode>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a list of prefab nodes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut children = Vec::new();
+        while let Some(child) = seq.next_element_seed(NodeSeed(self.0))? {
+            children.push(child);
+        }
+        Ok(children)
+    }
+}
+
+struct ComponentsSeed<'r>(&'r ComponentRegistry);
+
+impl<'de> DeserializeSeed<'de> for ComponentsSeed<'_> {
+    type Value = Vec<(&'static str, Value)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsSeed<'_> {
+    type Value = Vec<(&'static str, Value)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map of component names to components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::new();
+        while let Some(name) = map.next_key::<String>()? {
+            let registration = self
+                .0
+                .get(&name)
+                .ok_or_else(|| de::Error::custom(format_args!("unknown component `{}`", name)))?;
+
+            // Parse the typed component so that the value is checked, then keep it as JSON
+            let component = map.next_value_seed(ComponentSeed(registration))?;
+            let value = registration
+                .serialize_value(&*component)
+                .map(serde_json::to_value)
+                .expect("the component is deserialized with its registration")
+                .map_err(de::Error::custom)?;
+            components.push((registration.name(), value));
+        }
+        Ok(components)
+    }
+}
+
+struct ComponentSeed<'r>(&'r ComponentRegistration);
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed<'_> {
+    type Value = BoxedComponent;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Prefab, PrefabError, PrefabInstance};
+    use crate::components::*;
+    use crate::core::registry::ComponentRegistry;
+    use crate::ecs::query::{IntoQuery, Read};
+    use crate::ecs::world::World;
+    use crate::math::{UnitQuaternion, Vector3};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Wheel {
+        radius: f32,
+        grip: Grip,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    struct Grip {
+        dry: f32,
+        wet: f32,
+    }
+
+    const CAR: &str = r#"(
+        name: "car",
+        components: {"transform::Position": (0, 0, 0)},
+        children: [
+            (
+                name: "front",
+                components: {"transform::Position": (0, 0, 2)},
+                children: [
+                    (
+                        name: "wheel",
+                        components: {
+                            "transform::Position": (1, 0, 0),
+                            "test::Wheel": (radius: 0.5, grip: (dry: 1.0, wet: 0.5)),
+                        },
+                    ),
+                ],
+            ),
+        ],
+    )"#;
+
+    fn registry() -> ComponentRegistry {
+        let mut registry = ComponentRegistry::new().with::<Wheel>("test::Wheel");
+        register_components(&mut registry);
+        registry
+    }
+
+    #[test]
+    fn instantiate_hierarchy() {
+        let registry = registry();
+        let prefab = Prefab::from_ron(&registry, CAR).unwrap();
+        assert_eq!(prefab.root().children()[0].name(), "front");
+
+        let mut world = World::new();
+        let garage = world.insert((), vec![(Position::zero(),)])[0];
+        let rotation = Rotation::from(UnitQuaternion::new(Vector3::new(0.0, 1.0, 0.0)));
+
+        let first = prefab
+            .instantiate(
+                &mut world,
+                &registry,
+                &PrefabInstance::new()
+                    .with_parent(garage)
+                    .with_rotation(rotation)
+                    .with_scale(Scale::uniform(2.0)),
+            )
+            .unwrap();
+        let second = prefab
+            .instantiate(&mut world, &registry, &PrefabInstance::new())
+            .unwrap();
+
+        assert_eq!(first.len(), 3);
+        assert_ne!(first["wheel"], second["wheel"]);
+        assert_eq!(
+            world
+                .get_component::<Parent>(first["car"])
+                .unwrap()
+                .entity(),
+            garage
+        );
+        assert!(world.get_component::<Parent>(second["car"]).is_none());
+        assert_eq!(
+            world
+                .get_component::<Parent>(first["wheel"])
+                .unwrap()
+                .entity(),
+            first["front"]
+        );
+        assert_eq!(
+            *world.get_component::<Rotation>(first["car"]).unwrap(),
+            rotation
+        );
+        assert_eq!(
+            *world.get_component::<Scale>(first["car"]).unwrap(),
+            Scale::uniform(2.0)
+        );
+        assert!(world.get_component::<Scale>(second["car"]).is_none());
+        assert_eq!(
+            world
+                .get_component::<Wheel>(second["wheel"])
+                .unwrap()
+                .radius,
+            0.5
+        );
+    }
+
+    #[test]
+    fn override_nested_fields() {
+        let registry = registry();
+        let prefab = Prefab::from_ron(&registry, CAR).unwrap();
+
+        let mut world = World::new();
+        let instance = PrefabInstance::new()
+            .with_override("wheel", "test::Wheel", json!({"grip": {"wet": 0.1}}))
+            .with_override(
+                "front",
+                "test::Wheel",
+                json!({"radius": 2, "grip": {"dry": 3, "wet": 3}}),
+            );
+        let entities = prefab
+            .instantiate(&mut world, &registry, &instance)
+            .unwrap();
+
+        let wheel = Wheel::clone(&world.get_component::<Wheel>(entities["wheel"]).unwrap());
+        assert_eq!(
+            wheel,
+            Wheel {
+                radius: 0.5,
+                grip: Grip { dry: 1.0, wet: 0.1 },
+            }
+        );
+        assert_eq!(
+            world
+                .get_component::<Wheel>(entities["front"])
+                .unwrap()
+                .radius,
+            2.0
+        );
+    }
+
+    #[test]
+    fn invalid_prefabs_and_instances() {
+        let registry = registry();
+
+        let duplicate = r#"(name: "a", children: [(name: "a")])"#;
+        match Prefab::from_ron(&registry, duplicate) {
+            Err(PrefabError::DuplicateName(name)) => assert_eq!(name, "a"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let duplicate = r#"{"name": "a", "children": [{"name": "b"}, {"name": "b"}]}"#;
+        let result = Prefab::deserialize_seed(
+            &registry,
+            &mut serde_json::Deserializer::from_str(duplicate),
+        );
+        assert_eq!(result.unwrap_err().to_string(), "duplicate node name `b`");
+
+        let unknown = r#"(name: "a", components: {"test::Engine": ()})"#;
+        assert!(matches!(
+            Prefab::from_ron(&registry, unknown),
+            Err(PrefabError::Parse(_))
+        ));
+
+        let prefab = Prefab::from_ron(&registry, CAR).unwrap();
+        let mut world = World::new();
+
+        let instance = PrefabInstance::new().with_override("trunk", "test::Wheel", json!({}));
+        assert!(matches!(
+            prefab.instantiate(&mut world, &registry, &instance),
+            Err(PrefabError::UnknownNode(_))
+        ));
+
+        let instance =
+            PrefabInstance::new().with_override("wheel", "test::Wheel", json!({"radius": "big"}));
+        assert!(matches!(
+            prefab.instantiate(&mut world, &registry, &instance),
+            Err(PrefabError::InvalidComponent { .. })
+        ));
+
+        // Nothing is spawned by the failed instantiations
+        assert_eq!(<Read<Position>>::query().iter(&mut world).count(), 0);
+    }
+}
@@ -0,0 +1,525 @@
+//! Spatial index for finding entities by their location
+//!
+//! [`SpatialIndex`] resource keeps the world-space bounds of all entities with [`WorldTransform`] component, using
+//! either a uniform grid or a bounding volume hierarchy, as chosen by [`SpatialIndexKind`]. The bounds are taken from
+//! [`WorldAabb`] component if the entity has one, and otherwise they are just the point at the global position of the
+//! entity.
+//!
+//! The system built by [`build_spatial_index_update_system`] keeps the index in sync with the entities that have
+//! changed since its last run. Other systems query the index with `read_resource::<SpatialIndex>()`, so any number of
+//! them can run in parallel:
+//!
+//! - [`query_aabb`] finds entities whose bounds intersect a box
+//! - [`query_radius`] finds entities whose bounds are within a distance from a point
+//! - [`ray_cast`] finds entities hit by a [`Ray`], sorted by distance
+//! - [`nearest`] finds the `k` entities closest to a point, sorted by distance
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::ecs::prelude::*;
+//! use game_engine::math::{Point3, Vector3};
+//! use game_engine::transform::components::Aabb;
+//! use game_engine::transform::spatial_index::{Ray, SpatialIndex, SpatialIndexKind};
+//!
+//! let mut world = World::new();
+//! let entities = world.insert((), vec![(), (), ()]).to_vec();
+//!
+//! let mut index = SpatialIndex::new(SpatialIndexKind::Grid { cell_size: 4.0 });
+//! for (i, &entity) in entities.iter().enumerate() {
+//!     let point = Point3::new(i as f32 * 10.0, 0.0, 0.0);
+//!     index.insert(entity, Aabb::new(point, point));
+//! }
+//!
+//! assert_eq!(index.query_radius(&Point3::new(12.0, 0.0, 0.0), 3.0), vec![entities[1]]);
+//! let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::x());
+//! let hits: Vec<_> = index.ray_cast(&ray, 20.0).iter().map(|hit| hit.entity).collect();
+//! assert_eq!(hits, vec![entities[0], entities[1]]);
+//! ```
+//!
+//! [`SpatialIndex`]: ./struct.SpatialIndex.html
+//! [`WorldTransform`]: ../components/struct.WorldTransform.html
+//! [`SpatialIndexKind`]: ./enum.SpatialIndexKind.html
+//! [`WorldAabb`]: ../components/struct.WorldAabb.html
+//! [`build_spatial_index_update_system`]: ../systems/fn.build_spatial_index_update_system.html
+//! [`query_aabb`]: ./struct.SpatialIndex.html#method.query_aabb
+//! [`query_radius`]: ./struct.SpatialIndex.html#method.query_radius
+//! [`ray_cast`]: ./struct.SpatialIndex.html#method.ray_cast
+//! [`Ray`]: ./struct.Ray.html
+//! [`nearest`]: ./struct.SpatialIndex.html#method.nearest
+
+mod bvh;
+mod grid;
+
+use self::bvh::Bvh;
+use self::grid::Grid;
+use crate::components::{Aabb, BoundingSphere};
+use crate::ecs::entity::Entity;
+use crate::math::{Point3, Vector3};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The default size of the cells of [`SpatialIndexKind::Grid`]
+///
+/// [`SpatialIndexKind::Grid`]: ./enum.SpatialIndexKind.html#variant.Grid
+pub const DEFAULT_CELL_SIZE: f32 = 10.0;
+
+/// Half line used for ray casts, starting at `origin` and going towards `direction`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// The point where the ray starts
+    pub origin: Point3<f32>,
+    /// The direction of the ray, which is a unit vector
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    /// Create a new instance, normalizing the given direction, which must not be zero
+    pub fn new(origin: Point3<f32>, direction: Vector3<f32>) -> Ray {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// Get the point at the given distance from the origin along the ray
+    pub fn point_at(&self, distance: f32) -> Point3<f32> {
+        self.origin + self.direction * distance
+    }
+
+    /// Get the distance from the origin to where the ray enters the box, which is zero if the origin is inside the box
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for i in 0..3 {
+            if self.direction[i] == 0.0 {
+                if self.origin[i] < aabb.min[i] || self.origin[i] > aabb.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (aabb.min[i] - self.origin[i]) / self.direction[i];
+            let t2 = (aabb.max[i] - self.origin[i]) / self.direction[i];
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if far < near {
+                return None;
+            }
+        }
+        Some(near)
+    }
+
+    /// Get the distance from the origin to where the ray enters the sphere, which is zero if the origin is inside the
+    /// sphere
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let c = offset.norm_squared() - sphere.radius * sphere.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let b = offset.dot(&self.direction);
+        let discriminant = b * b - c;
+        if b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        Some(-b - discriminant.sqrt())
+    }
+}
+
+/// Entity hit by [`SpatialIndex::ray_cast`]
+///
+/// [`SpatialIndex::ray_cast`]: ./struct.SpatialIndex.html#method.ray_cast
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// The entity that was hit
+    pub entity: Entity,
+    /// The distance from the origin of the ray to where it enters the bounds of the entity
+    pub distance: f32,
+}
+
+/// Data structure used by [`SpatialIndex`]
+///
+/// [`SpatialIndex`]: ./struct.SpatialIndex.html
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SpatialIndexKind {
+    /// Uniform grid of cubic cells with the given size
+    ///
+    /// Updates are cheap, and queries are fast when the entities are spread evenly and the cells are about as large as
+    /// the queried regions. Entities spanning many cells are kept in a separate list that every query goes through.
+    Grid {
+        /// The length of the edges of the cells
+        cell_size: f32,
+    },
+
+    /// Bounding volume hierarchy, where each entity is inserted next to the one that grows the tree the least
+    ///
+    /// It adapts to any distribution of entities and sizes of bounds, at the cost of slower updates than the grid. The
+    /// tree is kept balanced, so the queries stay fast even when the entities are inserted in order.
+    #[default]
+    Bvh,
+}
+
+#[derive(Clone, Debug)]
+enum Backend {
+    Grid(Grid),
+    Bvh(Bvh),
+}
+
+impl Backend {
+    fn new(kind: SpatialIndexKind) -> Backend {
+        match kind {
+            SpatialIndexKind::Grid { cell_size } => Backend::Grid(Grid::new(cell_size)),
+            SpatialIndexKind::Bvh => Backend::Bvh(Bvh::new()),
+        }
+    }
+}
+
+/// ECS resource for finding entities by their world-space bounds
+///
+/// The resource is inserted with the default [`SpatialIndexKind`] by [`build_spatial_index_update_system`] if it does
+/// not exist. Insert it beforehand to use another kind.
+///
+/// Distances are measured to the nearest point of the bounds, so they are zero for the points inside the bounds.
+///
+/// [`SpatialIndexKind`]: ./enum.SpatialIndexKind.html
+/// [`build_spatial_index_update_system`]: ../systems/fn.build_spatial_index_update_system.html
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    kind: SpatialIndexKind,
+    entries: HashMap<Entity, Aabb>,
+    backend: Backend,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        SpatialIndex::new(SpatialIndexKind::default())
+    }
+}
+
+impl SpatialIndex {
+    /// Create a new empty index using the given data structure
+    pub fn new(kind: SpatialIndexKind) -> SpatialIndex {
+        SpatialIndex {
+            kind,
+            entries: HashMap::new(),
+            backend: Backend::new(kind),
+        }
+    }
+
+    /// Get the data structure used by this index
+    pub fn kind(&self) -> SpatialIndexKind {
+        self.kind
+    }
+
+    /// Switch to the given data structure, rebuilding the index
+    pub fn set_kind(&mut self, kind: SpatialIndexKind) {
+        self.kind = kind;
+        self.backend = Backend::new(kind);
+        for (&entity, aabb) in &self.entries {
+            match &mut self.backend {
+                Backend::Grid(grid) => grid.insert(entity, aabb),
+                Backend::Bvh(bvh) => bvh.insert(entity, aabb),
+            }
+        }
+    }
+
+    /// Get the number of the entities in the index
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the bounds of the given entity, or `None` if it is not in the index
+    pub fn bounds(&self, entity: Entity) -> Option<&Aabb> {
+        self.entries.get(&entity)
+    }
+
+    /// Iterate over all entities in the index with their bounds
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &Aabb)> + '_ {
+        self.entries.iter().map(|(&entity, aabb)| (entity, aabb))
+    }
+
+    /// Insert the given entity, or update its bounds if it is already in the index
+    pub fn insert(&mut self, entity: Entity, aabb: Aabb) {
+        if let Some(old) = self.entries.insert(entity, aabb) {
+            if old == aabb {
+                return;
+            }
+            self.remove_from_backend(entity, &old);
+        }
+        match &mut self.backend {
+            Backend::Grid(grid) => grid.insert(entity, &aabb),
+            Backend::Bvh(bvh) => bvh.insert(entity, &aabb),
+        }
+    }
+
+    /// Remove the given entity, returning its bounds if it was in the index
+    pub fn remove(&mut self, entity: Entity) -> Option<Aabb> {
+        let aabb = self.entries.remove(&entity)?;
+        self.remove_from_backend(entity, &aabb);
+        Some(aabb)
+    }
+
+    /// Remove all entities
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.backend = Backend::new(self.kind);
+    }
+
+    /// Find the entities whose bounds intersect the given box
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<Entity> {
+        match &self.backend {
+            Backend::Grid(grid) => grid.query_aabb(aabb, &self.entries),
+            Backend::Bvh(bvh) => bvh.query_aabb(aabb),
+        }
+    }
+
+    /// Find the entities whose bounds are within `radius` from the given point
+    pub fn query_radius(&self, center: &Point3<f32>, radius: f32) -> Vec<Entity> {
+        let radius = radius.max(0.0);
+        let reach = Aabb::from_center_half_extents(*center, Vector3::repeat(radius));
+        let mut entities = self.query_aabb(&reach);
+        entities
+            .retain(|entity| distance_squared(center, &self.entries[entity]) <= radius * radius);
+        entities
+    }
+
+    /// Find the entities whose bounds are hit by the given ray within `max_distance`, sorted by distance
+    pub fn ray_cast(&self, ray: &Ray, max_distance: f32) -> Vec<RayHit> {
+        let mut hits = match &self.backend {
+            Backend::Grid(grid) => grid.ray_cast(ray, max_distance, &self.entries),
+            Backend::Bvh(bvh) => bvh.ray_cast(ray, max_distance),
+        };
+        hits.sort_by(|a, b| compare_distances(a.distance, b.distance));
+        hits
+    }
+
+    /// Find at most `k` entities whose bounds are the closest to the given point, sorted by distance
+    pub fn nearest(&self, point: &Point3<f32>, k: usize) -> Vec<(Entity, f32)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut nearest = match &self.backend {
+            Backend::Grid(grid) => grid.nearest(point, k, &self.entries),
+            Backend::Bvh(bvh) => bvh.nearest(point, k),
+        };
+        for (_, distance) in &mut nearest {
+            *distance = distance.sqrt();
+        }
+        nearest
+    }
+
+    fn remove_from_backend(&mut self, entity: Entity, aabb: &Aabb) {
+        match &mut self.backend {
+            Backend::Grid(grid) => grid.remove(entity, aabb),
+            Backend::Bvh(bvh) => bvh.remove(entity),
+        }
+    }
+}
+
+/// Squared distance from the point to the nearest point of the box
+fn distance_squared(point: &Point3<f32>, aabb: &Aabb) -> f32 {
+    (0..3)
+        .map(|i| {
+            let outside = (aabb.min[i] - point[i])
+                .max(point[i] - aabb.max[i])
+                .max(0.0);
+            outside * outside
+        })
+        .sum()
+}
+
+fn compare_distances(a: f32, b: f32) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Sort the pairs of entities and squared distances by distance and keep the first `k` of them
+fn keep_nearest(mut candidates: Vec<(Entity, f32)>, k: usize) -> Vec<(Entity, f32)> {
+    candidates.sort_by(|a, b| compare_distances(a.1, b.1));
+    candidates.truncate(k);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ray, SpatialIndex, SpatialIndexKind};
+    use crate::components::{Aabb, BoundingSphere};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3};
+    use std::collections::HashSet;
+
+    fn cube(center: Point3<f32>, half_extent: f32) -> Aabb {
+        Aabb::from_center_half_extents(center, Vector3::repeat(half_extent))
+    }
+
+    #[test]
+    fn ray_intersections() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 2.0));
+        assert_eq!(ray.direction, Vector3::z());
+        assert_eq!(ray.intersect_aabb(&cube(Point3::origin(), 1.0)), Some(9.0));
+        assert_eq!(
+            ray.intersect_aabb(&cube(Point3::new(0.0, 3.0, 0.0), 1.0)),
+            None
+        );
+        assert_eq!(
+            ray.intersect_aabb(&cube(Point3::new(0.0, 0.0, -20.0), 1.0)),
+            None
+        );
+        assert_eq!(
+            ray.intersect_aabb(&cube(Point3::new(0.0, 0.0, -10.0), 1.0)),
+            Some(0.0)
+        );
+        assert_eq!(
+            ray.intersect_sphere(&BoundingSphere::new(Point3::origin(), 2.0)),
+            Some(8.0)
+        );
+        assert_eq!(
+            ray.intersect_sphere(&BoundingSphere::new(Point3::new(0.0, 0.0, -20.0), 2.0)),
+            None
+        );
+        assert_eq!(ray.point_at(4.0), Point3::new(0.0, 0.0, -6.0));
+    }
+
+    #[test]
+    fn query_index() {
+        let kinds = [
+            SpatialIndexKind::Grid { cell_size: 2.0 },
+            SpatialIndexKind::Grid { cell_size: 100.0 },
+            SpatialIndexKind::Bvh,
+        ];
+        for &kind in kinds.iter() {
+            let mut world = World::new();
+            let entities = world.insert((), (0..5).map(|_| ())).to_vec();
+            let mut index = SpatialIndex::new(kind);
+            for (i, &entity) in entities.iter().enumerate() {
+                index.insert(entity, cube(Point3::new(i as f32 * 5.0, 0.0, 0.0), 1.0));
+            }
+            // A large entity that spans many cells of the smaller grid
+            let ground = world.insert((), Some(()))[0];
+            index.insert(
+                ground,
+                Aabb::new(
+                    Point3::new(-50.0, -3.0, -50.0),
+                    Point3::new(50.0, -2.0, 50.0),
+                ),
+            );
+            assert_eq!(index.len(), 6);
+
+            let found: HashSet<_> = index
+                .query_aabb(&cube(Point3::new(7.5, 0.0, 0.0), 1.5))
+                .into_iter()
+                .collect();
+            assert_eq!(
+                found,
+                [entities[1], entities[2]].iter().copied().collect(),
+                "{:?}",
+                kind
+            );
+            let found: HashSet<_> = index
+                .query_radius(&Point3::new(10.0, -1.0, 0.0), 1.5)
+                .into_iter()
+                .collect();
+            assert_eq!(
+                found,
+                [entities[2], ground].iter().copied().collect(),
+                "{:?}",
+                kind
+            );
+
+            let ray = Ray::new(Point3::new(100.0, 0.0, 0.0), -Vector3::x());
+            let hits: Vec<_> = index
+                .ray_cast(&ray, 90.0)
+                .iter()
+                .map(|hit| (hit.entity, hit.distance))
+                .collect();
+            assert_eq!(
+                hits,
+                vec![
+                    (entities[4], 79.0),
+                    (entities[3], 84.0),
+                    (entities[2], 89.0)
+                ],
+                "{:?}",
+                kind
+            );
+            let ray = Ray::new(Point3::new(5.0, 10.0, 0.0), -Vector3::y());
+            let hits: Vec<_> = index
+                .ray_cast(&ray, f32::INFINITY)
+                .iter()
+                .map(|hit| hit.entity)
+                .collect();
+            assert_eq!(hits, vec![entities[1], ground], "{:?}", kind);
+
+            assert_eq!(
+                index.nearest(&Point3::new(21.0, 0.0, 0.0), 2),
+                vec![(entities[4], 0.0), (ground, 2.0)],
+                "{:?}",
+                kind
+            );
+            assert_eq!(index.nearest(&Point3::new(0.0, 100.0, 0.0), 10).len(), 6);
+
+            // Moving and removing entities
+            index.insert(entities[0], cube(Point3::new(20.0, 0.0, 0.0), 1.0));
+            assert_eq!(
+                index.remove(entities[4]),
+                Some(cube(Point3::new(20.0, 0.0, 0.0), 1.0))
+            );
+            assert_eq!(index.remove(entities[4]), None);
+            assert_eq!(
+                index.query_radius(&Point3::new(20.0, 0.0, 0.0), 0.0),
+                vec![entities[0]],
+                "{:?}",
+                kind
+            );
+            assert!(index.query_radius(&Point3::origin(), 0.5).is_empty());
+            index.set_kind(SpatialIndexKind::Bvh);
+            assert_eq!(index.nearest(&Point3::origin(), 1), vec![(ground, 2.0)]);
+        }
+    }
+
+    #[test]
+    fn grid_with_huge_bounds() {
+        let mut world = World::new();
+        let entities = world.insert((), (0..3).map(|_| ())).to_vec();
+        let mut index = SpatialIndex::new(SpatialIndexKind::Grid { cell_size: 10.0 });
+        index.insert(entities[0], cube(Point3::origin(), 1.0));
+        // Neither the number of the cells nor the cells of the bounds fit in the integers
+        index.insert(entities[1], cube(Point3::origin(), 5e7));
+        let ground = Aabb::new(
+            Point3::new(f32::NEG_INFINITY, -2.0, f32::NEG_INFINITY),
+            Point3::new(f32::INFINITY, -1.0, f32::INFINITY),
+        );
+        index.insert(entities[2], ground);
+        assert_eq!(index.len(), 3);
+
+        let found: HashSet<_> = index
+            .query_aabb(&cube(Point3::new(1e9, -1.0, 0.0), 1.0))
+            .into_iter()
+            .collect();
+        assert_eq!(found, [entities[2]].iter().copied().collect());
+        let found: HashSet<_> = index
+            .query_radius(&Point3::new(0.0, 5.0, 0.0), 4.5)
+            .into_iter()
+            .collect();
+        assert_eq!(found, [entities[0], entities[1]].iter().copied().collect());
+        let ray = Ray::new(Point3::new(0.0, 10.0, 0.0), -Vector3::y());
+        let hits: Vec<_> = index
+            .ray_cast(&ray, f32::INFINITY)
+            .iter()
+            .map(|hit| hit.entity)
+            .collect();
+        assert_eq!(hits, vec![entities[1], entities[0], entities[2]]);
+        assert_eq!(
+            index.nearest(&Point3::new(0.0, 2.0, 0.0), 2),
+            vec![(entities[1], 0.0), (entities[0], 1.0)]
+        );
+
+        assert_eq!(index.remove(entities[1]), Some(cube(Point3::origin(), 5e7)));
+        assert_eq!(index.remove(entities[2]), Some(ground));
+        assert_eq!(index.query_aabb(&ground), vec![entities[0]]);
+    }
+}
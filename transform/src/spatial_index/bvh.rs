@@ -0,0 +1,478 @@
+use super::{compare_distances, distance_squared, Ray, RayHit};
+use crate::components::Aabb;
+use crate::ecs::entity::Entity;
+use crate::math::Point3;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Leaf(Entity),
+    Branch([usize; 2]),
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    aabb: Aabb,
+    parent: Option<usize>,
+    /// The length of the longest path to the leaves under this node, which is zero for the leaves
+    height: usize,
+    kind: NodeKind,
+}
+
+/// Binary tree of boxes, where each branch covers the boxes of its two children
+///
+/// Entities are inserted and removed one by one without rebuilding the tree. The branches are rotated as in AVL trees
+/// whenever the heights of their children differ by more than one, so the tree stays balanced even when the entities
+/// are inserted in order along a line.
+#[derive(Clone, Debug)]
+pub(super) struct Bvh {
+    nodes: Vec<Node>,
+    /// Indices of the unused slots of `nodes`
+    free: Vec<usize>,
+    root: Option<usize>,
+    leaves: HashMap<Entity, usize>,
+}
+
+impl Bvh {
+    pub(super) fn new() -> Bvh {
+        Bvh {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            leaves: HashMap::new(),
+        }
+    }
+
+    pub(super) fn insert(&mut self, entity: Entity, aabb: &Aabb) {
+        let leaf = self.allocate(Node {
+            aabb: *aabb,
+            parent: None,
+            height: 0,
+            kind: NodeKind::Leaf(entity),
+        });
+        self.leaves.insert(entity, leaf);
+
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(leaf);
+                return;
+            }
+        };
+        let sibling = self.find_sibling(root, aabb);
+
+        let parent = self.nodes[sibling].parent;
+        let branch = self.allocate(Node {
+            aabb: self.nodes[sibling].aabb.union(aabb),
+            parent,
+            height: self.nodes[sibling].height + 1,
+            kind: NodeKind::Branch([sibling, leaf]),
+        });
+        self.nodes[sibling].parent = Some(branch);
+        self.nodes[leaf].parent = Some(branch);
+        match parent {
+            Some(parent) => {
+                self.replace_child(parent, sibling, branch);
+                self.refit(parent);
+            }
+            None => self.root = Some(branch),
+        }
+    }
+
+    pub(super) fn remove(&mut self, entity: Entity) {
+        let leaf = match self.leaves.remove(&entity) {
+            Some(leaf) => leaf,
+            None => return,
+        };
+        self.free.push(leaf);
+
+        let parent = match self.nodes[leaf].parent {
+            Some(parent) => parent,
+            None => {
+                self.root = None;
+                return;
+            }
+        };
+        let sibling = match self.nodes[parent].kind {
+            NodeKind::Branch([a, b]) => {
+                if a == leaf {
+                    b
+                } else {
+                    a
+                }
+            }
+            NodeKind::Leaf(_) => unreachable!("parent of a node must be a branch"),
+        };
+        self.free.push(parent);
+
+        // The sibling takes the place of the parent
+        let grandparent = self.nodes[parent].parent;
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            Some(grandparent) => {
+                self.replace_child(grandparent, parent, sibling);
+                self.refit(grandparent);
+            }
+            None => self.root = Some(sibling),
+        }
+    }
+
+    pub(super) fn query_aabb(&self, aabb: &Aabb) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.traverse(
+            |node| node.aabb.intersects(aabb),
+            |entity, _| found.push(entity),
+        );
+        found
+    }
+
+    pub(super) fn ray_cast(&self, ray: &Ray, max_distance: f32) -> Vec<RayHit> {
+        let mut hits = Vec::new();
+        self.traverse(
+            |node| {
+                matches!(ray.intersect_aabb(&node.aabb), Some(distance) if distance <= max_distance)
+            },
+            |entity, aabb| {
+                if let Some(distance) = ray.intersect_aabb(aabb) {
+                    hits.push(RayHit { entity, distance });
+                }
+            },
+        );
+        hits
+    }
+
+    /// Find the nearest entities with squared distances, visiting the nodes in order of distance
+    pub(super) fn nearest(&self, point: &Point3<f32>, k: usize) -> Vec<(Entity, f32)> {
+        let mut nearest = Vec::with_capacity(k.min(self.leaves.len()));
+        let mut queue = BinaryHeap::new();
+        if let Some(root) = self.root {
+            queue.push(Queued(
+                distance_squared(point, &self.nodes[root].aabb),
+                root,
+            ));
+        }
+        while let Some(Queued(distance, index)) = queue.pop() {
+            match self.nodes[index].kind {
+                NodeKind::Leaf(entity) => {
+                    nearest.push((entity, distance));
+                    if nearest.len() == k {
+                        break;
+                    }
+                }
+                NodeKind::Branch(children) => {
+                    for &child in children.iter() {
+                        queue.push(Queued(
+                            distance_squared(point, &self.nodes[child].aabb),
+                            child,
+                        ));
+                    }
+                }
+            }
+        }
+        nearest
+    }
+
+    /// Visit the leaves under the branches accepted by `enter`, which is also checked against the leaves
+    fn traverse<E, V>(&self, enter: E, mut visit: V)
+    where
+        E: Fn(&Node) -> bool,
+        V: FnMut(Entity, &Aabb),
+    {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(node) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf(entity) => visit(entity, &node.aabb),
+                NodeKind::Branch(children) => stack.extend(children.iter()),
+            }
+        }
+    }
+
+    /// Find the node to pair with the new box, which costs the least including the growth of its ancestors
+    ///
+    /// This is the branch and bound search of Box2D's dynamic tree. A branch is left as soon as pairing with it costs
+    /// less than the lower bounds of descending into its children.
+    fn find_sibling(&self, root: usize, aabb: &Aabb) -> usize {
+        let mut index = root;
+        while let NodeKind::Branch(children) = self.nodes[index].kind {
+            let node = &self.nodes[index].aabb;
+            let combined = Cost::of(&node.union(aabb));
+            // Pairing with this node creates a new branch covering both
+            let cost = combined * 2.0;
+            // Descending makes this node grow all the same
+            let inherited = (combined - Cost::of(node)) * 2.0;
+            let descend = |child: usize| {
+                let child = &self.nodes[child];
+                let covered = Cost::of(&child.aabb.union(aabb));
+                match child.kind {
+                    NodeKind::Leaf(_) => covered + inherited,
+                    NodeKind::Branch(_) => covered - Cost::of(&child.aabb) + inherited,
+                }
+            };
+            let (cost0, cost1) = (descend(children[0]), descend(children[1]));
+            if cost < cost0 && cost < cost1 {
+                break;
+            }
+            index = if cost0 <= cost1 {
+                children[0]
+            } else {
+                children[1]
+            };
+        }
+        index
+    }
+
+    fn allocate(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let NodeKind::Branch(children) = &mut self.nodes[parent].kind {
+            for child in children.iter_mut().filter(|child| **child == old) {
+                *child = new;
+            }
+        }
+    }
+
+    /// Rebalance the given branch and its ancestors, and recompute their boxes and heights
+    fn refit(&mut self, index: usize) {
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let index = self.balance(index);
+            self.update(index);
+            current = self.nodes[index].parent;
+        }
+    }
+
+    /// Recompute the box and height of the given branch from its children
+    fn update(&mut self, index: usize) {
+        if let NodeKind::Branch([a, b]) = self.nodes[index].kind {
+            self.nodes[index].aabb = self.nodes[a].aabb.union(&self.nodes[b].aabb);
+            self.nodes[index].height = self.nodes[a].height.max(self.nodes[b].height) + 1;
+        }
+    }
+
+    /// Rotate the taller child of the given branch up if the heights of its children differ by more than one
+    ///
+    /// Returns the node that takes the place of the branch.
+    fn balance(&mut self, index: usize) -> usize {
+        let [b, c] = match self.nodes[index].kind {
+            NodeKind::Branch(children) if self.nodes[index].height >= 2 => children,
+            _ => return index,
+        };
+        let (b_height, c_height) = (self.nodes[b].height, self.nodes[c].height);
+        if c_height > b_height + 1 {
+            self.rotate_up(index, c, b)
+        } else if b_height > c_height + 1 {
+            self.rotate_up(index, b, c)
+        } else {
+            index
+        }
+    }
+
+    /// Make the taller child the parent of the given branch, which keeps the other child
+    ///
+    /// The taller child keeps the taller of its own children, and hands the shorter one over to the branch.
+    fn rotate_up(&mut self, index: usize, taller: usize, other: usize) -> usize {
+        let [f, g] = match self.nodes[taller].kind {
+            NodeKind::Branch(children) => children,
+            NodeKind::Leaf(_) => unreachable!("taller child of a branch must be a branch"),
+        };
+        let (kept, moved) = if self.nodes[f].height > self.nodes[g].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        let parent = self.nodes[index].parent;
+        self.nodes[taller].parent = parent;
+        match parent {
+            Some(parent) => self.replace_child(parent, index, taller),
+            None => self.root = Some(taller),
+        }
+        self.nodes[taller].kind = NodeKind::Branch([index, kept]);
+        self.nodes[index].parent = Some(taller);
+        self.nodes[index].kind = NodeKind::Branch([other, moved]);
+        self.nodes[moved].parent = Some(index);
+
+        self.update(index);
+        self.update(taller);
+        taller
+    }
+}
+
+/// Cost of a box in the tree, which is its surface area, and then its perimeter for the boxes that are flat
+///
+/// The perimeter tells apart the boxes of points along a line, whose surface areas are all zero.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+struct Cost {
+    area: f32,
+    perimeter: f32,
+}
+
+impl Cost {
+    fn of(aabb: &Aabb) -> Cost {
+        let size = aabb.size();
+        Cost {
+            area: 2.0 * (size.x * size.y + size.y * size.z + size.z * size.x),
+            perimeter: 4.0 * (size.x + size.y + size.z),
+        }
+    }
+}
+
+impl Add for Cost {
+    type Output = Cost;
+
+    fn add(self, other: Cost) -> Cost {
+        Cost {
+            area: self.area + other.area,
+            perimeter: self.perimeter + other.perimeter,
+        }
+    }
+}
+
+impl Sub for Cost {
+    type Output = Cost;
+
+    fn sub(self, other: Cost) -> Cost {
+        Cost {
+            area: self.area - other.area,
+            perimeter: self.perimeter - other.perimeter,
+        }
+    }
+}
+
+impl Mul<f32> for Cost {
+    type Output = Cost;
+
+    fn mul(self, factor: f32) -> Cost {
+        Cost {
+            area: self.area * factor,
+            perimeter: self.perimeter * factor,
+        }
+    }
+}
+
+/// Node in the queue of the nearest search, ordered so that the closest one is popped first
+struct Queued(f32, usize);
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_distances(other.0, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bvh, NodeKind};
+    use crate::components::Aabb;
+    use crate::ecs::prelude::*;
+    use crate::math::Point3;
+    use std::collections::HashSet;
+
+    impl Bvh {
+        /// Check the links, boxes, and heights of the nodes, and get the height of the tree
+        fn validate(&self) -> usize {
+            let root = match self.root {
+                Some(root) => root,
+                None => return 0,
+            };
+            assert_eq!(self.nodes[root].parent, None);
+            let mut leaves = 0;
+            let mut stack = vec![root];
+            while let Some(index) = stack.pop() {
+                let node = &self.nodes[index];
+                match node.kind {
+                    NodeKind::Leaf(entity) => {
+                        assert_eq!(self.leaves[&entity], index);
+                        assert_eq!(node.height, 0);
+                        leaves += 1;
+                    }
+                    NodeKind::Branch(children) => {
+                        let (a, b) = (&self.nodes[children[0]], &self.nodes[children[1]]);
+                        assert_eq!(a.parent, Some(index));
+                        assert_eq!(b.parent, Some(index));
+                        assert_eq!(node.aabb, a.aabb.union(&b.aabb));
+                        assert_eq!(node.height, a.height.max(b.height) + 1);
+                        assert!(a.height.max(b.height) - a.height.min(b.height) <= 1);
+                        stack.extend(children.iter());
+                    }
+                }
+            }
+            assert_eq!(leaves, self.leaves.len());
+            self.nodes[root].height
+        }
+    }
+
+    #[test]
+    fn stay_balanced_with_points() {
+        const COUNT: usize = 1024;
+
+        let mut world = World::new();
+        let entities = world.insert((), (0..COUNT * 2).map(|_| ())).to_vec();
+        let (collinear, stacked) = entities.split_at(COUNT);
+        let mut bvh = Bvh::new();
+        // Points in order along a line, and points all at the same place
+        for (i, &entity) in collinear.iter().enumerate() {
+            let point = Point3::new(i as f32, 0.0, 0.0);
+            bvh.insert(entity, &Aabb::new(point, point));
+        }
+        for &entity in stacked {
+            let point = Point3::new(-1.0, 2.0, 3.0);
+            bvh.insert(entity, &Aabb::new(point, point));
+        }
+        // An AVL tree of 2048 leaves is never taller than 1.44 * log2(2048) ≈ 16
+        assert!(bvh.validate() <= 16);
+
+        let found = |bvh: &Bvh, min: f32, max: f32| {
+            let aabb = Aabb::new(Point3::new(min, -1.0, -1.0), Point3::new(max, 1.0, 1.0));
+            bvh.query_aabb(&aabb).into_iter().collect::<HashSet<_>>()
+        };
+        assert_eq!(
+            found(&bvh, 99.5, 110.5),
+            collinear[100..=110].iter().copied().collect()
+        );
+
+        for &entity in collinear.iter().step_by(2).chain(&stacked[1..]) {
+            bvh.remove(entity);
+        }
+        // ...and one of 513 leaves is never taller than 1.44 * log2(513) ≈ 13
+        assert!(bvh.validate() <= 13);
+        assert_eq!(
+            found(&bvh, 99.5, 110.5),
+            collinear[101..=109].iter().step_by(2).copied().collect()
+        );
+        assert_eq!(
+            bvh.nearest(&Point3::new(-1.0, 2.0, 3.0), 1),
+            vec![(stacked[0], 0.0)]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use super::{compare_distances, distance_squared, keep_nearest, Ray, RayHit};
+use crate::components::Aabb;
+use crate::ecs::entity::Entity;
+use crate::math::Point3;
+use std::collections::{HashMap, HashSet};
+
+/// Entities spanning more cells than this are kept in the list of large entities instead of the cells
+const MAX_CELLS_PER_ENTITY: i64 = 64;
+
+/// Cells are clamped to this distance from the origin along each axis, so that the bounds reaching infinity still map
+/// to cells, and the arithmetic on the neighboring cells does not overflow
+const MAX_CELL: i32 = i32::MAX / 4;
+
+type Cell = [i32; 3];
+
+/// Uniform grid of cubic cells, each of which lists the entities whose bounds overlap it
+#[derive(Clone, Debug)]
+pub(super) struct Grid {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<Entity>>,
+    large: HashSet<Entity>,
+    /// Range of the cells that have ever been occupied, which only grows until the grid is cleared
+    occupied: Option<(Cell, Cell)>,
+}
+
+impl Grid {
+    pub(super) fn new(cell_size: f32) -> Grid {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        Grid {
+            cell_size,
+            cells: HashMap::new(),
+            large: HashSet::new(),
+            occupied: None,
+        }
+    }
+
+    pub(super) fn insert(&mut self, entity: Entity, aabb: &Aabb) {
+        let (min, max) = self.cell_range(aabb);
+        if cell_count_exceeds(&min, &max, MAX_CELLS_PER_ENTITY) {
+            self.large.insert(entity);
+            return;
+        }
+        for cell in cells_in(min, max) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+        self.occupied = Some(match self.occupied {
+            Some((occupied_min, occupied_max)) => (
+                [0, 1, 2].map(|i| occupied_min[i].min(min[i])),
+                [0, 1, 2].map(|i| occupied_max[i].max(max[i])),
+            ),
+            None => (min, max),
+        });
+    }
+
+    pub(super) fn remove(&mut self, entity: Entity, aabb: &Aabb) {
+        if self.large.remove(&entity) {
+            return;
+        }
+        let (min, max) = self.cell_range(aabb);
+        for cell in cells_in(min, max) {
+            if let Some(entities) = self.cells.get_mut(&cell) {
+                entities.retain(|e| *e != entity);
+                if entities.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    pub(super) fn query_aabb(&self, aabb: &Aabb, entries: &HashMap<Entity, Aabb>) -> Vec<Entity> {
+        let mut candidates: HashSet<Entity> = self.large.iter().copied().collect();
+        if let Some((min, max)) = self.clip_to_occupied(self.cell_range(aabb)) {
+            if cell_count_exceeds(&min, &max, entries.len() as i64) {
+                candidates.extend(entries.keys());
+            } else {
+                for cell in cells_in(min, max) {
+                    candidates.extend(self.cells.get(&cell).into_iter().flatten());
+                }
+            }
+        }
+        candidates
+            .into_iter()
+            .filter(|entity| entries[entity].intersects(aabb))
+            .collect()
+    }
+
+    /// Find the hits, walking through the cells along the ray
+    pub(super) fn ray_cast(
+        &self,
+        ray: &Ray,
+        max_distance: f32,
+        entries: &HashMap<Entity, Aabb>,
+    ) -> Vec<RayHit> {
+        let mut candidates: HashSet<Entity> = self.large.iter().copied().collect();
+        if let Some((occupied_min, occupied_max)) = self.occupied {
+            let bounds = Aabb::new(
+                self.cell_min_corner(&occupied_min),
+                self.cell_min_corner(&[0, 1, 2].map(|i| occupied_max[i] + 1)),
+            );
+            if let Some(enter) = ray
+                .intersect_aabb(&bounds)
+                .filter(|enter| *enter <= max_distance)
+            {
+                let start = ray.point_at(enter);
+                let mut cell = [0, 1, 2].map(|i| {
+                    self.cell_of(start[i])
+                        .max(occupied_min[i])
+                        .min(occupied_max[i])
+                });
+                let step = [0, 1, 2].map(|i| ray.direction[i].signum() as i32);
+                let mut next = [f32::INFINITY; 3];
+                let mut delta = [f32::INFINITY; 3];
+                for i in 0..3 {
+                    if ray.direction[i] != 0.0 {
+                        let boundary = (cell[i] + (step[i] > 0) as i32) as f32 * self.cell_size;
+                        next[i] = (boundary - ray.origin[i]) / ray.direction[i];
+                        delta[i] = self.cell_size / ray.direction[i].abs();
+                    }
+                }
+                loop {
+                    candidates.extend(self.cells.get(&cell).into_iter().flatten());
+                    let axis = (0..3)
+                        .min_by(|&a, &b| compare_distances(next[a], next[b]))
+                        .unwrap();
+                    if next[axis] > max_distance {
+                        break;
+                    }
+                    cell[axis] += step[axis];
+                    if cell[axis] < occupied_min[axis] || cell[axis] > occupied_max[axis] {
+                        break;
+                    }
+                    next[axis] += delta[axis];
+                }
+            }
+        }
+        candidates
+            .into_iter()
+            .filter_map(|entity| {
+                ray.intersect_aabb(&entries[&entity])
+                    .filter(|distance| *distance <= max_distance)
+                    .map(|distance| RayHit { entity, distance })
+            })
+            .collect()
+    }
+
+    /// Find the nearest entities with squared distances, searching through the shells of cells around the point
+    pub(super) fn nearest(
+        &self,
+        point: &Point3<f32>,
+        k: usize,
+        entries: &HashMap<Entity, Aabb>,
+    ) -> Vec<(Entity, f32)> {
+        let mut visited: HashSet<Entity> = self.large.iter().copied().collect();
+        let mut candidates: Vec<(Entity, f32)> = visited
+            .iter()
+            .map(|&entity| (entity, distance_squared(point, &entries[&entity])))
+            .collect();
+        let (occupied_min, occupied_max) = match self.occupied {
+            Some(occupied) => occupied,
+            None => return keep_nearest(candidates, k),
+        };
+
+        let center = [0, 1, 2].map(|i| self.cell_of(point[i]));
+        // Start from the first shell that reaches the occupied cells
+        let first_radius = (0..3)
+            .map(|i| (occupied_min[i] - center[i]).max(center[i] - occupied_max[i]))
+            .fold(0, i32::max);
+        for radius in first_radius.. {
+            let min = [0, 1, 2].map(|i| (center[i] - radius).max(occupied_min[i]));
+            let max = [0, 1, 2].map(|i| (center[i] + radius).min(occupied_max[i]));
+            if cell_count_exceeds(&min, &max, entries.len() as i64) {
+                // Going through all entries is cheaper than going through the cells
+                let all = entries
+                    .iter()
+                    .map(|(&entity, aabb)| (entity, distance_squared(point, aabb)))
+                    .collect();
+                return keep_nearest(all, k);
+            }
+
+            for cell in cells_in(min, max) {
+                if (0..3).all(|i| (cell[i] - center[i]).abs() < radius) {
+                    continue;
+                }
+                for &entity in self.cells.get(&cell).into_iter().flatten() {
+                    if visited.insert(entity) {
+                        candidates.push((entity, distance_squared(point, &entries[&entity])));
+                    }
+                }
+            }
+
+            if min == occupied_min && max == occupied_max {
+                break;
+            }
+            // The entities that have not been found yet are only in the cells beyond this shell
+            if candidates.len() >= k {
+                candidates.sort_by(|a, b| compare_distances(a.1, b.1));
+                let reach = radius as f32 * self.cell_size;
+                if candidates[k - 1].1 <= reach * reach {
+                    break;
+                }
+            }
+        }
+        keep_nearest(candidates, k)
+    }
+
+    /// Get the cell along an axis, where NaN is in the cell at the origin
+    fn cell_of(&self, coordinate: f32) -> i32 {
+        let cell = (coordinate / self.cell_size).floor();
+        cell.clamp(-MAX_CELL as f32, MAX_CELL as f32) as i32
+    }
+
+    fn cell_min_corner(&self, cell: &Cell) -> Point3<f32> {
+        Point3::new(
+            cell[0] as f32 * self.cell_size,
+            cell[1] as f32 * self.cell_size,
+            cell[2] as f32 * self.cell_size,
+        )
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (Cell, Cell) {
+        (
+            [0, 1, 2].map(|i| self.cell_of(aabb.min[i])),
+            [0, 1, 2].map(|i| self.cell_of(aabb.max[i])),
+        )
+    }
+
+    fn clip_to_occupied(&self, (min, max): (Cell, Cell)) -> Option<(Cell, Cell)> {
+        let (occupied_min, occupied_max) = self.occupied?;
+        let min = [0, 1, 2].map(|i| min[i].max(occupied_min[i]));
+        let max = [0, 1, 2].map(|i| max[i].min(occupied_max[i]));
+        if (0..3).all(|i| min[i] <= max[i]) {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+}
+
+/// Check whether there are more cells than `limit` in the range, stopping as soon as the count exceeds it
+fn cell_count_exceeds(min: &Cell, max: &Cell, limit: i64) -> bool {
+    let mut count = 1i64;
+    for i in 0..3 {
+        let span = i64::from(max[i]) - i64::from(min[i]) + 1;
+        count = count.saturating_mul(span);
+        if count > limit {
+            return true;
+        }
+    }
+    false
+}
+
+fn cells_in(min: Cell, max: Cell) -> impl Iterator<Item = Cell> {
+    (min[0]..=max[0]).flat_map(move |x| {
+        (min[1]..=max[1]).flat_map(move |y| (min[2]..=max[2]).map(move |z| [x, y, z]))
+    })
+}
@@ -2,7 +2,7 @@
 //!
 //! # System Execution Order
 //!
-//! 1. Do your stuff with [`Position`], [`Rotation`], and [`Parent`] components
+//! 1. Do your stuff with [`Position`], [`Rotation`], [`Scale`], and [`Parent`] components
 //! 1. **Flush**
 //! 1. Combination of
 //!    - `hierarchy_sync`
@@ -11,14 +11,25 @@
 //! 1. **Flush**
 //! 1. `hierarchical_transform_update`
 //! 1. **Flush**
-//! 1. Do late updates that do not touch anything among [`Position`], [`Rotation`], and [`Parent`]
-//!    components
+//! 1. Do late updates that do not touch anything among [`Position`], [`Rotation`], [`Scale`], and
+//!    [`Parent`] components
 //! 1. Do thread-local stuff like rendering that uses [`WorldTransform`]
 //!
+//! The 2D systems, e.g. `world_transform2_update`, follow the same order with [`Position2`], [`Rotation2`],
+//! [`Scale2`], and [`WorldTransform2`] components, sharing `hierarchy_sync` with the 3D systems.
+//!
+//! To compute world-space bounds from [`Aabb`] and [`BoundingSphere`] components, run `bounds_update` after the last
+//! flush above. To find entities by their location through [`SpatialIndex`], run `spatial_index_update` after that,
+//! with another flush in between.
+//!
+//! When the simulation runs in fixed steps, run `previous_world_transform_update` right before each step, and
+//! `transform_interpolation` after the transform systems, then render with [`InterpolatedTransform`] instead.
+//!
 //! # That is too complicated
 //!
 //! If you are adding these systems to the [`Schedule`] [`Builder`] provided by [`legion`], consider
-//! using the [`TransformBundle`] helper struct. For an example usage, see the example below.
+//! using the [`TransformBundle`] helper struct, or [`Transform2dBundle`] for 2D games. For an example usage, see the
+//! example below.
 //!
 //! # Examples
 //!
@@ -98,22 +109,18 @@
 //!
 //! // `WorldTransform` is inserted by `world_transform_update` system
 //! let moving_transform = *world.get_component::<WorldTransform>(moving).unwrap();
-//! assert!((moving_transform.m14 - 0.1).abs() < EPSILON); // x coordinate
-//! assert!((moving_transform.m24 - -0.02).abs() < EPSILON); // y coordinate
-//! assert_eq!(moving_transform.m34, 0.0); // z coordinate
+//! let moving_position = moving_transform.global_position();
+//! assert!((moving_position.vector - Vector3::new(0.1, -0.02, 0.0)).norm() < EPSILON);
 //!
 //! // The position follows the parent entity
 //! let rotating_transform = *world.get_component::<WorldTransform>(rotating).unwrap();
-//! assert!((rotating_transform.m14 - 0.1).abs() < EPSILON); // x coordinate
-//! assert!((rotating_transform.m24 - -0.02).abs() < EPSILON); // y coordinate
-//! assert_eq!(rotating_transform.m34, 0.0); // z coordinate
+//! let rotating_position = rotating_transform.global_position();
+//! assert!((rotating_position.vector - Vector3::new(0.1, -0.02, 0.0)).norm() < EPSILON);
 //!
 //! // The rotation is propagated to the `WorldTransform`
-//! let mut rotation = *rotating_transform;
-//! rotation.m14 -= rotating_transform.m14;
-//! rotation.m24 -= rotating_transform.m24;
-//! rotation.m34 -= rotating_transform.m34;
-//! assert_eq!(rotation, UnitQuaternion::new(Vector3::new(0.0, 0.03, 0.0)).to_homogeneous());
+//! let rotation = rotating_transform.global_rotation();
+//! let expected = UnitQuaternion::new(Vector3::new(0.0, 0.03, 0.0));
+//! assert!((rotation.coords - expected.coords).norm() < EPSILON);
 //!
 //! // The position relative to the parent is still at zero
 //! let parent_transform = *world.get_component::<ParentTransform>(rotating).unwrap();
@@ -125,28 +132,75 @@
 //!
 //! [`Position`]: ../components/struct.Position.html
 //! [`Rotation`]: ../components/struct.Rotation.html
+//! [`Scale`]: ../components/struct.Scale.html
 //! [`Parent`]: ../components/struct.Parent.html
 //! [`WorldTransform`]: ../components/struct.WorldTransform.html
+//! [`Position2`]: ../components/struct.Position2.html
+//! [`Rotation2`]: ../components/struct.Rotation2.html
+//! [`Scale2`]: ../components/struct.Scale2.html
+//! [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+//! [`InterpolatedTransform`]: ../components/struct.InterpolatedTransform.html
+//! [`Aabb`]: ../components/struct.Aabb.html
+//! [`BoundingSphere`]: ../components/struct.BoundingSphere.html
+//! [`SpatialIndex`]: ../spatial_index/struct.SpatialIndex.html
 //! [`Schedule`]: ../../legion/schedule/struct.Schedule.html
 //! [`Builder`]: ../../legion/schedule/struct.Builder.html
 //! [`legion`]: ../../legion/index.html
 //! [`TransformBundle`]: ./struct.TransformBundle.html
+//! [`Transform2dBundle`]: ./struct.Transform2dBundle.html
 
+mod animation;
+mod bounds_update;
+mod change_tracking;
+mod floating_origin;
 mod hierarchical_transform_update;
 mod hierarchy_sync;
+mod simple_transform2_update;
 mod simple_transform_update;
+mod spatial_index_update;
+mod transform_interpolation;
+mod tween;
 
-pub use self::hierarchical_transform_update::build_hierarchical_transform_update_system;
-pub use self::hierarchy_sync::build_hierarchy_sync_system;
+pub use self::animation::{build_animation_system, AnimationSystem};
+pub use self::bounds_update::{build_bounds_update_system, BoundsUpdateSystem};
+pub use self::floating_origin::{build_floating_origin_system, FloatingOriginSystem};
+pub use self::hierarchical_transform_update::{
+    build_hierarchical_transform2_update_system, build_hierarchical_transform_update_system,
+    HierarchicalTransform2UpdateCount, HierarchicalTransform2UpdateSystem,
+    HierarchicalTransformUpdateCount, HierarchicalTransformUpdateSystem,
+};
+pub use self::hierarchy_sync::{build_hierarchy_sync_system, HierarchySyncSystem};
+pub use self::simple_transform2_update::{
+    build_parent_transform2_update_system, build_world_transform2_update_system,
+    ParentTransform2UpdateSystem, WorldTransform2UpdateSystem,
+};
 pub use self::simple_transform_update::{
     build_parent_transform_update_system, build_world_transform_update_system,
+    ParentTransformUpdateSystem, WorldTransformUpdateSystem,
+};
+pub use self::spatial_index_update::{build_spatial_index_update_system, SpatialIndexUpdateSystem};
+pub use self::transform_interpolation::{
+    build_previous_world_transform_update_system, build_transform_interpolation_system,
+    PreviousWorldTransformUpdateSystem, TransformInterpolationSystem,
 };
+pub use self::tween::{build_tween_system, TweenSystem};
+use crate::components::{register_components, OrphanPolicy};
+use crate::core::registry::ComponentRegistry;
 use crate::core::systems::{ScheduleBuilder, SystemBundle, SystemOrder};
 use crate::ecs::world::World;
 
 /// [`SystemBundle`] that adds transform and hierarchy related systems
 ///
+/// This also registers all transform components to the [`ComponentRegistry`] resource, inserting the
+/// resource if it does not exist, and inserts the [`OrphanPolicy`] resource.
+///
+/// `hierarchy_sync` system is only added once to a world, even when [`Transform2dBundle`] adds it
+/// as well. The bundle built first adds the system and inserts its [`OrphanPolicy`].
+///
 /// [`SystemBundle`]: ../../game_engine_core/systems/trait.SystemBundle.html
+/// [`ComponentRegistry`]: ../../game_engine_core/registry/struct.ComponentRegistry.html
+/// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+/// [`Transform2dBundle`]: ./struct.Transform2dBundle.html
 #[allow(missing_copy_implementations)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TransformBundle {
@@ -160,6 +214,10 @@ pub struct TransformBundle {
     pub end_order: SystemOrder,
     /// Whether to automatically add flush command right before adding the last system
     pub flush_before_end: bool,
+    /// The [`OrphanPolicy`] for entities without their own [`OrphanPolicy`] component
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    pub orphan_policy: OrphanPolicy,
 }
 
 impl TransformBundle {
@@ -169,6 +227,7 @@ impl TransformBundle {
             start_order,
             end_order: start_order,
             flush_before_end: false,
+            orphan_policy: OrphanPolicy::default(),
         }
     }
 
@@ -178,6 +237,7 @@ impl TransformBundle {
             start_order,
             end_order: start_order,
             flush_before_end: true,
+            orphan_policy: OrphanPolicy::default(),
         }
     }
 
@@ -204,11 +264,26 @@ impl TransformBundle {
         self.flush_before_end = false;
         self
     }
+
+    /// Chain method to construct the bundle; sets the [`OrphanPolicy`] for all entities
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    pub fn with_orphan_policy(mut self, orphan_policy: OrphanPolicy) -> Self {
+        self.orphan_policy = orphan_policy;
+        self
+    }
 }
 
 impl SystemBundle for TransformBundle {
-    fn build_systems(self, _world: &mut World, builder: &mut ScheduleBuilder) {
-        builder.add_system_create_fn(self.start_order, build_hierarchy_sync_system);
+    fn build_systems(self, world: &mut World, builder: &mut ScheduleBuilder) {
+        register_components(
+            &mut world
+                .resources
+                .get_mut_or_insert_with(ComponentRegistry::new)
+                .expect("the component registry is just inserted"),
+        );
+
+        add_hierarchy_sync(world, builder, self.start_order, self.orphan_policy);
         builder.add_system_create_fn(self.start_order, build_world_transform_update_system);
         builder.add_system_create_fn(self.start_order, build_parent_transform_update_system);
 
@@ -218,3 +293,152 @@ impl SystemBundle for TransformBundle {
         builder.add_system_create_fn(self.end_order, build_hierarchical_transform_update_system);
     }
 }
+
+/// [`SystemBundle`] that adds 2D transform and hierarchy related systems
+///
+/// This is the 2D counterpart of [`TransformBundle`] with the same ordering semantics, and registers the components
+/// and inserts the resources in the same way.
+///
+/// Both bundles add `hierarchy_sync` system, which must only run once per schedule, so it is only added once to a
+/// world. When 2D and 3D transforms are used in the same schedule, the bundle built first adds the system and inserts
+/// its [`OrphanPolicy`] resource, and the other bundle leaves both as they are. [`without_hierarchy_sync`] leaves them
+/// to [`TransformBundle`] regardless of the order.
+///
+/// [`SystemBundle`]: ../../game_engine_core/systems/trait.SystemBundle.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`without_hierarchy_sync`]: #method.without_hierarchy_sync
+/// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transform2dBundle {
+    /// The [`SystemOrder`] for first three systems
+    ///
+    /// [`SystemOrder`]: ../../game_engine_core/systems/struct.SystemOrder.html
+    pub start_order: SystemOrder,
+    /// The [`SystemOrder`] for the last system
+    ///
+    /// [`SystemOrder`]: ../../game_engine_core/systems/struct.SystemOrder.html
+    pub end_order: SystemOrder,
+    /// Whether to automatically add flush command right before adding the last system
+    pub flush_before_end: bool,
+    /// The [`OrphanPolicy`] for entities without their own [`OrphanPolicy`] component
+    ///
+    /// This is not used without `hierarchy_sync`, or when another bundle has added it.
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    pub orphan_policy: OrphanPolicy,
+    /// Whether to add `hierarchy_sync` system along with the 2D systems, unless another bundle has added it
+    pub hierarchy_sync: bool,
+}
+
+impl Transform2dBundle {
+    /// Create a new instance with the given system execution order
+    pub fn new(start_order: SystemOrder) -> Self {
+        Self {
+            start_order,
+            end_order: start_order,
+            flush_before_end: false,
+            orphan_policy: OrphanPolicy::default(),
+            hierarchy_sync: true,
+        }
+    }
+
+    /// Create a new instance with the given system execution order with automatic flushing
+    pub fn new_flush(start_order: SystemOrder) -> Self {
+        Self {
+            start_order,
+            end_order: start_order,
+            flush_before_end: true,
+            orphan_policy: OrphanPolicy::default(),
+            hierarchy_sync: true,
+        }
+    }
+
+    /// Chain method to construct the bundle; sets the order of the first three systems
+    pub fn with_start_order(mut self, start_order: SystemOrder) -> Self {
+        self.start_order = start_order;
+        self
+    }
+
+    /// Chain method to construct the bundle; sets the order of the last system
+    pub fn with_end_order(mut self, end_order: SystemOrder) -> Self {
+        self.end_order = end_order;
+        self
+    }
+
+    /// Chain method to construct the bundle; automatically add flush command before the last system
+    pub fn with_flush(mut self) -> Self {
+        self.flush_before_end = true;
+        self
+    }
+
+    /// Chain method to construct the bundle; do not add flush command before the last system
+    pub fn without_flush(mut self) -> Self {
+        self.flush_before_end = false;
+        self
+    }
+
+    /// Chain method to construct the bundle; sets the [`OrphanPolicy`] for all entities
+    ///
+    /// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+    pub fn with_orphan_policy(mut self, orphan_policy: OrphanPolicy) -> Self {
+        self.orphan_policy = orphan_policy;
+        self
+    }
+
+    /// Chain method to construct the bundle; add `hierarchy_sync` system along with the 2D systems
+    pub fn with_hierarchy_sync(mut self) -> Self {
+        self.hierarchy_sync = true;
+        self
+    }
+
+    /// Chain method to construct the bundle; do not add `hierarchy_sync` system, e.g. when [`TransformBundle`] adds it
+    ///
+    /// [`TransformBundle`]: ./struct.TransformBundle.html
+    pub fn without_hierarchy_sync(mut self) -> Self {
+        self.hierarchy_sync = false;
+        self
+    }
+}
+
+impl SystemBundle for Transform2dBundle {
+    fn build_systems(self, world: &mut World, builder: &mut ScheduleBuilder) {
+        register_components(
+            &mut world
+                .resources
+                .get_mut_or_insert_with(ComponentRegistry::new)
+                .expect("the component registry is just inserted"),
+        );
+
+        if self.hierarchy_sync {
+            add_hierarchy_sync(world, builder, self.start_order, self.orphan_policy);
+        }
+        builder.add_system_create_fn(self.start_order, build_world_transform2_update_system);
+        builder.add_system_create_fn(self.start_order, build_parent_transform2_update_system);
+
+        if self.flush_before_end {
+            builder.add_flush(self.end_order);
+        }
+        builder.add_system_create_fn(self.end_order, build_hierarchical_transform2_update_system);
+    }
+}
+
+/// Resource that marks the worlds to which `hierarchy_sync` system has been added by one of the bundles
+struct HierarchySyncAdded;
+
+/// Add `hierarchy_sync` system and insert the [`OrphanPolicy`] resource, unless another bundle has done so
+///
+/// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+fn add_hierarchy_sync(
+    world: &mut World,
+    builder: &mut ScheduleBuilder,
+    order: SystemOrder,
+    orphan_policy: OrphanPolicy,
+) {
+    if world.resources.contains::<HierarchySyncAdded>() {
+        return;
+    }
+    world.resources.insert(HierarchySyncAdded);
+    world.resources.insert(orphan_policy);
+    builder.add_system_create_fn(order, build_hierarchy_sync_system);
+}
@@ -0,0 +1,197 @@
+use crate::animation::{AnimationEvent, AnimationPlayer};
+use crate::components::{Position, Rotation, Scale};
+use crate::core::events::Events;
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::core::time::Time;
+use crate::ecs::command::CommandBuffer;
+use crate::ecs::entity::Entity;
+use crate::ecs::query::{IntoQuery, Read};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::storage::Component;
+use crate::ecs::system::{SubWorld, SystemBuilder};
+use crate::ecs::world::World;
+
+/// System descriptor(builder) for playing animations of [`AnimationPlayer`] components
+///
+/// This is a wrapper for [`build_animation_system`].
+///
+/// [`AnimationPlayer`]: ../animation/struct.AnimationPlayer.html
+/// [`build_animation_system`]: ./fn.build_animation_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_animation_system))]
+pub struct AnimationSystem;
+
+/// Build a system that advances [`AnimationPlayer`] components by the delta time of [`Time`] resource, and writes the
+/// sampled values to [`Position`], [`Rotation`], and [`Scale`] components
+///
+/// The components are added to the entities that do not have them yet, and the properties that are not animated by
+/// the clips are left untouched. An [`AnimationEvent`] is written for each event the playback passes.
+///
+/// Only the players that are playing are advanced and sampled, so the components can be changed freely while the
+/// playback is paused or after it has finished. The components are only written when the sampled values differ from
+/// them, so that the other systems do not see unchanged entities as changed.
+///
+/// This system is not added by [`TransformBundle`]. Run it before the transform systems, with a flush in between so
+/// that the added components are picked up in the same frame. [`Time`] resource and [`Events`] resource for
+/// [`AnimationEvent`] are inserted if they do not exist.
+///
+/// [`AnimationPlayer`]: ../animation/struct.AnimationPlayer.html
+/// [`Time`]: ../../game_engine_core/time/struct.Time.html
+/// [`Position`]: ../components/struct.Position.html
+/// [`Rotation`]: ../components/struct.Rotation.html
+/// [`Scale`]: ../components/struct.Scale.html
+/// [`AnimationEvent`]: ../animation/struct.AnimationEvent.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+pub fn build_animation_system(world: &mut World) -> Box<dyn Schedulable> {
+    world.resources.get_or_insert_with(Time::default);
+    Events::<AnimationEvent>::ensure(world);
+
+    // Entities whose players are playing, reused between the runs
+    let mut playing = Vec::new();
+
+    SystemBuilder::new("Animation")
+        .read_resource::<Time>()
+        .write_resource::<Events<AnimationEvent>>()
+        .with_query(<Read<AnimationPlayer>>::query())
+        .read_component::<Position>()
+        .read_component::<Rotation>()
+        .read_component::<Scale>()
+        .write_component::<AnimationPlayer>()
+        .write_component::<Position>()
+        .write_component::<Rotation>()
+        .write_component::<Scale>()
+        .build(move |cmd, world, (time, events), query| {
+            let delta = time.delta_time();
+            playing.extend(
+                query
+                    .iter_entities(world)
+                    .filter(|(_, player)| player.is_playing())
+                    .map(|(entity, _)| entity),
+            );
+
+            let mut fired = Vec::new();
+            for entity in playing.drain(..) {
+                let pose = {
+                    let mut player = world.get_component_mut::<AnimationPlayer>(entity).unwrap();
+                    player.advance(delta, &mut fired);
+                    player.sample()
+                };
+                events.iter_write(fired.drain(..).map(|name| AnimationEvent { entity, name }));
+
+                if let Some(pose) = pose {
+                    write_sampled(cmd, world, entity, pose.position);
+                    write_sampled(cmd, world, entity, pose.rotation);
+                    write_sampled(cmd, world, entity, pose.scale);
+                }
+            }
+        })
+}
+
+/// Write the sampled value to the component of the entity, unless the property is not animated or the value is the same
+fn write_sampled<T>(cmd: &CommandBuffer, world: &mut SubWorld, entity: Entity, value: Option<T>)
+where
+    T: Component + PartialEq,
+{
+    let value = match value {
+        Some(value) => value,
+        None => return,
+    };
+    // Whether the current component is the same as the value, or `None` if there is no component
+    let unchanged = world
+        .get_component::<T>(entity)
+        .map(|component| *component == value);
+    match unchanged {
+        Some(true) => {}
+        Some(false) => *world.get_component_mut::<T>(entity).unwrap() = value,
+        None => cmd.add_component(entity, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_animation_system;
+    use crate::animation::{AnimationClip, AnimationEvent, AnimationPlayer};
+    use crate::components::*;
+    use crate::core::events::{EventReader, Events};
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::core::time::Time;
+    use crate::ecs::prelude::*;
+    use crate::systems::TransformBundle;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn animate_transform() {
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_create_fn(SystemOrder::numbered(0, 0), build_animation_system)
+            .with_flush(SystemOrder::numbered(0, 0))
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(1, 0)))
+            .build(&mut world);
+        let mut reader = EventReader::<AnimationEvent>::register(&mut world);
+        world
+            .resources
+            .get_mut::<Time>()
+            .unwrap()
+            .set_delta_duration(Duration::from_millis(500));
+
+        let clip = AnimationClip::from_ron(
+            r#"(
+                position: (keyframes: [(time: 0, value: (0, 0, 0)), (time: 1, value: (0, 2, 0))]),
+                events: [(time: 1, name: "open")],
+            )"#,
+        )
+        .unwrap();
+        let mut player = AnimationPlayer::new();
+        player.play(Arc::new(clip));
+        let door = world.insert((), Some((player, Scale::uniform(2.0))))[0];
+
+        schedule.execute(&mut world);
+        assert_eq!(
+            *world.get_component::<Position>(door).unwrap(),
+            Position::from_y(1.0)
+        );
+        // The transform systems pick up the added component in the same frame
+        assert_eq!(
+            world
+                .get_component::<WorldTransform>(door)
+                .unwrap()
+                .global_position(),
+            Position::from_y(1.0)
+        );
+        assert_eq!(
+            *world.get_component::<Scale>(door).unwrap(),
+            Scale::uniform(2.0)
+        );
+
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert_eq!(
+            *world.get_component::<Position>(door).unwrap(),
+            Position::from_y(2.0)
+        );
+        assert!(!world
+            .get_component::<AnimationPlayer>(door)
+            .unwrap()
+            .is_playing());
+        {
+            let events = world.resources.get::<Events<AnimationEvent>>().unwrap();
+            assert_eq!(
+                events.read(&mut reader).cloned().collect::<Vec<_>>(),
+                vec![AnimationEvent {
+                    entity: door,
+                    name: "open".to_owned(),
+                }]
+            );
+        }
+
+        // The finished player no longer overwrites the changes made by the game code
+        *world.get_component_mut::<Position>(door).unwrap() = Position::from_x(5.0);
+        schedule.execute(&mut world);
+        assert_eq!(
+            *world.get_component::<Position>(door).unwrap(),
+            Position::from_x(5.0)
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use super::change_tracking::{changed_since_last_run, ChunkVersions};
+use crate::components::{
+    Aabb, BoundingSphere, Children, HierarchicalBounds, Parent, WorldAabb, WorldBoundingSphere,
+    WorldTransform,
+};
+use crate::core::event_channel::EventChannel;
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::command::CommandBuffer;
+use crate::ecs::entity::Entity;
+use crate::ecs::query::{IntoQuery, Read};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::storage::Component;
+use crate::ecs::system::{SubWorld, SystemBuilder};
+use crate::ecs::world::World;
+use crate::hierarchy::{ancestors, descendants, HierarchyEvent};
+use std::collections::HashSet;
+use std::iter;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// System descriptor(builder) for computing [`WorldAabb`] and [`WorldBoundingSphere`] components
+///
+/// This is a wrapper for [`build_bounds_update_system`].
+///
+/// [`WorldAabb`]: ../components/struct.WorldAabb.html
+/// [`WorldBoundingSphere`]: ../components/struct.WorldBoundingSphere.html
+/// [`build_bounds_update_system`]: ./fn.build_bounds_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_bounds_update_system))]
+pub struct BoundsUpdateSystem;
+
+/// Build a system that transforms [`Aabb`] and [`BoundingSphere`] components by [`WorldTransform`] component, and
+/// writes the results to [`WorldAabb`] and [`WorldBoundingSphere`] components
+///
+/// For the entities with [`HierarchicalBounds`] component, the results also cover the bounds of all of their
+/// descendants. The world-space components are added to the entities that do not have them yet, and removed from the
+/// entities that no longer have any bounds.
+///
+/// Only the entities whose [`WorldTransform`], [`Aabb`], or [`BoundingSphere`] components are changed are updated,
+/// along with their ancestors with [`HierarchicalBounds`] component. The ancestors are also updated when their
+/// descendants leave them, which is told by [`HierarchyEvent`] events. The components are written only when their
+/// values differ, so that the systems that track their changes do not see the entities that have not moved.
+///
+/// This system is not added by [`TransformBundle`]. Run it after the transform systems, with a flush in between.
+/// [`EventChannel`] resource for [`HierarchyEvent`] is inserted if it does not exist.
+///
+/// [`Aabb`]: ../components/struct.Aabb.html
+/// [`BoundingSphere`]: ../components/struct.BoundingSphere.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`WorldAabb`]: ../components/struct.WorldAabb.html
+/// [`WorldBoundingSphere`]: ../components/struct.WorldBoundingSphere.html
+/// [`HierarchicalBounds`]: ../components/struct.HierarchicalBounds.html
+/// [`HierarchyEvent`]: ../hierarchy/enum.HierarchyEvent.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`EventChannel`]: ../../shrev/struct.EventChannel.html
+pub fn build_bounds_update_system(world: &mut World) -> Box<dyn Schedulable> {
+    let mut reader = world
+        .resources
+        .get_mut_or_insert_with(EventChannel::<HierarchyEvent>::new)
+        .unwrap()
+        .register_reader();
+
+    let transform_versions = Arc::new(ChunkVersions::default());
+    let aabb_versions = Arc::new(ChunkVersions::default());
+    let sphere_versions = Arc::new(ChunkVersions::default());
+    let hierarchical_versions = Arc::new(ChunkVersions::default());
+
+    SystemBuilder::new("BoundsUpdate")
+        .read_resource::<EventChannel<HierarchyEvent>>()
+        .read_component::<Parent>()
+        .read_component::<Children>()
+        .read_component::<Aabb>()
+        .read_component::<BoundingSphere>()
+        .read_component::<WorldTransform>()
+        .read_component::<HierarchicalBounds>()
+        .read_component::<WorldAabb>()
+        .read_component::<WorldBoundingSphere>()
+        .write_component::<WorldAabb>()
+        .write_component::<WorldBoundingSphere>()
+        .with_query(<Read<WorldTransform>>::query().filter(
+            changed_since_last_run::<WorldTransform>(&transform_versions),
+        ))
+        .with_query(<Read<Aabb>>::query().filter(changed_since_last_run::<Aabb>(&aabb_versions)))
+        .with_query(
+            <Read<BoundingSphere>>::query()
+                .filter(changed_since_last_run::<BoundingSphere>(&sphere_versions)),
+        )
+        .with_query(
+            <Read<HierarchicalBounds>>::query().filter(
+                changed_since_last_run::<HierarchicalBounds>(&hierarchical_versions),
+            ),
+        )
+        .build(move |cmd, world, events, queries| {
+            let (transforms, aabbs, spheres, hierarchical) = queries;
+
+            let mut changed = HashSet::<Entity>::new();
+            changed.extend(transforms.iter_entities(world).map(|(entity, _)| entity));
+            changed.extend(aabbs.iter_entities(world).map(|(entity, _)| entity));
+            changed.extend(spheres.iter_entities(world).map(|(entity, _)| entity));
+            changed.extend(hierarchical.iter_entities(world).map(|(entity, _)| entity));
+            transform_versions.advance();
+            aabb_versions.advance();
+            sphere_versions.advance();
+            hierarchical_versions.advance();
+
+            // The entities with hierarchical bounds that have any of their descendants changed or detached
+            let mut dirty = HashSet::<Entity>::new();
+            let mut mark_dirty = |world: &SubWorld, entity: Entity| {
+                for entity in iter::once(entity).chain(ancestors(world, entity)) {
+                    if world.get_component::<HierarchicalBounds>(entity).is_some() {
+                        dirty.insert(entity);
+                    }
+                }
+            };
+            for &entity in &changed {
+                mark_dirty(world, entity);
+            }
+            for event in events.read(&mut reader) {
+                let parent = match *event {
+                    HierarchyEvent::ChildAdded { parent, .. } => parent,
+                    HierarchyEvent::ChildRemoved { parent, .. } => parent,
+                    HierarchyEvent::Reparented {
+                        previous_parent, ..
+                    } => previous_parent,
+                    HierarchyEvent::DeletedWithParent { .. } => continue,
+                };
+                if world.is_alive(parent) {
+                    mark_dirty(world, parent);
+                }
+            }
+
+            for entity in changed.difference(&dirty) {
+                let aabb = own_aabb(world, *entity);
+                let sphere = own_sphere(world, *entity);
+                write_bounds::<WorldAabb, _>(cmd, world, *entity, aabb);
+                write_bounds::<WorldBoundingSphere, _>(cmd, world, *entity, sphere);
+            }
+            for entity in dirty {
+                let aabb = cover_descendants(world, entity, own_aabb, Aabb::union);
+                let sphere = cover_descendants(world, entity, own_sphere, BoundingSphere::union);
+                write_bounds::<WorldAabb, _>(cmd, world, entity, aabb);
+                write_bounds::<WorldBoundingSphere, _>(cmd, world, entity, sphere);
+            }
+        })
+}
+
+/// Get the world-space bounds of the entity's own [`Aabb`] component
+///
+/// [`Aabb`]: ../components/struct.Aabb.html
+fn own_aabb(world: &SubWorld, entity: Entity) -> Option<Aabb> {
+    let aabb = world.get_component::<Aabb>(entity)?;
+    let transform = world.get_component::<WorldTransform>(entity)?;
+    Some(aabb.transformed(transform.matrix()))
+}
+
+/// Get the world-space bounds of the entity's own [`BoundingSphere`] component
+///
+/// [`BoundingSphere`]: ../components/struct.BoundingSphere.html
+fn own_sphere(world: &SubWorld, entity: Entity) -> Option<BoundingSphere> {
+    let sphere = world.get_component::<BoundingSphere>(entity)?;
+    let transform = world.get_component::<WorldTransform>(entity)?;
+    Some(sphere.transformed(transform.matrix()))
+}
+
+/// Extend the bounds of the entity to cover the bounds of its descendants
+fn cover_descendants<T, B, F>(world: &SubWorld, entity: Entity, bounds: B, union: F) -> Option<T>
+where
+    B: Fn(&SubWorld, Entity) -> Option<T>,
+    F: Fn(&T, &T) -> T,
+{
+    // The descendants contribute their own bounds, so that nested hierarchical bounds are not counted twice
+    descendants(world, entity)
+        .filter_map(|descendant| bounds(world, descendant))
+        .fold(bounds(world, entity), |covered, descendant| {
+            Some(match covered {
+                Some(covered) => union(&covered, &descendant),
+                None => descendant,
+            })
+        })
+}
+
+/// Write the bounds to the component of the entity if they differ, adding or removing the component as needed
+fn write_bounds<C, T>(cmd: &CommandBuffer, world: &mut SubWorld, entity: Entity, bounds: Option<T>)
+where
+    C: Component + Deref<Target = T> + From<T>,
+    T: PartialEq,
+{
+    // Whether the current component is the same as the bounds, or `None` if there is no component
+    let unchanged = world
+        .get_component::<C>(entity)
+        .map(|component| Some(&**component) == bounds.as_ref());
+    match (bounds, unchanged) {
+        (Some(_), Some(true)) | (None, None) => {}
+        (Some(bounds), Some(false)) => {
+            *world.get_component_mut::<C>(entity).unwrap() = bounds.into()
+        }
+        (Some(bounds), None) => cmd.add_component(entity, C::from(bounds)),
+        (None, Some(_)) => cmd.remove_component::<C>(entity),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_bounds_update_system;
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3};
+    use crate::systems::TransformBundle;
+
+    #[test]
+    fn update_bounds() {
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_bounds_update_system)
+            .with_flush(SystemOrder::numbered(1, 0))
+            .build(&mut world);
+
+        let unit_box = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let unit_sphere = BoundingSphere::new(Point3::origin(), 1.0);
+        let root = world.insert(
+            (),
+            Some((Position::from_x(10.0), HierarchicalBounds, unit_box)),
+        )[0];
+        let child = world.insert(
+            (),
+            Some((
+                Parent::new(root),
+                Position::from_y(5.0),
+                Scale::uniform(2.0),
+                unit_box,
+                unit_sphere,
+            )),
+        )[0];
+        // A grandchild without bounds of its own does not contribute anything
+        let grandchild = world.insert((), Some((Parent::new(child), Position::from_z(100.0))))[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        assert_eq!(
+            **world.get_component::<WorldAabb>(child).unwrap(),
+            Aabb::new(Point3::new(8.0, 3.0, -2.0), Point3::new(12.0, 7.0, 2.0))
+        );
+        assert_eq!(
+            **world.get_component::<WorldBoundingSphere>(child).unwrap(),
+            BoundingSphere::new(Point3::new(10.0, 5.0, 0.0), 2.0)
+        );
+        assert_eq!(
+            **world.get_component::<WorldAabb>(root).unwrap(),
+            Aabb::new(Point3::new(8.0, -1.0, -2.0), Point3::new(12.0, 7.0, 2.0))
+        );
+        // The root has no sphere of its own, so it gets the one of its child
+        assert_eq!(
+            **world.get_component::<WorldBoundingSphere>(root).unwrap(),
+            BoundingSphere::new(Point3::new(10.0, 5.0, 0.0), 2.0)
+        );
+        assert!(world.get_component::<WorldAabb>(grandchild).is_none());
+
+        // Bounds follow the changes of the hierarchy and are removed along with the local bounds
+        world.remove_component::<Parent>(child);
+        world.remove_component::<BoundingSphere>(child);
+        *world.get_component_mut::<Position>(child).unwrap() = Position::from_y(-5.0);
+        schedule.execute(&mut world);
+        assert_eq!(
+            **world.get_component::<WorldAabb>(root).unwrap(),
+            Aabb::from_center_half_extents(
+                Point3::new(10.0, 0.0, 0.0),
+                Vector3::new(1.0, 1.0, 1.0)
+            )
+        );
+        assert_eq!(
+            **world.get_component::<WorldAabb>(child).unwrap(),
+            Aabb::new(Point3::new(-2.0, -7.0, -2.0), Point3::new(2.0, -3.0, 2.0))
+        );
+        assert!(world.get_component::<WorldBoundingSphere>(child).is_none());
+        assert!(world.get_component::<WorldBoundingSphere>(root).is_none());
+    }
+
+    #[test]
+    fn update_only_changed_bounds() {
+        use crate::systems::change_tracking::{changed_since_last_run, ChunkVersions};
+        use std::sync::Arc;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_bounds_update_system)
+            .with_flush(SystemOrder::numbered(1, 0))
+            .build(&mut world);
+
+        let unit_box = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let root = world.insert((), Some((Position::zero(), HierarchicalBounds)))[0];
+        let middle = world.insert((), Some((Parent::new(root), Position::zero())))[0];
+        let leaf = world.insert((), Some((Parent::new(middle), Position::zero(), unit_box)))[0];
+        let other = world.insert((), Some((Position::from_x(-10.0), unit_box)))[0];
+        for _ in 0..3 {
+            schedule.execute(&mut world);
+        }
+
+        let versions = Arc::new(ChunkVersions::default());
+        let query =
+            <Read<WorldAabb>>::query().filter(changed_since_last_run::<WorldAabb>(&versions));
+        let written = |world: &mut World| {
+            let count = query.iter_entities(world).count();
+            versions.advance();
+            count
+        };
+        assert_eq!(written(&mut world), 3);
+
+        // Nothing is written while nothing moves
+        schedule.execute(&mut world);
+        assert_eq!(written(&mut world), 0);
+
+        // Moving the leaf updates the bounds of its ancestor with hierarchical bounds
+        *world.get_component_mut::<Position>(leaf).unwrap() = Position::from_y(4.0);
+        schedule.execute(&mut world);
+        assert_eq!(
+            **world.get_component::<WorldAabb>(root).unwrap(),
+            Aabb::new(Point3::new(-1.0, 3.0, -1.0), Point3::new(1.0, 5.0, 1.0))
+        );
+        assert_eq!(
+            **world.get_component::<WorldAabb>(other).unwrap(),
+            Aabb::new(Point3::new(-11.0, -1.0, -1.0), Point3::new(-9.0, 1.0, 1.0))
+        );
+
+        // Detaching the leaf leaves the root without any bounds
+        world.remove_component::<Parent>(leaf);
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert!(world.get_component::<WorldAabb>(root).is_none());
+        assert!(world.get_component::<WorldAabb>(middle).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ecs::filter::{
+    filter_fns::component, ActiveFilter, ChunkFilterData, ComponentFilter, EntityFilterTuple,
+    Filter, Passthrough,
+};
+use crate::ecs::storage::{Component, ComponentStorage, ComponentTypeId};
+use std::marker::PhantomData;
+use std::slice::Iter;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Chunk versions of a component type that are seen by a system, shared between its query and the system itself
+#[derive(Debug, Default)]
+pub(crate) struct ChunkVersions {
+    last_run: AtomicU64,
+    newest: AtomicU64,
+}
+
+impl ChunkVersions {
+    /// Mark all chunks seen so far as read, so that they only match again after being written
+    pub(crate) fn advance(&self) {
+        self.last_run
+            .store(self.newest.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
+
+/// Chunk filter that matches chunks in which `T` was written since the last call to [`ChunkVersions::advance`]
+///
+/// This is the same as legion's `changed` filter, except that every chunk is compared against the newest version that
+/// was seen in the previous run. legion's filter compares against the newest version seen so far, so it skips a chunk
+/// that was written earlier than another chunk that happens to be iterated first.
+///
+/// [`ChunkVersions::advance`]: ./struct.ChunkVersions.html#method.advance
+#[derive(Debug)]
+pub(crate) struct ChangedSinceLastRun<T> {
+    versions: Arc<ChunkVersions>,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Clone for ChangedSinceLastRun<T> {
+    fn clone(&self) -> Self {
+        Self {
+            versions: Arc::clone(&self.versions),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> ActiveFilter for ChangedSinceLastRun<T> {}
+
+impl<'a, T: Component> Filter<ChunkFilterData<'a>> for ChangedSinceLastRun<T> {
+    type Iter = Iter<'a, ComponentStorage>;
+
+    fn collect(&self, source: ChunkFilterData<'a>) -> Self::Iter {
+        source.chunks.iter()
+    }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        let version = match item.components(ComponentTypeId::of::<T>()) {
+            Some(components) => components.version(),
+            None => return Some(false),
+        };
+        self.versions.newest.fetch_max(version, Ordering::Relaxed);
+        Some(version > self.versions.last_run.load(Ordering::Relaxed))
+    }
+}
+
+/// Create an entity filter for entities with `T` that was written since the last run, sharing the given versions
+pub(crate) fn changed_since_last_run<T: Component>(
+    versions: &Arc<ChunkVersions>,
+) -> EntityFilterTuple<ComponentFilter<T>, Passthrough, ChangedSinceLastRun<T>> {
+    EntityFilterTuple::new(
+        component::<T>().arch_filter,
+        Passthrough,
+        ChangedSinceLastRun {
+            versions: Arc::clone(versions),
+            phantom: PhantomData,
+        },
+    )
+}
@@ -0,0 +1,222 @@
+use crate::components::{
+    FloatingOriginTarget, Parent, Position, PreviousWorldTransform, Rotation, Scale, WorldTransform,
+};
+use crate::core::events::Events;
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::entity::Entity;
+use crate::ecs::filter::filter_fns::component;
+use crate::ecs::query::{IntoQuery, Read, Write};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::{SubWorld, SystemBuilder};
+use crate::ecs::world::World;
+use crate::floating_origin::{FloatingOrigin, OriginShifted};
+use crate::hierarchy::ancestors;
+use crate::math::{Matrix4, Vector3, U1, U3};
+use crate::utils::local_transform_matrix;
+
+/// System descriptor(builder) for shifting the world to keep the floating origin target near the origin
+///
+/// This is a wrapper for [`build_floating_origin_system`].
+///
+/// [`build_floating_origin_system`]: ./fn.build_floating_origin_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_floating_origin_system))]
+pub struct FloatingOriginSystem;
+
+/// Build a system that shifts the world when the entity with [`FloatingOriginTarget`] component gets too far from the
+/// origin
+///
+/// The distance is checked with the world position of the target, which is computed from the [`Position`],
+/// [`Rotation`], and [`Scale`] components of the target and its ancestors, so that the changes made in this frame are
+/// taken into account before [`WorldTransform`] components are updated. When it exceeds the threshold of
+/// [`FloatingOrigin`] resource, the [`Position`] of every root entity is moved so that the target is at the origin,
+/// and a [`Position`] is added to root entities that do not have one. The valid [`PreviousWorldTransform`] snapshots
+/// are moved by the same amount, so that the interpolation does not slide between the old and new origins. Then the
+/// offset of [`FloatingOrigin`] resource is updated, and an [`OriginShifted`] event is written.
+///
+/// This system is not added by [`TransformBundle`]. Run it before the transform systems, so that [`WorldTransform`]
+/// components are updated in the same frame. [`FloatingOrigin`] resource and [`Events`] resource for
+/// [`OriginShifted`] are inserted if they do not exist.
+///
+/// [`FloatingOriginTarget`]: ../components/struct.FloatingOriginTarget.html
+/// [`Position`]: ../components/struct.Position.html
+/// [`Rotation`]: ../components/struct.Rotation.html
+/// [`Scale`]: ../components/struct.Scale.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`FloatingOrigin`]: ../floating_origin/struct.FloatingOrigin.html
+/// [`PreviousWorldTransform`]: ../components/struct.PreviousWorldTransform.html
+/// [`OriginShifted`]: ../floating_origin/struct.OriginShifted.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+pub fn build_floating_origin_system(world: &mut World) -> Box<dyn Schedulable> {
+    world.resources.get_or_insert_with(FloatingOrigin::default);
+    Events::<OriginShifted>::ensure(world);
+
+    SystemBuilder::new("FloatingOrigin")
+        .write_resource::<FloatingOrigin>()
+        .write_resource::<Events<OriginShifted>>()
+        .with_query(<Read<FloatingOriginTarget>>::query())
+        .with_query(<Write<Position>>::query().filter(!component::<Parent>()))
+        .with_query(
+            <Read<WorldTransform>>::query()
+                .filter(!component::<Parent>() & !component::<Position>()),
+        )
+        .with_query(<Write<PreviousWorldTransform>>::query())
+        .read_component::<Parent>()
+        .read_component::<Position>()
+        .read_component::<Rotation>()
+        .read_component::<Scale>()
+        .build(|cmd, world, (origin, events), queries| {
+            let (targets, roots, roots_without_position, previous_world_transforms) = queries;
+            let target = match targets.iter_entities(world).next() {
+                Some((entity, _)) => entity,
+                None => return,
+            };
+            let shift = world_position(world, target);
+            if shift.norm() <= origin.threshold {
+                return;
+            }
+
+            for mut position in roots.iter(world) {
+                *position -= shift;
+            }
+            for (entity, _) in roots_without_position.iter_entities(world) {
+                cmd.add_component(entity, Position::from(-shift));
+            }
+            for mut previous in previous_world_transforms.iter(world) {
+                if let Some(matrix) = previous.matrix() {
+                    *previous = PreviousWorldTransform::from(matrix.append_translation(&-shift));
+                }
+            }
+
+            origin.shift(shift);
+            events.single_write(OriginShifted {
+                shift,
+                offset: origin.offset(),
+            });
+        })
+}
+
+/// Compute the world position of the entity from the local transform components of it and its ancestors
+fn world_position(world: &SubWorld, entity: Entity) -> Vector3<f32> {
+    let local = |entity| {
+        local_transform_matrix(
+            world.get_component::<Position>(entity).as_deref(),
+            world.get_component::<Rotation>(entity).as_deref(),
+            world.get_component::<Scale>(entity).as_deref(),
+        )
+    };
+    let matrix = ancestors(world, entity).fold(local(entity), |matrix: Matrix4<f32>, ancestor| {
+        local(ancestor) * matrix
+    });
+    matrix.fixed_slice::<U3, U1>(0, 3).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_floating_origin_system;
+    use crate::components::*;
+    use crate::core::events::{EventReader, Events};
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::floating_origin::{FloatingOrigin, OriginShifted};
+    use crate::math::{Matrix4, Vector3};
+    use crate::systems::TransformBundle;
+
+    #[test]
+    fn shift_world_when_target_is_far() {
+        let mut world = World::new();
+        world.resources.insert(FloatingOrigin::new(100.0));
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_create_fn(SystemOrder::numbered(0, 0), build_floating_origin_system)
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(1, 0)))
+            .with_flush(SystemOrder::numbered(0, 0))
+            .build(&mut world);
+        let mut reader = EventReader::<OriginShifted>::register(&mut world);
+
+        let player = world.insert((), Some((Position::from_x(50.0),)))[0];
+        let camera = world.insert(
+            (),
+            Some((
+                FloatingOriginTarget,
+                Parent::new(player),
+                Position::from_y(2.0),
+            )),
+        )[0];
+        let landmark = world.insert(
+            (),
+            Some((
+                Position::from_xyz(300.0, 0.0, 5.0),
+                PreviousWorldTransform::from(Matrix4::new_translation(&Vector3::new(
+                    290.0, 0.0, 5.0,
+                ))),
+            )),
+        )[0];
+        let snapshotless = world.insert((), Some((PreviousWorldTransform::invalid(),)))[0];
+        let rotated = world.insert((), Some((Rotation::identity(),)))[0];
+        for _ in 0..2 {
+            schedule.execute(&mut world);
+        }
+        {
+            let events = world.resources.get::<Events<OriginShifted>>().unwrap();
+            assert_eq!(events.read(&mut reader).count(), 0);
+        }
+
+        // The camera is now 250 units away from the origin, and the shift happens in the same frame
+        *world.get_component_mut::<Position>(player).unwrap() = Position::from_x(250.0);
+        schedule.execute(&mut world);
+
+        let shift = Vector3::new(250.0, 2.0, 0.0);
+        {
+            let events = world.resources.get::<Events<OriginShifted>>().unwrap();
+            let shifts = events.read(&mut reader).copied().collect::<Vec<_>>();
+            assert_eq!(
+                shifts,
+                vec![OriginShifted {
+                    shift,
+                    offset: shift.map(f64::from),
+                }]
+            );
+        }
+
+        let world_position = |entity| {
+            world
+                .get_component::<WorldTransform>(entity)
+                .unwrap()
+                .global_position()
+        };
+        assert_eq!(world_position(camera), Position::zero());
+        assert_eq!(
+            world_position(landmark),
+            Position::from_xyz(50.0, -2.0, 5.0)
+        );
+        assert_eq!(
+            world_position(rotated),
+            Position::from_xyz(-250.0, -2.0, 0.0)
+        );
+        // The snapshots are shifted along with the world, and the invalid ones stay invalid
+        assert_eq!(
+            world
+                .get_component::<PreviousWorldTransform>(landmark)
+                .unwrap()
+                .matrix()
+                .copied(),
+            Some(Matrix4::new_translation(&Vector3::new(40.0, -2.0, 5.0)))
+        );
+        assert!(!world
+            .get_component::<PreviousWorldTransform>(snapshotless)
+            .unwrap()
+            .is_valid());
+        // The child keeps its local position
+        assert_eq!(
+            *world.get_component::<Position>(camera).unwrap(),
+            Position::from_y(2.0)
+        );
+
+        let origin = *world.resources.get::<FloatingOrigin>().unwrap();
+        assert_eq!(
+            origin.to_absolute(&world_position(landmark)),
+            Vector3::new(300.0, 0.0, 5.0)
+        );
+    }
+}
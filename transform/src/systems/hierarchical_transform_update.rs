@@ -1,13 +1,25 @@
-use crate::components::{Children, Parent, ParentTransform, Position, Rotation, WorldTransform};
+use super::change_tracking::{changed_since_last_run, ChunkVersions};
+use crate::components::{
+    Children, Parent, ParentTransform, ParentTransform2, Position, Position2, Rotation, Rotation2,
+    Scale, Scale2, WorldTransform, WorldTransform2,
+};
 use crate::core::systems::{types, SystemDesc, SystemType};
-use crate::ecs::command::CommandBuffer;
 use crate::ecs::entity::Entity;
 use crate::ecs::filter::filter_fns::component;
 use crate::ecs::query::{IntoQuery, Read};
+use crate::ecs::resource::Resource;
 use crate::ecs::schedule::Schedulable;
+use crate::ecs::storage::Component;
 use crate::ecs::system::{SubWorld, SystemBuilder};
 use crate::ecs::world::World;
-use crate::utils::entity_should_have_transform_in_sub_world;
+use crate::hierarchy::{ancestors, HierarchyView};
+use crate::utils::{
+    entity_should_have_transform2_in_sub_world, entity_should_have_transform_in_sub_world,
+};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::ops::Mul;
+use std::sync::Arc;
 
 /// System descriptor(builder) for updating [`WorldTransform`] component of entities in hierarchy
 ///
@@ -19,56 +31,247 @@ use crate::utils::entity_should_have_transform_in_sub_world;
 #[system_desc(type(types::Parallel), fn(build_hierarchical_transform_update_system))]
 pub struct HierarchicalTransformUpdateSystem;
 
+/// System descriptor(builder) for updating [`WorldTransform2`] component of entities in hierarchy
+///
+/// This is a wrapper struct of [`build_hierarchical_transform2_update_system`].
+///
+/// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+/// [`build_hierarchical_transform2_update_system`]: ./fn.build_hierarchical_transform2_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_hierarchical_transform2_update_system))]
+pub struct HierarchicalTransform2UpdateSystem;
+
+/// ECS resource for the number of entities whose [`WorldTransform`] was updated by the last run of
+/// [`HierarchicalTransformUpdate`] system
+///
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`HierarchicalTransformUpdate`]: ./fn.build_hierarchical_transform_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct HierarchicalTransformUpdateCount(pub usize);
+
+/// ECS resource for the number of entities whose [`WorldTransform2`] was updated by the last run of
+/// [`HierarchicalTransform2Update`] system
+///
+/// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+/// [`HierarchicalTransform2Update`]: ./fn.build_hierarchical_transform2_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct HierarchicalTransform2UpdateCount(pub usize);
+
+impl From<usize> for HierarchicalTransformUpdateCount {
+    fn from(count: usize) -> Self {
+        HierarchicalTransformUpdateCount(count)
+    }
+}
+
+impl From<usize> for HierarchicalTransform2UpdateCount {
+    fn from(count: usize) -> Self {
+        HierarchicalTransform2UpdateCount(count)
+    }
+}
+
 /// Build a system that updates [`WorldTransform`] component of entities in hierarchy
 ///
+/// Only the subtrees that may have moved are recomputed: the ones under roots whose [`WorldTransform`] changed, and
+/// the ones under entities whose [`ParentTransform`] or [`Parent`] changed. Changes are detected per chunk like
+/// legion's `changed` filter, so all entities that share a chunk with a changed entity are also recomputed.
+///
+/// The subtrees are computed in parallel, walking each of them iteratively. The computed [`WorldTransform`]
+/// components are then written in place, and the missing ones are added through the command buffer. The number of
+/// updated entities is stored in [`HierarchicalTransformUpdateCount`] resource, which is inserted if it does not
+/// exist.
+///
 /// [`WorldTransform`]: ../components/struct.WorldTransform.html
-pub fn build_hierarchical_transform_update_system(_: &mut World) -> Box<dyn Schedulable> {
-    SystemBuilder::new("HierarchicalTransformUpdate")
+/// [`ParentTransform`]: ../components/struct.ParentTransform.html
+/// [`Parent`]: ../components/struct.Parent.html
+/// [`HierarchicalTransformUpdateCount`]: ./struct.HierarchicalTransformUpdateCount.html
+pub fn build_hierarchical_transform_update_system(world: &mut World) -> Box<dyn Schedulable> {
+    build_system::<WorldTransform, ParentTransform, HierarchicalTransformUpdateCount>(
+        world,
+        SystemBuilder::new("HierarchicalTransformUpdate")
+            .read_component::<Position>()
+            .read_component::<Rotation>()
+            .read_component::<Scale>(),
+        entity_should_have_transform_in_sub_world,
+    )
+}
+
+/// Build a system that updates [`WorldTransform2`] component of entities in hierarchy
+///
+/// This is the 2D counterpart of [`build_hierarchical_transform_update_system`], and the number of updated entities is
+/// stored in [`HierarchicalTransform2UpdateCount`] resource instead.
+///
+/// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+/// [`build_hierarchical_transform_update_system`]: ./fn.build_hierarchical_transform_update_system.html
+/// [`HierarchicalTransform2UpdateCount`]: ./struct.HierarchicalTransform2UpdateCount.html
+pub fn build_hierarchical_transform2_update_system(world: &mut World) -> Box<dyn Schedulable> {
+    build_system::<WorldTransform2, ParentTransform2, HierarchicalTransform2UpdateCount>(
+        world,
+        SystemBuilder::new("HierarchicalTransform2Update")
+            .read_component::<Position2>()
+            .read_component::<Rotation2>()
+            .read_component::<Scale2>(),
+        entity_should_have_transform2_in_sub_world,
+    )
+}
+
+/// Build the hierarchical update system for the given world and parent transform components
+///
+/// `builder` should declare read access to the local transform components that `should_have_transform` checks.
+fn build_system<W, P, C>(
+    world: &mut World,
+    builder: SystemBuilder,
+    should_have_transform: fn(Entity, &SubWorld) -> bool,
+) -> Box<dyn Schedulable>
+where
+    W: Component + Copy + Mul<P, Output = W>,
+    P: Component + Copy,
+    C: Resource + Default + From<usize>,
+{
+    world.resources.get_or_insert_with(C::default);
+
+    let root_versions = Arc::new(ChunkVersions::default());
+    let parent_versions = Arc::new(ChunkVersions::default());
+    let parent_transform_versions = Arc::new(ChunkVersions::default());
+
+    builder
+        .write_resource::<C>()
         .with_query(
-            <(Read<Children>, Read<WorldTransform>)>::query().filter(!component::<Parent>()),
+            <Read<W>>::query()
+                .filter(!component::<Parent>())
+                .filter(changed_since_last_run::<W>(&root_versions)),
         )
+        .with_query(
+            <Read<Parent>>::query().filter(changed_since_last_run::<Parent>(&parent_versions)),
+        )
+        .with_query(
+            <Read<P>>::query().filter(changed_since_last_run::<P>(&parent_transform_versions)),
+        )
+        .read_component::<Parent>()
         .read_component::<Children>()
-        .read_component::<ParentTransform>()
-        .read_component::<Position>()
-        .read_component::<Rotation>()
-        .build(|cmd, world, _resources, query| {
-            for (children, world_transform) in query.iter(world) {
-                for child in children.iter() {
-                    update_world_transform_recursive(cmd, world, *world_transform, *child);
+        .read_component::<P>()
+        .write_component::<W>()
+        .build(
+            move |cmd, world, count, (root_query, parent_query, parent_transform_query)| {
+                let mut dirty = root_query
+                    .iter_entities(world)
+                    .map(|(entity, _)| entity)
+                    .collect::<Vec<_>>();
+                dirty.extend(parent_query.iter_entities(world).map(|(entity, _)| entity));
+                dirty.extend(
+                    parent_transform_query
+                        .iter_entities(world)
+                        .map(|(entity, _)| entity),
+                );
+                root_versions.advance();
+                parent_versions.advance();
+                parent_transform_versions.advance();
+
+                let dirty_set = dirty.iter().copied().collect::<HashSet<_>>();
+                let mut started = HashSet::new();
+                let starts = dirty
+                    .into_iter()
+                    .filter(|entity| started.insert(*entity))
+                    .filter_map(|entity| subtree_start::<W>(world, &dirty_set, entity))
+                    .collect::<Vec<_>>();
+
+                let updates = starts
+                    .into_par_iter()
+                    .map(|stack| {
+                        compute_subtree_world_transforms::<W, P>(
+                            world,
+                            stack,
+                            should_have_transform,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut updated = 0;
+                for (entity, world_transform) in updates.into_iter().flatten() {
+                    updated += 1;
+                    if let Some(mut component) = world.get_component_mut::<W>(entity) {
+                        *component = world_transform;
+                    } else {
+                        cmd.add_component(entity, world_transform);
+                    }
                 }
-            }
-        })
+                **count = C::from(updated);
+            },
+        )
 }
 
-fn update_world_transform_recursive(
-    cmd: &mut CommandBuffer,
+/// Get the entities to start walking from to update the subtree of the given dirty entity
+///
+/// This returns `None` if the subtree is covered by the subtree of a dirty ancestor, or if it cannot be updated.
+fn subtree_start<W: Component + Copy>(
     world: &SubWorld,
-    parent_world_transform: WorldTransform,
+    dirty: &HashSet<Entity>,
     entity: Entity,
-) {
-    if !entity_should_have_transform_in_sub_world(entity, world) {
-        // This entity does not need to be updated
-        // Also skip for children
-        return;
+) -> Option<Vec<(Entity, W)>> {
+    match world.parent_of(entity) {
+        Some(parent) => {
+            if ancestors(world, entity).any(|ancestor| dirty.contains(&ancestor)) {
+                return None;
+            }
+            let parent_world_transform = *world.get_component::<W>(parent)?;
+            Some(vec![(entity, parent_world_transform)])
+        }
+        None => {
+            let world_transform = *world.get_component::<W>(entity)?;
+            let mut stack = Vec::new();
+            push_children(world, &mut stack, entity, world_transform);
+            Some(stack)
+        }
     }
+}
 
-    let parent_transform = if let Some(t) = world.get_component::<ParentTransform>(entity) {
-        *t
-    } else {
-        log::warn!(
-            "ParentTransform not found for an entity in a hierarchy: {}",
-            entity
-        );
-        return;
-    };
+/// Compute the world transforms of the entities in the stack, with the world transforms of their parents, and of all
+/// their descendants
+fn compute_subtree_world_transforms<W, P>(
+    world: &SubWorld,
+    mut stack: Vec<(Entity, W)>,
+    should_have_transform: fn(Entity, &SubWorld) -> bool,
+) -> Vec<(Entity, W)>
+where
+    W: Component + Copy + Mul<P, Output = W>,
+    P: Component + Copy,
+{
+    let mut updates = Vec::new();
+    // Guards against stale `Children` components that form a cycle
+    let mut visited = HashSet::new();
+
+    while let Some((entity, parent_world_transform)) = stack.pop() {
+        if !visited.insert(entity) || !should_have_transform(entity, world) {
+            // This entity does not need to be updated
+            // Also skip for children
+            continue;
+        }
+
+        let parent_transform = if let Some(t) = world.get_component::<P>(entity) {
+            *t
+        } else {
+            log::warn!(
+                "{} not found for an entity in a hierarchy: {}",
+                std::any::type_name::<P>(),
+                entity
+            );
+            continue;
+        };
 
-    let world_transform = parent_world_transform * parent_transform;
-    cmd.add_component(entity, world_transform);
+        let world_transform = parent_world_transform * parent_transform;
+        updates.push((entity, world_transform));
+        push_children(world, &mut stack, entity, world_transform);
+    }
+    updates
+}
 
+fn push_children<W: Copy>(
+    world: &SubWorld,
+    stack: &mut Vec<(Entity, W)>,
+    entity: Entity,
+    world_transform: W,
+) {
     if let Some(children) = world.get_component::<Children>(entity) {
-        for child in children.iter() {
-            update_world_transform_recursive(cmd, world, world_transform, *child);
-        }
+        stack.extend(children.iter().map(|child| (*child, world_transform)));
     }
 }
 
@@ -77,9 +280,10 @@ mod tests {
     use super::build_hierarchical_transform_update_system as build_system;
     use crate::components::*;
     use crate::ecs::prelude::*;
-    use crate::math::Vector3;
+    use crate::math::{Point3, Vector3};
     use smallvec::smallvec;
-    use std::f32::consts::FRAC_PI_4;
+    use std::collections::HashMap;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
     #[test]
     fn hierarchical_transform_update_system() {
@@ -171,4 +375,353 @@ mod tests {
             wt * right_pt * right_weapon_pt
         );
     }
+
+    #[test]
+    fn scaled_parent_with_rotated_child() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::TransformBundle;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let root = world.insert(
+            (),
+            Some((Position::from_y(1.0), Scale::from_xyz(2.0, 3.0, 1.0))),
+        )[0];
+        let child_rot = Rotation::from_axis_angle(Vector3::new(0.0, 0.0, 1.0) * FRAC_PI_2);
+        let child = world.insert(
+            (),
+            Some((
+                Parent::new(root),
+                Position::from_x(1.0),
+                child_rot,
+                Scale::uniform(0.5),
+            )),
+        )[0];
+        let grandchild = world.insert((), Some((Parent::new(child), Position::from_x(2.0))))[0];
+
+        // Hierarchy is synced on the first run, and transforms are propagated on the second run
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let world_point = |world: &World, entity, point: Point3<f32>| {
+            let transform = *world.get_component::<WorldTransform>(entity).unwrap();
+            transform.transform_point(&point)
+        };
+        assert_eq!(
+            world_point(&world, root, Point3::new(1.0, 1.0, 1.0)),
+            Point3::new(2.0, 4.0, 1.0)
+        );
+        // The child is placed in the scaled space of the root
+        assert!(
+            (world_point(&world, child, Point3::origin()) - Point3::new(2.0, 1.0, 0.0)).norm()
+                < 1e-6
+        );
+        // The local x axis of the child points to +y of the root, which is scaled by 3 and then by 0.5
+        assert!(
+            (world_point(&world, grandchild, Point3::origin()) - Point3::new(2.0, 4.0, 0.0)).norm()
+                < 1e-6
+        );
+
+        // Changing only the scale of the root updates the whole hierarchy
+        *world.get_component_mut::<Scale>(root).unwrap() = Scale::identity();
+        schedule.execute(&mut world);
+        assert!(
+            (world_point(&world, grandchild, Point3::origin()) - Point3::new(1.0, 2.0, 0.0)).norm()
+                < 1e-6
+        );
+    }
+
+    /// The previous recursive implementation, used as the reference
+    fn reference_world_transforms(world: &World) -> HashMap<Entity, WorldTransform> {
+        fn recurse(
+            world: &World,
+            result: &mut HashMap<Entity, WorldTransform>,
+            parent_world_transform: WorldTransform,
+            entity: Entity,
+        ) {
+            if !crate::utils::entity_should_have_transform_in_world(entity, world) {
+                return;
+            }
+            let parent_transform = match world.get_component::<ParentTransform>(entity) {
+                Some(t) => *t,
+                None => return,
+            };
+            let world_transform = parent_world_transform * parent_transform;
+            result.insert(entity, world_transform);
+            if let Some(children) = world.get_component::<Children>(entity) {
+                for child in children.iter() {
+                    recurse(world, result, world_transform, *child);
+                }
+            }
+        }
+
+        let mut result = HashMap::new();
+        let query =
+            <(Read<Children>, Read<WorldTransform>)>::query().filter(!component::<Parent>());
+        for (children, world_transform) in query.iter_immutable(world) {
+            for child in children.iter() {
+                recurse(world, &mut result, *world_transform, *child);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn large_hierarchy_matches_reference() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::TransformBundle;
+        use std::time::Instant;
+
+        const ROOTS: usize = 64;
+        const ENTITIES_PER_ROOT: usize = 500;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        for r in 0..ROOTS {
+            let root = world.insert(
+                (),
+                Some((
+                    Position::from_xyz(r as f32, 0.0, 0.0),
+                    Rotation::from_axis_angle(Vector3::new(0.0, 0.01 * r as f32, 0.0)),
+                )),
+            )[0];
+            let mut tree = vec![root];
+            for i in 1..ENTITIES_PER_ROOT {
+                // Deterministic but irregular shape with various depths
+                let parent = tree[(i * 7919) % i];
+                let f = i as f32;
+                let entity = world.insert(
+                    (),
+                    Some((
+                        Parent::new(parent),
+                        Position::from_xyz(f.sin(), f.cos(), 0.1),
+                        Rotation::from_axis_angle(Vector3::new(0.01, 0.02 * f.sin(), 0.0)),
+                        Scale::uniform(1.0 + 0.001 * (i % 3) as f32),
+                    )),
+                )[0];
+                tree.push(entity);
+            }
+        }
+
+        schedule.execute(&mut world);
+        let start = Instant::now();
+        schedule.execute(&mut world);
+        log::info!(
+            "Transforms of {} entities updated in {:?}",
+            ROOTS * ENTITIES_PER_ROOT,
+            start.elapsed()
+        );
+
+        let expected = reference_world_transforms(&world);
+        assert_eq!(expected.len(), ROOTS * (ENTITIES_PER_ROOT - 1));
+        for (entity, world_transform) in expected {
+            assert_eq!(
+                *world.get_component::<WorldTransform>(entity).unwrap(),
+                world_transform
+            );
+        }
+    }
+
+    #[test]
+    fn only_dirty_subtrees_are_updated() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::{HierarchicalTransformUpdateCount, TransformBundle};
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let root = world.insert((), Some((Position::from_y(1.0),)))[0];
+        // `a` and `b` have different components so that they are stored in different chunks
+        let a = world.insert((), Some((Parent::new(root), Position::from_x(1.0))))[0];
+        let b = world.insert(
+            (),
+            Some((
+                Parent::new(root),
+                Position::from_x(-1.0),
+                Rotation::identity(),
+            )),
+        )[0];
+        let leaves = world
+            .insert(
+                (),
+                vec![
+                    (Parent::new(a), Position::from_z(1.0)),
+                    (Parent::new(a), Position::from_z(2.0)),
+                    (Parent::new(b), Position::from_z(3.0)),
+                ],
+            )
+            .to_vec();
+
+        let updated_count = |world: &World| {
+            world
+                .resources
+                .get::<HierarchicalTransformUpdateCount>()
+                .unwrap()
+                .0
+        };
+        let assert_matches_reference = |world: &World| {
+            for (entity, world_transform) in reference_world_transforms(world) {
+                assert_eq!(
+                    *world.get_component::<WorldTransform>(entity).unwrap(),
+                    world_transform
+                );
+            }
+        };
+
+        for _ in 0..3 {
+            schedule.execute(&mut world);
+        }
+        assert_matches_reference(&world);
+
+        // Nothing is recomputed when nothing moved
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 0);
+
+        // Only `b` and its child are recomputed
+        *world.get_component_mut::<Position>(b).unwrap() = Position::from_x(-2.0);
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 2);
+        assert_matches_reference(&world);
+        assert_eq!(
+            world
+                .get_component::<WorldTransform>(leaves[2])
+                .unwrap()
+                .global_position(),
+            Position::from_xyz(-2.0, 1.0, 3.0)
+        );
+
+        // Changes in different chunks in the same frame are all picked up
+        *world.get_component_mut::<Position>(b).unwrap() = Position::from_x(-3.0);
+        *world.get_component_mut::<Position>(a).unwrap() = Position::from_x(3.0);
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 5);
+        assert_matches_reference(&world);
+
+        // Moving the root recomputes the whole hierarchy
+        *world.get_component_mut::<Position>(root).unwrap() = Position::from_y(2.0);
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 5);
+        assert_matches_reference(&world);
+
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 0);
+    }
+
+    #[test]
+    fn hierarchical_transform2_update() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::{
+            HierarchicalTransform2UpdateCount, Transform2dBundle, TransformBundle,
+        };
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(
+                TransformBundle::new_flush(SystemOrder::first())
+                    .with_orphan_policy(OrphanPolicy::DetachKeepWorld),
+            )
+            .with_system_bundle(Transform2dBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+        // `hierarchy_sync` is only added by the first bundle, which keeps its policy
+        assert_eq!(
+            *world.resources.get::<OrphanPolicy>().unwrap(),
+            OrphanPolicy::DetachKeepWorld
+        );
+
+        let root = world.insert((), Some((Position2::from_x(1.0),)))[0];
+        // `other` has different components from `root` so that they are stored in different chunks
+        let other = world.insert((), Some((Position2::from_y(5.0), Rotation2::identity())))[0];
+        let child = world.insert((), Some((Parent::new(root), Position2::from_x(1.0))))[0];
+        let grandchild = world.insert((), Some((Parent::new(child), Position2::from_y(1.0))))[0];
+
+        let updated_count = |world: &World| {
+            world
+                .resources
+                .get::<HierarchicalTransform2UpdateCount>()
+                .unwrap()
+                .0
+        };
+        let global_position = |world: &World, entity| {
+            world
+                .get_component::<WorldTransform2>(entity)
+                .unwrap()
+                .global_position()
+        };
+
+        for _ in 0..3 {
+            schedule.execute(&mut world);
+        }
+        assert_eq!(global_position(&world, child), Position2::from_xy(2.0, 0.0));
+        assert_eq!(
+            global_position(&world, grandchild),
+            Position2::from_xy(2.0, 1.0)
+        );
+        assert_eq!(
+            world.get_component::<Children>(root).unwrap().as_slice(),
+            &[child]
+        );
+
+        // Nothing is recomputed when nothing moved
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 0);
+
+        // Moving the child to another parent recomputes only its subtree
+        *world.get_component_mut::<Parent>(child).unwrap() = Parent::new(other);
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 2);
+        assert_eq!(global_position(&world, child), Position2::from_xy(1.0, 5.0));
+        assert_eq!(
+            global_position(&world, grandchild),
+            Position2::from_xy(1.0, 6.0)
+        );
+        assert!(world.get_component::<Children>(root).unwrap().is_empty());
+
+        schedule.execute(&mut world);
+        assert_eq!(updated_count(&world), 0);
+
+        // The child stays where it was in the world when its parent is deleted
+        world.delete(other);
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert!(world.get_component::<Parent>(child).is_none());
+        assert_eq!(
+            *world.get_component::<Position2>(child).unwrap(),
+            Position2::from_xy(1.0, 5.0)
+        );
+        assert_eq!(
+            global_position(&world, grandchild),
+            Position2::from_xy(1.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn deep_chain() {
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::systems::TransformBundle;
+
+        const DEPTH: usize = 20_000;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+
+        let mut last = world.insert((), Some((Position::from_x(1.0),)))[0];
+        for _ in 1..DEPTH {
+            last = world.insert((), Some((Position::from_x(1.0), Parent::new(last))))[0];
+        }
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let world_transform = *world.get_component::<WorldTransform>(last).unwrap();
+        assert_eq!(world_transform.global_position().x, DEPTH as f32);
+    }
 }
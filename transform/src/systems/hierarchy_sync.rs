@@ -1,13 +1,18 @@
-use crate::components::{Children, Parent, PreviousParent};
+use super::change_tracking::{changed_since_last_run, ChunkVersions};
+use crate::components::{Children, OrphanPolicy, Parent, PreviousParent, SiblingIndex};
+use crate::core::event_channel::EventChannel;
+use crate::core::events::Events;
 use crate::core::systems::{types, SystemDesc, SystemType};
 use crate::ecs::command::CommandBuffer;
 use crate::ecs::entity::Entity;
-use crate::ecs::filter::filter_fns::{changed, component};
+use crate::ecs::filter::filter_fns::component;
 use crate::ecs::query::{IntoQuery, Read, TryRead};
 use crate::ecs::schedule::Schedulable;
 use crate::ecs::system::{SubWorld, SystemBuilder};
 use crate::ecs::world::World;
+use crate::hierarchy::{self, HierarchyError, HierarchyEvent};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// System descriptor(builder) for maintaining hierarchy information from [`Parent`] components
 ///
@@ -25,26 +30,80 @@ pub struct HierarchySyncSystem;
 /// 1. Updating [`Children`] component of previous parents of entities which [`Parent`] components are removed
 /// 1. Doing the same for entities whose [`Parent`] components are added or modified, and update [`Children`] components
 ///    of the new parents, adding the component if it does not exist
+/// 1. While doing the above steps, rejecting any changes that create cycles in the hierarchy, and writing
+///    [`HierarchyError`] events for them
 /// 1. While doing the above steps, detecting any deleted entities that were parents of some other entities, and
-///    deleting those entities
+///    handling those children according to their [`OrphanPolicy`]
+/// 1. Removing deleted entities from [`Children`] components of their parents
+/// 1. Keeping [`Children`] components sorted by [`SiblingIndex`] components of the children, re-sorting them when the
+///    indices are modified
 /// 1. For the time being, checking every [`Parent`] component to see if any parent entities got deleted
 ///
 /// The last one will be removed for the sake of performance when [`legion`] provides detection of entity deletion.
 ///
+/// Every change applied to the hierarchy is written as a [`HierarchyEvent`], so that other systems do not have to
+/// compare [`Children`] components by themselves. A rejected change restores the previous [`Parent`] component, or
+/// removes it if there was none. The [`OrphanPolicy`] resource is inserted with its default value if it does not
+/// exist, and so are the [`Events`] resource for [`HierarchyError`] and the [`EventChannel`] resource for
+/// [`HierarchyEvent`].
+///
 /// [`Parent`]: ../components/struct.Parent.html
 /// [`Children`]: ../components/struct.Children.html
+/// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+/// [`HierarchyError`]: ../hierarchy/enum.HierarchyError.html
+/// [`HierarchyEvent`]: ../hierarchy/enum.HierarchyEvent.html
+/// [`SiblingIndex`]: ../components/struct.SiblingIndex.html
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+/// [`EventChannel`]: ../../shrev/struct.EventChannel.html
 /// [`legion`]: ../../legion/index.html
-pub fn build_hierarchy_sync_system(_: &mut World) -> Box<dyn Schedulable> {
+pub fn build_hierarchy_sync_system(world: &mut World) -> Box<dyn Schedulable> {
+    world.resources.get_or_insert_with(OrphanPolicy::default);
+    Events::<HierarchyError>::ensure(world);
+    world
+        .resources
+        .get_or_insert_with(EventChannel::<HierarchyEvent>::new);
+
+    // Parents of all entities with `Parent` component as of the last run
+    // This is used to find the ancestors of deleted entities.
+    let mut last_parents = HashMap::<Entity, Entity>::new();
+
+    let parent_versions = Arc::new(ChunkVersions::default());
+    let sibling_index_versions = Arc::new(ChunkVersions::default());
+
     SystemBuilder::new("HierarchySync")
+        .read_resource::<OrphanPolicy>()
+        .write_resource::<Events<HierarchyError>>()
+        .write_resource::<EventChannel<HierarchyEvent>>()
         // entities whose `Parent` component are removed
         .with_query(<Read<PreviousParent>>::query().filter(!component::<Parent>()))
         // entities whose `Parent` components are added or modified
-        .with_query(<(Read<Parent>, TryRead<PreviousParent>)>::query().filter(changed::<Parent>()))
-        // entities whose `Parent` components are not modified
-        .with_query(<Read<Parent>>::query().filter(!changed::<Parent>()))
+        .with_query(
+            <(Read<Parent>, TryRead<PreviousParent>)>::query().filter(changed_since_last_run::<
+                Parent,
+            >(
+                &parent_versions
+            )),
+        )
+        // all entities with `Parent` components
+        // A negated changed filter cannot be used here, since it also excludes every entity with `Parent` component
+        .with_query(<Read<Parent>>::query())
+        // entities whose `SiblingIndex` components are added or modified
+        .with_query(
+            <Read<Parent>>::query().filter(changed_since_last_run::<SiblingIndex>(
+                &sibling_index_versions,
+            )),
+        )
+        .read_component::<Parent>()
+        .read_component::<OrphanPolicy>()
+        .read_component::<SiblingIndex>()
         .write_component::<Children>()
-        .build(|cmd, world, _, queries| {
-            let (parent_removed_query, parent_changed_query, check_parent_alive_query) = queries;
+        .build(move |cmd, world, (policy, errors, events), queries| {
+            let (
+                parent_removed_query,
+                parent_changed_query,
+                check_parent_alive_query,
+                sibling_index_changed_query,
+            ) = queries;
 
             // Entities with `Parent` components removed
             // This is for entities that got out of the hierarchy, not those whose parents are deleted
@@ -59,38 +118,95 @@ pub fn build_hierarchy_sync_system(_: &mut World) -> Box<dyn Schedulable> {
 
                     // update the `PreviousParent` component
                     cmd.add_component(entity, PreviousParent::new());
+                    events.single_write(HierarchyEvent::ChildRemoved {
+                        parent: previous_parent,
+                        child: entity,
+                    });
                 }
             }
 
-            // Any entities to remove, including their children
-            let mut entities_to_remove = HashSet::<Entity>::new();
+            // Entities whose parents are deleted
+            let mut orphans = HashSet::<Entity>::new();
+
+            // Orphans that are attached to deleted parents in this run, for which no events are written yet
+            let mut unannounced_orphans = HashSet::<Entity>::new();
 
             // Entities that need `Children` component to be newly inserted
             let mut children_to_insert = HashMap::<Entity, Children>::new();
 
+            // Parents of all entities with `Parent` component in this run
+            let mut parents = HashMap::<Entity, Entity>::new();
+
+            // Entities whose new parents are rejected, mapped to the parents they are left with
+            let mut rejected = HashMap::<Entity, Option<Entity>>::new();
+
+            // Entities whose ancestors are known to end at a root, so that long chains are followed only once
+            let mut rooted = HashSet::<Entity>::new();
+
             // Entities that have their `Parent` components added or modified
             for (entity, (parent, previous_parent)) in parent_changed_query.iter_entities(world) {
                 let parent = parent.entity();
+                let previous_parent = previous_parent.and_then(|p| p.inner());
+                parents.insert(entity, parent);
 
-                if let Some(previous_parent) = previous_parent {
-                    // This entity has `PreviousParent` component
-                    if let Some(previous_parent) = previous_parent.inner() {
-                        // This entity was previously attached to other entity
-                        if parent == previous_parent {
-                            // The component storage was marked changed but the value did not actually change, so ignore
-                            // ...unless the parent entity is deleted
-                            if !world.is_alive(parent) {
-                                entities_to_remove.insert(entity);
+                if previous_parent == Some(parent) {
+                    // The component storage was marked changed but the value did not actually change, so ignore
+                    // ...unless the parent entity is deleted
+                    if !world.is_alive(parent) {
+                        orphans.insert(entity);
+                    }
+                    continue;
+                }
+
+                if creates_cycle(world, &rejected, &mut rooted, entity, parent) {
+                    log::error!(
+                        "Setting the parent of {} to {} creates a cycle; the change is rejected",
+                        entity,
+                        parent
+                    );
+                    errors.single_write(HierarchyError::Cycle { entity, parent });
+
+                    // Restore the previous link, which is still in the `Children` component of the previous parent,
+                    // unless that creates a cycle as well
+                    let restored = previous_parent.filter(|previous_parent| {
+                        world.is_alive(*previous_parent)
+                            && !creates_cycle(
+                                world,
+                                &rejected,
+                                &mut rooted,
+                                entity,
+                                *previous_parent,
+                            )
+                    });
+                    if let Some(restored) = restored {
+                        cmd.add_component(entity, Parent::new(restored));
+                        parents.insert(entity, restored);
+                    } else {
+                        if let Some(previous_parent) = previous_parent {
+                            if let Some(mut children) =
+                                world.get_component_mut::<Children>(previous_parent)
+                            {
+                                children.retain(|e| *e != entity);
                             }
-                            continue;
+                            events.single_write(HierarchyEvent::ChildRemoved {
+                                parent: previous_parent,
+                                child: entity,
+                            });
                         }
+                        cmd.remove_component::<Parent>(entity);
+                        cmd.add_component(entity, PreviousParent::new());
+                        parents.remove(&entity);
+                    }
+                    rejected.insert(entity, restored);
+                    continue;
+                }
 
-                        if let Some(mut children) =
-                            world.get_component_mut::<Children>(previous_parent)
-                        {
-                            // Update the `Children` component of the previous parent since it exists
-                            children.retain(|e| *e != entity);
-                        }
+                if let Some(previous_parent) = previous_parent {
+                    // This entity was previously attached to other entity
+                    if let Some(mut children) = world.get_component_mut::<Children>(previous_parent)
+                    {
+                        // Update the `Children` component of the previous parent since it exists
+                        children.retain(|e| *e != entity);
                     }
                 }
 
@@ -99,21 +215,28 @@ pub fn build_hierarchy_sync_system(_: &mut World) -> Box<dyn Schedulable> {
 
                 if world.is_alive(parent) {
                     // The new parent entity is alive
-                    if let Some(children) = world
-                        .get_component_mut::<Children>(parent) // get a real attached component
-                        .as_deref_mut()
-                        .or_else(|| children_to_insert.get_mut(&parent))
-                    // get a new component to be added later
-                    {
-                        // There is already a `Children` component to modify
-                        children.push(entity);
-                    } else {
-                        // Create a new `Children` component and save it for later
-                        children_to_insert.insert(parent, Children::from_single(entity));
-                    }
+                    add_child(world, &mut children_to_insert, parent, entity);
+                    events.single_write(match previous_parent {
+                        Some(previous_parent) => HierarchyEvent::Reparented {
+                            previous_parent,
+                            parent,
+                            child: entity,
+                        },
+                        None => HierarchyEvent::ChildAdded {
+                            parent,
+                            child: entity,
+                        },
+                    });
                 } else {
-                    // The new parent is deleted, so save this entity to delete later
-                    entities_to_remove.insert(entity);
+                    // The new parent is deleted, so save this entity to handle later
+                    if let Some(previous_parent) = previous_parent {
+                        events.single_write(HierarchyEvent::ChildRemoved {
+                            parent: previous_parent,
+                            child: entity,
+                        });
+                    }
+                    orphans.insert(entity);
+                    unannounced_orphans.insert(entity);
                 }
             }
 
@@ -121,79 +244,306 @@ pub fn build_hierarchy_sync_system(_: &mut World) -> Box<dyn Schedulable> {
             // It iterates through all entities that have `Parent` components and check if each parent entity is alive.
             // This needs to be changed when deleted component detection becomes available in legion.
             for (entity, parent) in check_parent_alive_query.iter_entities(world) {
+                if rejected.contains_key(&entity) {
+                    // The `Parent` component is replaced or removed by the command buffer
+                    continue;
+                }
+                parents.insert(entity, parent.entity());
                 if !world.is_alive(parent.entity()) {
-                    // The parent is delete, so save this entity to delete later
-                    entities_to_remove.insert(entity);
+                    // The parent is delete, so save this entity to handle later
+                    orphans.insert(entity);
+                }
+            }
+
+            // Remove deleted entities from the `Children` components of their parents
+            for (entity, parent) in last_parents.iter() {
+                if !world.is_alive(*entity) {
+                    if let Some(mut children) = world.get_component_mut::<Children>(*parent) {
+                        children.retain(|e| e != entity);
+                    }
+                    if world.is_alive(*parent) {
+                        events.single_write(HierarchyEvent::ChildRemoved {
+                            parent: *parent,
+                            child: *entity,
+                        });
+                    }
                 }
             }
 
-            // This is not needed if deleting the same entity twice is okay
-            let mut entities_removed = HashSet::<Entity>::with_capacity(entities_to_remove.len());
-            // Delete the saved entities and their children
-            delete_entities_and_children_recursive(
+            // Re-sort the children of the parents whose children have their `SiblingIndex` components modified
+            let parents_to_sort: HashSet<Entity> = sibling_index_changed_query
+                .iter(world)
+                .map(|parent| parent.entity())
+                .collect();
+            for parent in parents_to_sort {
+                sort_children(world, parent);
+            }
+
+            // Handle the saved entities, and the children of those deleted among them
+            let mut orphan_handler = OrphanHandler {
                 cmd,
-                world,
-                &children_to_insert,
-                &mut entities_removed,
-                entities_to_remove,
-            );
+                events: &mut *events,
+                default_policy: **policy,
+                last_parents: &last_parents,
+                children_to_insert: &mut children_to_insert,
+                unannounced: &unannounced_orphans,
+                removed: HashSet::new(),
+            };
+            orphan_handler.handle(world, orphans);
 
             // Add the new `Children` components
             for (entity, children) in children_to_insert.into_iter() {
                 cmd.add_component(entity, children);
             }
+
+            last_parents = parents;
+            parent_versions.advance();
+            sibling_index_versions.advance();
         })
 }
 
-fn delete_entities_and_children_recursive<I>(
-    cmd: &mut CommandBuffer,
+/// Check if setting the parent of the entity creates a cycle, following the `Parent` components except for the rejected
+/// ones
+///
+/// The entities that are found to end at a root are added to `rooted`, and the walk stops at them later. A rejection
+/// does not invalidate them, because the ancestors of a rooted entity never include an entity that is in a cycle.
+fn creates_cycle(
+    world: &SubWorld,
+    rejected: &HashMap<Entity, Option<Entity>>,
+    rooted: &mut HashSet<Entity>,
+    entity: Entity,
+    parent: Entity,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut current = Some(parent);
+    while let Some(ancestor) = current {
+        if ancestor == entity {
+            return true;
+        }
+        if rooted.contains(&ancestor) {
+            break;
+        }
+        if !visited.insert(ancestor) {
+            // There is a cycle above, but it does not include this entity
+            return false;
+        }
+        current = match rejected.get(&ancestor) {
+            Some(parent) => *parent,
+            None => world.get_component::<Parent>(ancestor).map(|p| p.entity()),
+        };
+    }
+    rooted.extend(visited);
+    false
+}
+
+/// Add the child to the `Children` component of the parent, or to the one that will be inserted
+///
+/// The child is placed after the siblings whose `SiblingIndex` components are less than or equal to its own.
+fn add_child(
     world: &mut SubWorld,
-    children_to_insert: &HashMap<Entity, Children>,
-    removed: &mut HashSet<Entity>,
-    entities_to_remove: I,
-) where
-    I: IntoIterator<Item = Entity>,
-{
-    for entity in entities_to_remove {
-        if removed.contains(&entity) {
-            continue;
+    children_to_insert: &mut HashMap<Entity, Children>,
+    parent: Entity,
+    child: Entity,
+) {
+    let key = sibling_key(world, child);
+    let siblings = world
+        .get_component_mut::<Children>(parent) // get a real attached component
+        .map(|children| children.to_vec())
+        .or_else(|| {
+            children_to_insert
+                .get(&parent)
+                .map(|children| children.to_vec())
+        }); // get a new component to be added later
+    if let Some(siblings) = siblings {
+        // There is already a `Children` component to modify
+        let position = siblings
+            .iter()
+            .position(|sibling| sibling_key(world, *sibling) > key)
+            .unwrap_or(siblings.len());
+        if let Some(children) = world
+            .get_component_mut::<Children>(parent)
+            .as_deref_mut()
+            .or_else(|| children_to_insert.get_mut(&parent))
+        {
+            children.insert(position, child);
         }
+    } else {
+        // Create a new `Children` component and save it for later
+        children_to_insert.insert(parent, Children::from_single(child));
+    }
+}
 
-        cmd.delete(entity);
-        removed.insert(entity);
+/// Stable-sort the `Children` component of the parent by the `SiblingIndex` components of the children
+fn sort_children(world: &mut SubWorld, parent: Entity) {
+    let mut children = match world.get_component_mut::<Children>(parent) {
+        Some(children) => children.to_vec(),
+        None => return,
+    };
+    children.sort_by_key(|child| sibling_key(world, *child));
+    if let Some(mut component) = world.get_component_mut::<Children>(parent) {
+        *component = Children::from(&children[..]);
+    }
+}
 
-        let mut entities_to_remove = None;
+/// Key for sorting siblings, which places the entities without `SiblingIndex` component after the others
+fn sibling_key(world: &SubWorld, entity: Entity) -> (bool, usize) {
+    match world.get_component::<SiblingIndex>(entity) {
+        Some(index) => (false, index.index()),
+        None => (true, 0),
+    }
+}
 
-        if let Some(children) = world
+/// Applies [`OrphanPolicy`] to entities whose parents are deleted
+///
+/// [`OrphanPolicy`]: ../components/enum.OrphanPolicy.html
+struct OrphanHandler<'a> {
+    cmd: &'a mut CommandBuffer,
+    events: &'a mut EventChannel<HierarchyEvent>,
+    default_policy: OrphanPolicy,
+    last_parents: &'a HashMap<Entity, Entity>,
+    children_to_insert: &'a mut HashMap<Entity, Children>,
+    /// Orphans that have never been announced as children of their deleted parents
+    unannounced: &'a HashSet<Entity>,
+    /// Entities that are deleted in this run; this is not needed if deleting the same entity twice is okay
+    removed: HashSet<Entity>,
+}
+
+impl OrphanHandler<'_> {
+    fn handle<I>(&mut self, world: &mut SubWorld, orphans: I)
+    where
+        I: IntoIterator<Item = Entity>,
+    {
+        for entity in orphans {
+            if self.removed.contains(&entity) {
+                continue;
+            }
+
+            let policy = world
+                .get_component::<OrphanPolicy>(entity)
+                .map_or(self.default_policy, |policy| *policy);
+            let parent = world
+                .get_component::<Parent>(entity)
+                .map(|p| p.entity())
+                .or_else(|| self.last_parents.get(&entity).copied());
+            // The deleted parent to write the events against, unless the entity was never announced as its child
+            let announced_parent = parent.filter(|_| !self.unannounced.contains(&entity));
+            match policy {
+                OrphanPolicy::DeleteRecursive => {
+                    if let Some(parent) = parent {
+                        self.events.single_write(HierarchyEvent::DeletedWithParent {
+                            parent,
+                            child: entity,
+                        });
+                    }
+                    self.delete(world, entity);
+                }
+                OrphanPolicy::Detach => self.detach(entity, announced_parent),
+                OrphanPolicy::DetachKeepWorld => {
+                    hierarchy::detach_keep_world(self.cmd, entity);
+                    self.cmd.add_component(entity, PreviousParent::new());
+                    self.write_removed(entity, announced_parent);
+                }
+                OrphanPolicy::AttachToGrandparent => {
+                    if let Some(ancestor) = self.closest_alive_ancestor(world, entity) {
+                        self.cmd.add_component(entity, Parent::new(ancestor));
+                        self.cmd
+                            .add_component(entity, PreviousParent::from_entity(ancestor));
+                        add_child(world, self.children_to_insert, ancestor, entity);
+                        self.events.single_write(match announced_parent {
+                            Some(previous_parent) => HierarchyEvent::Reparented {
+                                previous_parent,
+                                parent: ancestor,
+                                child: entity,
+                            },
+                            None => HierarchyEvent::ChildAdded {
+                                parent: ancestor,
+                                child: entity,
+                            },
+                        });
+                    } else {
+                        self.detach(entity, announced_parent);
+                    }
+                }
+            }
+        }
+    }
+
+    fn delete(&mut self, world: &mut SubWorld, entity: Entity) {
+        self.cmd.delete(entity);
+        self.removed.insert(entity);
+
+        let children = world
             .get_component_mut::<Children>(entity)
             .as_deref()
-            .or_else(|| children_to_insert.get(&entity))
-        {
-            entities_to_remove.replace(children.iter().copied().collect::<Vec<_>>());
+            .or_else(|| self.children_to_insert.get(&entity))
+            .map(|children| children.to_vec());
+        if let Some(children) = children {
+            // The children are orphans now
+            self.handle(world, children);
         }
+    }
 
-        if let Some(entities_to_remove) = entities_to_remove {
-            delete_entities_and_children_recursive(
-                cmd,
-                world,
-                children_to_insert,
-                removed,
-                entities_to_remove,
-            );
+    fn detach(&mut self, entity: Entity, parent: Option<Entity>) {
+        self.cmd.remove_component::<Parent>(entity);
+        self.cmd.add_component(entity, PreviousParent::new());
+        self.write_removed(entity, parent);
+    }
+
+    fn write_removed(&mut self, entity: Entity, parent: Option<Entity>) {
+        if let Some(parent) = parent {
+            self.events.single_write(HierarchyEvent::ChildRemoved {
+                parent,
+                child: entity,
+            });
         }
     }
+
+    /// Find the closest ancestor of the given entity that is alive and not deleted in this run
+    fn closest_alive_ancestor(&self, world: &SubWorld, entity: Entity) -> Option<Entity> {
+        let mut visited = HashSet::new();
+        let mut current = entity;
+        while visited.insert(current) {
+            let parent = if world.is_alive(current) {
+                world.get_component::<Parent>(current).map(|p| p.entity())
+            } else {
+                self.last_parents.get(&current).copied()
+            }?;
+            if world.is_alive(parent) && !self.removed.contains(&parent) {
+                return Some(parent);
+            }
+            current = parent;
+        }
+        // The ancestors form a cycle
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::build_hierarchy_sync_system;
-    use crate::components::{Children, Parent, PreviousParent};
+    use crate::components::{Children, OrphanPolicy, Parent, PreviousParent};
     use crate::ecs::prelude::*;
 
     #[test]
     fn hierarchy_sync_system() {
+        use crate::core::event_channel::EventChannel;
+        use crate::hierarchy::HierarchyEvent::{self, *};
+        use std::collections::HashSet;
+
         let mut world = World::new();
         let system = build_hierarchy_sync_system(&mut world);
+        let mut reader = world
+            .resources
+            .get_mut::<EventChannel<HierarchyEvent>>()
+            .unwrap()
+            .register_reader();
+        let mut read_events = |world: &World| {
+            let events = world
+                .resources
+                .get::<EventChannel<HierarchyEvent>>()
+                .unwrap();
+            events.read(&mut reader).copied().collect::<HashSet<_>>()
+        };
 
         // initial hierarchy
         // <-- parent                 child -->
@@ -224,6 +574,17 @@ mod tests {
         assert!(g2
             .iter()
             .all(|e| world.get_component::<Children>(*e).is_none()));
+        let added = |parent, child| ChildAdded { parent, child };
+        let expected = vec![
+            added(r[0], g1[0]),
+            added(r[0], g1[1]),
+            added(r[1], g1[2]),
+            added(r[1], g1[3]),
+            added(g1[0], g2[0]),
+            added(g1[2], g2[1]),
+            added(g1[2], g2[2]),
+        ];
+        assert_eq!(read_events(&world), expected.into_iter().collect());
 
         // changed hierarchy
         // <-- parent                  child -->
@@ -256,5 +617,247 @@ mod tests {
             [g2[1], r[0]]
         );
         assert_eq!(***world.get_component::<Children>(r[1]).unwrap(), [g1[3]]);
+        let expected = vec![
+            ChildRemoved {
+                parent: r[0],
+                child: g1[0],
+            },
+            DeletedWithParent {
+                parent: g1[0],
+                child: g2[0],
+            },
+            Reparented {
+                previous_parent: g1[2],
+                parent: g1[3],
+                child: g2[2],
+            },
+            ChildRemoved {
+                parent: r[1],
+                child: g1[2],
+            },
+            ChildAdded {
+                parent: g1[2],
+                child: r[0],
+            },
+        ];
+        assert_eq!(read_events(&world), expected.into_iter().collect());
+
+        system.run(&world);
+        system.command_buffer_mut().write(&mut world);
+        assert!(read_events(&world).is_empty());
+    }
+
+    #[test]
+    fn orphan_policies() {
+        use crate::components::{Position, WorldTransform};
+        use crate::core::event_channel::EventChannel;
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::hierarchy::HierarchyEvent::{self, *};
+        use crate::systems::TransformBundle;
+        use std::collections::HashSet;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(
+                TransformBundle::new_flush(SystemOrder::first())
+                    .with_orphan_policy(OrphanPolicy::Detach),
+            )
+            .build(&mut world);
+
+        // ┌─ gp ─── p ─┬─ a
+        // │            ├─ b (DeleteRecursive) ─── b1
+        // │            ├─ c (DetachKeepWorld)
+        // │            └─ d (AttachToGrandparent)
+        let gp = world.insert((), Some((Position::from_x(1.0),)))[0];
+        let p = world.insert((), Some((Position::from_x(2.0), Parent::new(gp))))[0];
+        let a = world.insert((), Some((Position::from_x(3.0), Parent::new(p))))[0];
+        let b = world.insert(
+            (),
+            Some((
+                Position::from_x(3.0),
+                Parent::new(p),
+                OrphanPolicy::DeleteRecursive,
+            )),
+        )[0];
+        let b1 = world.insert((), Some((Position::from_x(3.0), Parent::new(b))))[0];
+        let c = world.insert(
+            (),
+            Some((
+                Position::from_x(3.0),
+                Parent::new(p),
+                OrphanPolicy::DetachKeepWorld,
+            )),
+        )[0];
+        let d = world.insert(
+            (),
+            Some((
+                Position::from_x(3.0),
+                Parent::new(p),
+                OrphanPolicy::AttachToGrandparent,
+            )),
+        )[0];
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        let mut reader = world
+            .resources
+            .get_mut::<EventChannel<HierarchyEvent>>()
+            .unwrap()
+            .register_reader();
+
+        world.delete(p);
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+
+        let events = world
+            .resources
+            .get::<EventChannel<HierarchyEvent>>()
+            .unwrap();
+        let expected = vec![
+            ChildRemoved {
+                parent: gp,
+                child: p,
+            },
+            ChildRemoved {
+                parent: p,
+                child: a,
+            },
+            DeletedWithParent {
+                parent: p,
+                child: b,
+            },
+            ChildRemoved {
+                parent: b,
+                child: b1,
+            },
+            ChildRemoved {
+                parent: p,
+                child: c,
+            },
+            Reparented {
+                previous_parent: p,
+                parent: gp,
+                child: d,
+            },
+        ];
+        assert_eq!(
+            events.read(&mut reader).copied().collect::<HashSet<_>>(),
+            expected.into_iter().collect()
+        );
+        drop(events);
+
+        let global_x = |world: &World, entity| {
+            world
+                .get_component::<WorldTransform>(entity)
+                .unwrap()
+                .global_position()
+                .x
+        };
+
+        // Detached with its local transform
+        for entity in [a, b1].iter().copied() {
+            assert!(world.get_component::<Parent>(entity).is_none());
+            assert!(world
+                .get_component::<PreviousParent>(entity)
+                .unwrap()
+                .is_none());
+            assert_eq!(global_x(&world, entity), 3.0);
+        }
+
+        // Deleted, while its child has its own policy
+        assert!(!world.is_alive(b));
+        assert!(world.is_alive(b1));
+
+        // Detached in place
+        assert!(world.get_component::<Parent>(c).is_none());
+        assert_eq!(
+            *world.get_component::<Position>(c).unwrap(),
+            Position::from_x(6.0)
+        );
+        assert_eq!(global_x(&world, c), 6.0);
+
+        // Attached to the grandparent with its local transform
+        assert_eq!(*world.get_component::<Parent>(d).unwrap(), Parent::new(gp));
+        assert_eq!(***world.get_component::<Children>(gp).unwrap(), [d]);
+        assert_eq!(global_x(&world, d), 4.0);
+    }
+
+    #[test]
+    fn reject_cycles() {
+        use crate::components::Position;
+        use crate::core::events::{EventReader, Events};
+        use crate::core::systems::{ScheduleBuilder, SystemOrder};
+        use crate::hierarchy::{self, HierarchyError};
+        use crate::systems::TransformBundle;
+
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+        let mut reader = EventReader::<HierarchyError>::register(&mut world);
+        let mut read_errors = |world: &World| {
+            let events = world.resources.get::<Events<HierarchyError>>().unwrap();
+            events.read(&mut reader).copied().collect::<Vec<_>>()
+        };
+
+        // ┌─ a ─── b ─── c ─── d
+        // └─ x
+        let a = world.insert((), Some((Position::zero(),)))[0];
+        let x = world.insert((), Some((Position::zero(),)))[0];
+        let b = world.insert((), Some((Position::zero(), Parent::new(a))))[0];
+        let c = world.insert((), Some((Position::zero(), Parent::new(b))))[0];
+        let d = world.insert((), Some((Position::zero(), Parent::new(c))))[0];
+        schedule.execute(&mut world);
+        assert!(read_errors(&world).is_empty());
+
+        // Self-parenting of a root is rejected, and the `Parent` component is removed
+        world.add_component(x, Parent::new(x));
+        schedule.execute(&mut world);
+        assert_eq!(
+            read_errors(&world),
+            vec![HierarchyError::Cycle {
+                entity: x,
+                parent: x
+            }]
+        );
+        assert!(world.get_component::<Parent>(x).is_none());
+        assert!(world.get_component::<Children>(x).is_none());
+
+        // A longer loop is rejected, and the previous parent is restored
+        world.add_component(b, Parent::new(d));
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert_eq!(
+            read_errors(&world),
+            vec![HierarchyError::Cycle {
+                entity: b,
+                parent: d
+            }]
+        );
+        assert_eq!(*world.get_component::<Parent>(b).unwrap(), Parent::new(a));
+        assert_eq!(
+            hierarchy::descendants(&world, a).collect::<Vec<_>>(),
+            vec![b, c, d]
+        );
+        assert!(world.get_component::<Children>(d).is_none());
+
+        // Only one of two links that form a loop together is rejected
+        world.add_component(a, Parent::new(x));
+        world.add_component(x, Parent::new(a));
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        let errors = read_errors(&world);
+        assert_eq!(errors.len(), 1);
+        // The entity whose link is rejected stays a root, and the other one is attached to it
+        let (root, attached) = match errors[0] {
+            HierarchyError::Cycle { entity, parent } => (entity, parent),
+        };
+        assert_eq!(
+            *world.get_component::<Parent>(attached).unwrap(),
+            Parent::new(root)
+        );
+        assert!(world.get_component::<Parent>(root).is_none());
+        assert_eq!(hierarchy::root_of(&world, d), root);
+        assert_eq!(hierarchy::descendants(&world, root).count(), 4);
+        assert!(read_errors(&world).is_empty());
     }
 }
@@ -0,0 +1,180 @@
+use crate::components::{Parent, ParentTransform2, Position2, Rotation2, Scale2, WorldTransform2};
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::filter::filter_fns::{changed, component};
+use crate::ecs::query::{IntoQuery, TryRead, Write};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::utils::local_transform2_matrix;
+
+macro_rules! transform2_update_system_fn {
+    (
+        $( #[$struct_attrs:meta] )*
+        pub struct $struct_name:ident;
+
+        $( #[$fn_attrs:meta] )*
+        pub fn $fn_name:ident<$comp:ident>($system_name:expr) -> Box<dyn Schedulable> {
+            filters: {
+                $( update: $update_filter:expr, )?
+                $( add: $add_filter:expr, )?
+            }
+        }
+    ) => {
+        $( #[$struct_attrs] )*
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+        #[system_desc(type(types::Parallel), fn($fn_name))]
+        pub struct $struct_name;
+
+        $( #[$fn_attrs] )*
+        pub fn $fn_name(_: &mut World) -> Box<dyn Schedulable> {
+            SystemBuilder::new($system_name)
+                // Transform should be updated
+                .with_query(
+                    <(Write<$comp>, TryRead<Position2>, TryRead<Rotation2>, TryRead<Scale2>)>::query()
+                        .filter((changed::<Position2>() | changed::<Rotation2>() | changed::<Scale2>())
+                            $( & $update_filter )?),
+                )
+                // Transform should be added
+                .with_query(
+                    <(TryRead<Position2>, TryRead<Rotation2>, TryRead<Scale2>)>::query().filter(
+                        !component::<$comp>()
+                            & (component::<Position2>() | component::<Rotation2>() | component::<Scale2>())
+                            $( & $add_filter )?
+                    ),
+                )
+                .build(|cmd, world, _resources, queries| {
+                    let (transforms_to_update, transforms_to_add) = queries;
+                    rayon::scope(|s| {
+                        // Transform should be updated
+                        s.spawn(|_| unsafe {
+                            transforms_to_update.for_each_unchecked(
+                                world,
+                                |(mut transform, position, rotation, scale)| {
+                                    *transform = local_transform2_matrix(
+                                        position.as_deref(),
+                                        rotation.as_deref(),
+                                        scale.as_deref(),
+                                    )
+                                    .into();
+                                },
+                            );
+                        });
+                        // Transform should be added
+                        s.spawn(|_| unsafe {
+                            transforms_to_add.for_each_entities_unchecked(
+                                world,
+                                |(entity, (position, rotation, scale))| {
+                                    let transform = $comp::from(local_transform2_matrix(
+                                        position.as_deref(),
+                                        rotation.as_deref(),
+                                        scale.as_deref(),
+                                    ));
+                                    cmd.add_component(entity, transform);
+                                },
+                            );
+                        });
+                    });
+                })
+
+        }
+    };
+}
+
+transform2_update_system_fn! {
+    /// System descriptor(builder) for updating [`ParentTransform2`] components for entities with
+    /// [`Parent`] component
+    ///
+    /// This is a wrapper of [`build_parent_transform2_update_system`].
+    ///
+    /// [`ParentTransform2`]: ../components/struct.ParentTransform2.html
+    /// [`Parent`]: ../components/struct.Parent.html
+    /// [`build_parent_transform2_update_system`]: ./fn.build_parent_transform2_update_system.html
+    pub struct ParentTransform2UpdateSystem;
+
+    /// Build a system that updates [`ParentTransform2`] component from [`Position2`], [`Rotation2`], and/or
+    /// [`Scale2`] components
+    ///
+    /// This is the 2D counterpart of [`build_parent_transform_update_system`].
+    ///
+    /// [`ParentTransform2`]: ../components/struct.ParentTransform2.html
+    /// [`Position2`]: ../components/struct.Position2.html
+    /// [`Rotation2`]: ../components/struct.Rotation2.html
+    /// [`Scale2`]: ../components/struct.Scale2.html
+    /// [`build_parent_transform_update_system`]: ./fn.build_parent_transform_update_system.html
+    pub fn build_parent_transform2_update_system<ParentTransform2>(
+        "ParentTransform2Update"
+    ) -> Box<dyn Schedulable> {
+        filters: {
+            add: component::<Parent>(),
+        }
+    }
+}
+
+transform2_update_system_fn! {
+    /// System descriptor(builder) for updating [`WorldTransform2`] components for entities at the
+    /// root of hierarchy
+    ///
+    /// This is a wrapper of [`build_world_transform2_update_system`].
+    ///
+    /// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+    /// [`build_world_transform2_update_system`]: ./fn.build_world_transform2_update_system.html
+    pub struct WorldTransform2UpdateSystem;
+
+    /// Build a system that updates [`WorldTransform2`] component from [`Position2`], [`Rotation2`], and/or
+    /// [`Scale2`] components
+    ///
+    /// This is the 2D counterpart of [`build_world_transform_update_system`].
+    ///
+    /// [`WorldTransform2`]: ../components/struct.WorldTransform2.html
+    /// [`Position2`]: ../components/struct.Position2.html
+    /// [`Rotation2`]: ../components/struct.Rotation2.html
+    /// [`Scale2`]: ../components/struct.Scale2.html
+    /// [`build_world_transform_update_system`]: ./fn.build_world_transform_update_system.html
+    pub fn build_world_transform2_update_system<WorldTransform2>(
+        "WorldTransform2Update"
+    ) -> Box<dyn Schedulable> {
+        filters: {
+            update: !component::<Parent>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_world_transform2_update_system as build_system;
+    use crate::components::*;
+    use crate::ecs::prelude::*;
+
+    #[test]
+    fn world_transform2_update_system() {
+        let mut world = World::new();
+        let system = build_system(&mut world);
+
+        let t = WorldTransform2::new();
+        let p = Position2::from_xy(1.0, 2.0);
+        let r = Rotation2::from_angle(0.5);
+        let s = Scale2::from_xy(2.0, 3.0);
+
+        let pos = world.insert((), Some((t, p)))[0];
+        let pos_w_parent = world.insert((), Some((t, p, Parent::new(pos))))[0];
+        let no_t = world.insert((), Some((p, r, s)))[0];
+
+        system.run(&world);
+        system.command_buffer_mut().write(&mut world);
+
+        assert_eq!(
+            **world.get_component::<WorldTransform2>(pos).unwrap(),
+            p.to_homogeneous()
+        );
+        assert_eq!(
+            *world
+                .get_component::<WorldTransform2>(pos_w_parent)
+                .unwrap(),
+            t // should not update the entities with `Parent` component
+        );
+        assert_eq!(
+            **world.get_component::<WorldTransform2>(no_t).unwrap(),
+            (r.to_homogeneous() * s.to_homogeneous()).append_translation(&p.vector)
+        );
+    }
+}
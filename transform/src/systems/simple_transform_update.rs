@@ -1,10 +1,11 @@
-use crate::components::{Parent, ParentTransform, Position, Rotation, WorldTransform};
+use crate::components::{Parent, ParentTransform, Position, Rotation, Scale, WorldTransform};
 use crate::core::systems::{types, SystemDesc, SystemType};
 use crate::ecs::filter::filter_fns::{changed, component};
 use crate::ecs::query::{IntoQuery, Read, TryRead, Write};
 use crate::ecs::schedule::Schedulable;
 use crate::ecs::system::SystemBuilder;
 use crate::ecs::world::World;
+use crate::utils::local_transform_matrix;
 
 macro_rules! transform_update_system_fn {
     (
@@ -20,7 +21,7 @@ macro_rules! transform_update_system_fn {
         }
     ) => {
         $( #[$struct_attrs] )*
-        #[derive(SystemDesc)]
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
         #[system_desc(type(types::Parallel), fn($fn_name))]
         pub struct $struct_name;
 
@@ -30,23 +31,32 @@ macro_rules! transform_update_system_fn {
                 // Position only
                 .with_query(
                     <(Write<$comp>, Read<Position>)>::query()
-                        .filter((!component::<Rotation>() & changed::<Position>()) $( & $update_filter )?),
+                        .filter((!component::<Rotation>() & !component::<Scale>() & changed::<Position>())
+                            $( & $update_filter )?),
                 )
                 // Rotation only
                 .with_query(
                     <(Write<$comp>, Read<Rotation>)>::query()
-                        .filter((!component::<Position>() & changed::<Rotation>()) $( & $update_filter )?),
+                        .filter((!component::<Position>() & !component::<Scale>() & changed::<Rotation>())
+                            $( & $update_filter )?),
                 )
                 // Position & Rotation
                 .with_query(
                     <(Write<$comp>, Read<Position>, Read<Rotation>)>::query()
-                        .filter((changed::<Position>() | changed::<Rotation>()) $( & $update_filter )?),
+                        .filter((!component::<Scale>() & (changed::<Position>() | changed::<Rotation>()))
+                            $( & $update_filter )?),
+                )
+                // Scale with or without Position and/or Rotation
+                .with_query(
+                    <(Write<$comp>, TryRead<Position>, TryRead<Rotation>, Read<Scale>)>::query()
+                        .filter((changed::<Position>() | changed::<Rotation>() | changed::<Scale>())
+                            $( & $update_filter )?),
                 )
                 // Transform should be added
                 .with_query(
-                    <(TryRead<Position>, TryRead<Rotation>)>::query().filter(
+                    <(TryRead<Position>, TryRead<Rotation>, TryRead<Scale>)>::query().filter(
                         !component::<$comp>()
-                            & (component::<Position>() | component::<Rotation>())
+                            & (component::<Position>() | component::<Rotation>() | component::<Scale>())
                             $( & $add_filter )?
                     ),
                 )
@@ -55,6 +65,7 @@ macro_rules! transform_update_system_fn {
                         position_only,
                         rotation_only,
                         position_and_rotation,
+                        scaled,
                         transforms_to_add,
                     ) = queries;
                     rayon::scope(|s| {
@@ -82,19 +93,30 @@ macro_rules! transform_update_system_fn {
                                 },
                             );
                         });
+                        // Scale with or without Position and/or Rotation
+                        s.spawn(|_| unsafe {
+                            scaled.for_each_unchecked(
+                                world,
+                                |(mut transform, position, rotation, scale)| {
+                                    *transform = local_transform_matrix(
+                                        position.as_deref(),
+                                        rotation.as_deref(),
+                                        Some(&scale),
+                                    )
+                                    .into();
+                                },
+                            );
+                        });
                         // Transform should be added
                         s.spawn(|_| unsafe {
                             transforms_to_add.for_each_entities_unchecked(
                                 world,
-                                |(entity, (position, rotation))| {
-                                    let mut transform = if let Some(rotation) = rotation {
-                                        $comp::from(rotation.to_homogeneous())
-                                    } else {
-                                        $comp::new()
-                                    };
-                                    if let Some(position) = position {
-                                        transform.append_translation_mut(&position.vector);
-                                    }
+                                |(entity, (position, rotation, scale))| {
+                                    let transform = $comp::from(local_transform_matrix(
+                                        position.as_deref(),
+                                        rotation.as_deref(),
+                                        scale.as_deref(),
+                                    ));
                                     cmd.add_component(entity, transform);
                                 },
                             );
@@ -117,15 +139,17 @@ transform_update_system_fn! {
     /// [`build_parent_transform_update_system`]: ./fn.build_parent_transform_update_system.html
     pub struct ParentTransformUpdateSystem;
 
-    /// Build a system that updates [`ParentTransform`] component from [`Position`] and/or [`Rotation`] components
+    /// Build a system that updates [`ParentTransform`] component from [`Position`], [`Rotation`], and/or [`Scale`]
+    /// components
     ///
     /// This system only updates entities with [`Parent`] component. It also adds any missing [`ParentTransform`]
-    /// components for entities that have [`Parent`] component and at least one of [`Position`] and [`Rotation`]
-    /// components.
+    /// components for entities that have [`Parent`] component and at least one of [`Position`], [`Rotation`], and
+    /// [`Scale`] components.
     ///
     /// [`ParentTransform`]: ../components/struct.ParentTransform.html
     /// [`Position`]: ../components/struct.Position.html
     /// [`Rotation`]: ../components/struct.Rotation.html
+    /// [`Scale`]: ../components/struct.Scale.html
     /// [`Parent`]: ../components/struct.Parent.html
     pub fn build_parent_transform_update_system<ParentTransform>(
         "ParentTransformUpdate"
@@ -146,15 +170,17 @@ transform_update_system_fn! {
     /// [`build_world_transform_update_system`]: ./fn.build_world_transform_update_system.html
     pub struct WorldTransformUpdateSystem;
 
-    /// Build a system that updates [`WorldTransform`] component from [`Position`] and/or [`Rotation`] components
+    /// Build a system that updates [`WorldTransform`] component from [`Position`], [`Rotation`], and/or [`Scale`]
+    /// components
     ///
     /// This system does **NOT** update the component for entities that have [`Parent`] component. This means that only
     /// entities at the root of the hierarchy tree are updated. It also adds any missing [`WorldTransform`] components
-    /// for entities that have at least one of [`Position`] and [`Rotation`] components.
+    /// for entities that have at least one of [`Position`], [`Rotation`], and [`Scale`] components.
     ///
     /// [`WorldTransform`]: ../components/struct.WorldTransform.html
     /// [`Position`]: ../components/struct.Position.html
     /// [`Rotation`]: ../components/struct.Rotation.html
+    /// [`Scale`]: ../components/struct.Scale.html
     /// [`Parent`]: ../components/struct.Parent.html
     pub fn build_world_transform_update_system<WorldTransform>(
         "WorldTransformUpdate"
@@ -187,6 +213,10 @@ mod tests {
         let pos_rot = world.insert((), Some((t, p, r)))[0];
         let pos_w_parent = world.insert((), Some((t, p, Parent::new(pos_rot))))[0];
         let no_t = world.insert((), Some((p, r)))[0];
+        let s = Scale::from_xyz(1.0, 2.0, 3.0);
+        let scale = world.insert((), Some((t, s)))[0];
+        let pos_rot_scale = world.insert((), Some((t, p, r, s)))[0];
+        let no_t_scale = world.insert((), Some((s,)))[0];
 
         system.run(&world);
         system.command_buffer_mut().write(&mut world);
@@ -211,5 +241,19 @@ mod tests {
             **world.get_component::<WorldTransform>(no_t).unwrap(),
             r.to_homogeneous().append_translation(&p.vector)
         );
+        assert_eq!(
+            **world.get_component::<WorldTransform>(scale).unwrap(),
+            s.to_homogeneous()
+        );
+        assert_eq!(
+            **world
+                .get_component::<WorldTransform>(pos_rot_scale)
+                .unwrap(),
+            (r.to_homogeneous() * s.to_homogeneous()).append_translation(&p.vector)
+        );
+        assert_eq!(
+            **world.get_component::<WorldTransform>(no_t_scale).unwrap(),
+            s.to_homogeneous()
+        );
     }
 }
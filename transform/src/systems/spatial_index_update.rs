@@ -0,0 +1,170 @@
+use super::change_tracking::{changed_since_last_run, ChunkVersions};
+use crate::components::{Aabb, WorldAabb, WorldTransform};
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::ecs::entity::Entity;
+use crate::ecs::query::{IntoQuery, Read, TryRead};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::spatial_index::SpatialIndex;
+use std::sync::Arc;
+
+/// System descriptor(builder) for keeping [`SpatialIndex`] resource in sync with the entities
+///
+/// This is a wrapper for [`build_spatial_index_update_system`].
+///
+/// [`SpatialIndex`]: ../spatial_index/struct.SpatialIndex.html
+/// [`build_spatial_index_update_system`]: ./fn.build_spatial_index_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_spatial_index_update_system))]
+pub struct SpatialIndexUpdateSystem;
+
+/// Build a system that updates [`SpatialIndex`] resource with the entities whose [`WorldTransform`] or [`WorldAabb`]
+/// components have changed since the last run
+///
+/// Each entity is indexed with its [`WorldAabb`], or with its global position if it has none. The entities that are
+/// deleted or no longer have [`WorldTransform`] component are removed from the index. They are found by going through
+/// the index only when it has more entities than the world has entities with [`WorldTransform`] component.
+///
+/// This system is not added by [`TransformBundle`]. Run it after the transform systems, and after `bounds_update` if
+/// bounds are used, with a flush in between. [`SpatialIndex`] resource is inserted if it does not exist.
+///
+/// [`SpatialIndex`]: ../spatial_index/struct.SpatialIndex.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`WorldAabb`]: ../components/struct.WorldAabb.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+pub fn build_spatial_index_update_system(world: &mut World) -> Box<dyn Schedulable> {
+    world.resources.get_or_insert_with(SpatialIndex::default);
+
+    let transform_versions = Arc::new(ChunkVersions::default());
+    let bounds_versions = Arc::new(ChunkVersions::default());
+
+    SystemBuilder::new("SpatialIndexUpdate")
+        .write_resource::<SpatialIndex>()
+        // entities whose `WorldTransform` components are added or modified
+        .with_query(
+            <(Read<WorldTransform>, TryRead<WorldAabb>)>::query().filter(changed_since_last_run::<
+                WorldTransform,
+            >(
+                &transform_versions
+            )),
+        )
+        // entities whose `WorldAabb` components are added or modified
+        .with_query(
+            <(Read<WorldTransform>, Read<WorldAabb>)>::query().filter(changed_since_last_run::<
+                WorldAabb,
+            >(
+                &bounds_versions
+            )),
+        )
+        // all entities with `WorldTransform` components
+        .with_query(<Read<WorldTransform>>::query())
+        .read_component::<WorldTransform>()
+        .read_component::<WorldAabb>()
+        .build(move |_, world, index, queries| {
+            let (transform_changed_query, bounds_changed_query, transform_query) = queries;
+
+            // An entity that loses its `WorldAabb` component moves to another chunk, so it is found here as well
+            for (entity, (transform, bounds)) in transform_changed_query.iter_entities(world) {
+                index.insert(entity, indexed_bounds(&transform, bounds.as_deref()));
+            }
+            for (entity, (transform, bounds)) in bounds_changed_query.iter_entities(world) {
+                index.insert(entity, indexed_bounds(&transform, Some(&*bounds)));
+            }
+
+            // Removal of components cannot be detected through the chunk versions, but every entity with
+            // `WorldTransform` component is in the index by now, so the index has more entities only if some of them
+            // are deleted or have lost their `WorldTransform` components
+            let count: usize = transform_query
+                .iter_chunks(world)
+                .map(|chunk| chunk.entities().len())
+                .sum();
+            if index.len() > count {
+                let removed: Vec<Entity> = index
+                    .iter()
+                    .map(|(entity, _)| entity)
+                    .filter(|entity| world.get_component::<WorldTransform>(*entity).is_none())
+                    .collect();
+                for entity in removed {
+                    index.remove(entity);
+                }
+            }
+
+            transform_versions.advance();
+            bounds_versions.advance();
+        })
+}
+
+fn indexed_bounds(transform: &WorldTransform, bounds: Option<&WorldAabb>) -> Aabb {
+    match bounds {
+        Some(bounds) => **bounds,
+        None => {
+            let position = transform.global_position().vector.into();
+            Aabb::new(position, position)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_spatial_index_update_system;
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::ecs::prelude::*;
+    use crate::math::{Point3, Vector3};
+    use crate::spatial_index::{SpatialIndex, SpatialIndexKind};
+    use crate::systems::{build_bounds_update_system, TransformBundle};
+    use std::collections::HashSet;
+
+    #[test]
+    fn sync_spatial_index() {
+        let mut world = World::new();
+        world
+            .resources
+            .insert(SpatialIndex::new(SpatialIndexKind::Grid { cell_size: 5.0 }));
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::numbered(0, 0)))
+            .with_system_create_fn(SystemOrder::numbered(1, 0), build_bounds_update_system)
+            .with_flush(SystemOrder::numbered(1, 0))
+            .with_system_create_fn(
+                SystemOrder::numbered(2, 0),
+                build_spatial_index_update_system,
+            )
+            .build(&mut world);
+
+        let near = world.insert((), Some((Position::from_x(3.0),)))[0];
+        let far = world.insert((), Some((Position::from_x(30.0),)))[0];
+        let boxed = world.insert(
+            (),
+            Some((
+                Position::from_x(-11.0),
+                Aabb::from_center_half_extents(Point3::origin(), Vector3::repeat(2.0)),
+            )),
+        )[0];
+        schedule.execute(&mut world);
+
+        let radius_query = |world: &World| {
+            let index = world.resources.get::<SpatialIndex>().unwrap();
+            index
+                .query_radius(&Point3::origin(), 10.0)
+                .into_iter()
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(world.resources.get::<SpatialIndex>().unwrap().len(), 3);
+        assert_eq!(
+            radius_query(&world),
+            [near, boxed].iter().copied().collect()
+        );
+
+        *world.get_component_mut::<Position>(far).unwrap() = Position::from_x(5.0);
+        *world.get_component_mut::<Position>(near).unwrap() = Position::from_x(15.0);
+        world.remove_component::<Aabb>(boxed);
+        schedule.execute(&mut world);
+        assert_eq!(radius_query(&world), Some(far).into_iter().collect());
+
+        world.delete(far);
+        schedule.execute(&mut world);
+        assert!(radius_query(&world).is_empty());
+        assert_eq!(world.resources.get::<SpatialIndex>().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::components::{Children, InterpolatedTransform, PreviousWorldTransform, WorldTransform};
+use crate::core::systems::{types, SystemDesc, SystemType};
+use crate::core::time::Time;
+use crate::ecs::entity::Entity;
+use crate::ecs::filter::filter_fns::component;
+use crate::ecs::query::{IntoQuery, Read, TryRead, Write};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::math::{Matrix4, Vector3};
+use crate::utils::local_transform_matrix;
+use std::collections::HashSet;
+
+/// System descriptor(builder) for taking snapshots of [`WorldTransform`] components before a simulation step
+///
+/// This is a wrapper of [`build_previous_world_transform_update_system`].
+///
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`build_previous_world_transform_update_system`]: ./fn.build_previous_world_transform_update_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(
+    type(types::Parallel),
+    fn(build_previous_world_transform_update_system)
+)]
+pub struct PreviousWorldTransformUpdateSystem;
+
+/// Build a system that copies [`WorldTransform`] into [`PreviousWorldTransform`] component
+///
+/// [`PreviousWorldTransform`] is added to the entities that do not have one yet. Run this system right before the
+/// systems that simulate a fixed step, so that the snapshot holds the state of the previous step.
+///
+/// This system is not added by [`TransformBundle`].
+///
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`PreviousWorldTransform`]: ../components/struct.PreviousWorldTransform.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+pub fn build_previous_world_transform_update_system(_: &mut World) -> Box<dyn Schedulable> {
+    SystemBuilder::new("PreviousWorldTransformUpdate")
+        .with_query(<(Read<WorldTransform>, Write<PreviousWorldTransform>)>::query())
+        .with_query(<Read<WorldTransform>>::query().filter(!component::<PreviousWorldTransform>()))
+        .build(|cmd, world, _resources, queries| {
+            let (snapshots_to_update, snapshots_to_add) = queries;
+            for (world_transform, mut previous) in snapshots_to_update.iter(world) {
+                *previous = PreviousWorldTransform::new(*world_transform);
+            }
+            for (entity, world_transform) in snapshots_to_add.iter_entities(world) {
+                cmd.add_component(entity, PreviousWorldTransform::new(*world_transform));
+            }
+        })
+}
+
+/// System descriptor(builder) for blending [`WorldTransform`] components between the last two simulation steps
+///
+/// This is a wrapper of [`build_transform_interpolation_system`].
+///
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`build_transform_interpolation_system`]: ./fn.build_transform_interpolation_system.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, SystemDesc)]
+#[system_desc(type(types::Parallel), fn(build_transform_interpolation_system))]
+pub struct TransformInterpolationSystem;
+
+/// Build a system that updates [`InterpolatedTransform`] component by blending [`PreviousWorldTransform`] and
+/// [`WorldTransform`] components
+///
+/// The blend factor is [`Time::fixed_step_fraction`]. Positions and scales are linearly interpolated, and rotations
+/// are spherically interpolated. [`InterpolatedTransform`] is added to the entities that do not have one yet.
+///
+/// The current [`WorldTransform`] is used as is when the entity has no valid snapshot, e.g. when it has just been
+/// spawned or it has been teleported, or when any of its ancestors has no valid snapshot. This way, such entities
+/// show up right where they are instead of sliding from somewhere else.
+///
+/// This system is not added by [`TransformBundle`]. Run it after the transform systems and before rendering.
+/// [`Time`] resource is inserted if it does not exist.
+///
+/// [`InterpolatedTransform`]: ../components/struct.InterpolatedTransform.html
+/// [`PreviousWorldTransform`]: ../components/struct.PreviousWorldTransform.html
+/// [`WorldTransform`]: ../components/struct.WorldTransform.html
+/// [`Time::fixed_step_fraction`]: ../../game_engine_core/time/struct.Time.html#method.fixed_step_fraction
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`Time`]: ../../game_engine_core/time/struct.Time.html
+pub fn build_transform_interpolation_system(world: &mut World) -> Box<dyn Schedulable> {
+    world.resources.get_or_insert_with(Time::default);
+
+    SystemBuilder::new("TransformInterpolation")
+        .read_resource::<Time>()
+        .read_component::<Children>()
+        .write_component::<InterpolatedTransform>()
+        .with_query(<(Read<WorldTransform>, TryRead<PreviousWorldTransform>)>::query())
+        .with_query(<Read<WorldTransform>>::query().filter(!component::<InterpolatedTransform>()))
+        .build(|cmd, world, time, queries| {
+            let (transforms, transforms_to_add) = queries;
+            let fraction = time.fixed_step_fraction();
+
+            let transforms: Vec<_> = transforms
+                .iter_entities(world)
+                .map(|(entity, (current, previous))| {
+                    (
+                        entity,
+                        *current,
+                        previous.and_then(|previous| previous.world_transform()),
+                    )
+                })
+                .collect();
+
+            // Propagate the invalid snapshots down to the descendants, visiting each of them once
+            let mut invalid = HashSet::<Entity>::new();
+            let mut stack: Vec<Entity> = transforms
+                .iter()
+                .filter(|(_, _, previous)| previous.is_none())
+                .map(|(entity, _, _)| *entity)
+                .collect();
+            while let Some(entity) = stack.pop() {
+                if !invalid.insert(entity) {
+                    continue;
+                }
+                if let Some(children) = world.get_component::<Children>(entity) {
+                    stack.extend(children.iter().copied());
+                }
+            }
+
+            for (entity, current, previous) in transforms {
+                let matrix = match previous {
+                    Some(previous) if !invalid.contains(&entity) => {
+                        interpolate(&previous, &current, fraction)
+                    }
+                    _ => current.into(),
+                };
+                if let Some(mut interpolated) =
+                    world.get_component_mut::<InterpolatedTransform>(entity)
+                {
+                    *interpolated = matrix.into();
+                }
+            }
+
+            for (entity, world_transform) in transforms_to_add.iter_entities(world) {
+                cmd.add_component(entity, InterpolatedTransform::from(*world_transform));
+            }
+        })
+}
+
+fn interpolate(previous: &WorldTransform, current: &WorldTransform, fraction: f32) -> Matrix4<f32> {
+    if previous == current || fraction >= 1.0 {
+        return *current.matrix();
+    }
+    let position = previous
+        .global_position()
+        .vector
+        .lerp(&current.global_position().vector, fraction);
+    let scale: Vector3<f32> = previous
+        .global_scale()
+        .lerp(&current.global_scale(), fraction);
+    let current_rotation = *current.global_rotation();
+    let rotation = previous
+        .global_rotation()
+        .try_slerp(&current_rotation, fraction, f32::EPSILON)
+        .unwrap_or(current_rotation);
+    local_transform_matrix(
+        Some(&position.into()),
+        Some(&rotation.into()),
+        Some(&scale.into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_previous_world_transform_update_system, build_transform_interpolation_system,
+    };
+    use crate::components::*;
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::core::time::Time;
+    use crate::ecs::prelude::*;
+    use crate::math::Vector3;
+    use crate::systems::TransformBundle;
+    use std::time::Duration;
+
+    fn interpolated_position(world: &World, entity: Entity) -> Vector3<f32> {
+        let transform = world
+            .get_component::<InterpolatedTransform>(entity)
+            .unwrap();
+        Vector3::new(transform.m14, transform.m24, transform.m34)
+    }
+
+    #[test]
+    fn interpolate_between_steps() {
+        let mut world = World::new();
+        let mut snapshot = ScheduleBuilder::new()
+            .with_system_create_fn(
+                SystemOrder::first(),
+                build_previous_world_transform_update_system,
+            )
+            .with_flush(SystemOrder::first())
+            .build(&mut world);
+        let mut simulation = ScheduleBuilder::new()
+            .with_system_bundle(TransformBundle::new_flush(SystemOrder::first()))
+            .build(&mut world);
+        let mut render = ScheduleBuilder::new()
+            .with_system_create_fn(SystemOrder::first(), build_transform_interpolation_system)
+            .with_flush(SystemOrder::first())
+            .build(&mut world);
+        {
+            // Half of the next fixed step has been accumulated
+            let mut time = world.resources.get_mut::<Time>().unwrap();
+            time.set_fixed_delta_duration(Duration::from_millis(20));
+            time.set_delta_duration(Duration::from_millis(10));
+        }
+
+        let mover = world.insert((), Some((Position::from_x(0.0),)))[0];
+        let child = world.insert((), Some((Parent::new(mover), Position::from_y(1.0))))[0];
+        for _ in 0..2 {
+            snapshot.execute(&mut world);
+            simulation.execute(&mut world);
+        }
+
+        // The mover moves by 10 units in the next step
+        snapshot.execute(&mut world);
+        *world.get_component_mut::<Position>(mover).unwrap() = Position::from_x(10.0);
+        let spawned = world.insert((), Some((Position::from_z(3.0),)))[0];
+        simulation.execute(&mut world);
+        render.execute(&mut world);
+        render.execute(&mut world);
+        assert_eq!(
+            interpolated_position(&world, mover),
+            Vector3::new(5.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            interpolated_position(&world, child),
+            Vector3::new(5.0, 1.0, 0.0)
+        );
+        // The spawned entity has no snapshot yet
+        assert_eq!(
+            interpolated_position(&world, spawned),
+            Vector3::new(0.0, 0.0, 3.0)
+        );
+
+        // The mover is teleported in the next step
+        snapshot.execute(&mut world);
+        *world.get_component_mut::<Position>(mover).unwrap() = Position::from_x(100.0);
+        world
+            .get_component_mut::<PreviousWorldTransform>(mover)
+            .unwrap()
+            .invalidate();
+        simulation.execute(&mut world);
+        render.execute(&mut world);
+        assert_eq!(
+            interpolated_position(&world, mover),
+            Vector3::new(100.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            interpolated_position(&world, child),
+            Vector3::new(100.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            interpolated_position(&world, spawned),
+            Vector3::new(0.0, 0.0, 3.0)
+        );
+    }
+}
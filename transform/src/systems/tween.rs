@@ -0,0 +1,209 @@
+use crate::core::events::Events;
+use crate::core::systems::{types, SystemDesc};
+use crate::core::time::Time;
+use crate::ecs::query::{IntoQuery, Read};
+use crate::ecs::schedule::Schedulable;
+use crate::ecs::system::SystemBuilder;
+use crate::ecs::world::World;
+use crate::tween::{Interpolate, Tween, TweenCompleted};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// System descriptor(builder) for advancing [`Tween<T>`] components
+///
+/// This is a wrapper for [`build_tween_system`].
+///
+/// [`Tween<T>`]: ../tween/struct.Tween.html
+/// [`build_tween_system`]: ./fn.build_tween_system.html
+pub struct TweenSystem<T>(PhantomData<fn() -> T>);
+
+impl<T> TweenSystem<T> {
+    /// Create a new instance
+    pub fn new() -> Self {
+        TweenSystem(PhantomData)
+    }
+}
+
+impl<T> Clone for TweenSystem<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TweenSystem<T> {}
+
+impl<T> Default for TweenSystem<T> {
+    fn default() -> Self {
+        TweenSystem::new()
+    }
+}
+
+impl<T> fmt::Debug for TweenSystem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TweenSystem").finish()
+    }
+}
+
+impl<T> SystemDesc for TweenSystem<T>
+where
+    T: Interpolate + PartialEq + Send + Sync + 'static,
+{
+    type SystemType = types::Parallel;
+
+    fn build(self, world: &mut World) -> Box<dyn Schedulable> {
+        build_tween_system::<T>(world)
+    }
+}
+
+/// Build a system that advances [`Tween<T>`] components by the delta time of [`Time`] resource
+///
+/// When the entity has a `T` component, the tween starts from its value and the tweened value is written back to it.
+/// A [`TweenCompleted`] event is written when a tween completes. Finished tweens are kept on the entities, so remove
+/// them when they are no longer needed.
+///
+/// Only the tweens that are neither paused nor finished are advanced, and the `T` component is only written when the
+/// tweened value differs from it, so that the other systems do not see unchanged entities as changed.
+///
+/// This system is not added by [`TransformBundle`]. Add one for each type to be tweened, and run the ones for the
+/// transform components before the transform systems. [`Time`] resource and [`Events`] resource for
+/// [`TweenCompleted`] are inserted if they do not exist.
+///
+/// [`Tween<T>`]: ../tween/struct.Tween.html
+/// [`Time`]: ../../game_engine_core/time/struct.Time.html
+/// [`TweenCompleted`]: ../tween/struct.TweenCompleted.html
+/// [`TransformBundle`]: ./struct.TransformBundle.html
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+pub fn build_tween_system<T>(world: &mut World) -> Box<dyn Schedulable>
+where
+    T: Interpolate + PartialEq + Send + Sync + 'static,
+{
+    world.resources.get_or_insert_with(Time::default);
+    Events::<TweenCompleted>::ensure(world);
+
+    // Entities whose tweens are running, reused between the runs
+    let mut running = Vec::new();
+
+    SystemBuilder::new("Tween")
+        .read_resource::<Time>()
+        .write_resource::<Events<TweenCompleted>>()
+        .with_query(<Read<Tween<T>>>::query())
+        .read_component::<T>()
+        .write_component::<Tween<T>>()
+        .write_component::<T>()
+        .build(move |_, world, (time, events), query| {
+            let delta = time.delta_time();
+            running.extend(
+                query
+                    .iter_entities(world)
+                    .filter(|(_, tween)| !tween.is_finished() && !tween.is_paused())
+                    .map(|(entity, _)| entity),
+            );
+
+            for entity in running.drain(..) {
+                let target = world.get_component::<T>(entity).map(|target| *target);
+                let value = {
+                    let mut tween = world.get_component_mut::<Tween<T>>(entity).unwrap();
+                    if let Some(target) = target {
+                        tween.set_value(target);
+                    }
+                    if tween.advance(delta) {
+                        events.single_write(TweenCompleted {
+                            entity,
+                            name: tween.name().to_owned(),
+                        });
+                    }
+                    tween.value()
+                };
+                match (target, value) {
+                    (Some(target), Some(value)) if target != value => {
+                        *world.get_component_mut::<T>(entity).unwrap() = value
+                    }
+                    _ => {}
+                }
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TweenSystem;
+    use crate::components::*;
+    use crate::core::events::{EventReader, Events};
+    use crate::core::systems::{ScheduleBuilder, SystemOrder};
+    use crate::core::time::Time;
+    use crate::ecs::prelude::*;
+    use crate::tween::{Easing, Tween, TweenCompleted};
+    use std::time::Duration;
+
+    #[test]
+    fn tween_values() {
+        let mut world = World::new();
+        let mut schedule = ScheduleBuilder::new()
+            .with_system_create_desc(SystemOrder::first(), TweenSystem::<Position>::new())
+            .with_system_create_desc(SystemOrder::first(), TweenSystem::<f32>::new())
+            .build(&mut world);
+        let mut reader = EventReader::<TweenCompleted>::register(&mut world);
+        world
+            .resources
+            .get_mut::<Time>()
+            .unwrap()
+            .set_delta_duration(Duration::from_millis(250));
+
+        let slide = world.insert(
+            (),
+            Some((
+                Position::from_x(2.0),
+                Tween::to(Position::from_x(4.0), 1.0, Easing::Linear).with_name("slide"),
+            )),
+        )[0];
+        let fade = world.insert(
+            (),
+            Some((Tween::<f32>::from_to(1.0, 0.0, 0.5, Easing::QuadIn),)),
+        )[0];
+
+        schedule.execute(&mut world);
+        schedule.execute(&mut world);
+        assert_eq!(
+            *world.get_component::<Position>(slide).unwrap(),
+            Position::from_x(3.0)
+        );
+        assert_eq!(
+            world.get_component::<Tween<f32>>(fade).unwrap().value(),
+            Some(0.0)
+        );
+
+        for _ in 0..3 {
+            schedule.execute(&mut world);
+        }
+        assert_eq!(
+            *world.get_component::<Position>(slide).unwrap(),
+            Position::from_x(4.0)
+        );
+        {
+            let events = world.resources.get::<Events<TweenCompleted>>().unwrap();
+            let mut completed = events.read(&mut reader).cloned().collect::<Vec<_>>();
+            completed.sort_by_key(|event| event.name.clone());
+            assert_eq!(
+                completed,
+                vec![
+                    TweenCompleted {
+                        entity: fade,
+                        name: String::new(),
+                    },
+                    TweenCompleted {
+                        entity: slide,
+                        name: "slide".to_owned(),
+                    },
+                ]
+            );
+        }
+
+        // The finished tween no longer overwrites the changes made by the game code
+        *world.get_component_mut::<Position>(slide).unwrap() = Position::from_x(-1.0);
+        schedule.execute(&mut world);
+        assert_eq!(
+            *world.get_component::<Position>(slide).unwrap(),
+            Position::from_x(-1.0)
+        );
+    }
+}
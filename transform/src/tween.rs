@@ -0,0 +1,715 @@
+//! Fire-and-forget tweens of [`Position`], [`Rotation`], and other [`Interpolate`] values
+//!
+//! A [`Tween`] component moves a value towards its targets over time, shaped by an [`Easing`] function. The steps of
+//! a tween are built with [`TweenStep`], which can be chained into sequences and run side by side in parallel groups.
+//! The whole tween can be repeated, optionally going back and forth.
+//!
+//! The system built by [`build_tween_system`] advances [`Tween<T>`] components by the delta time of [`Time`] resource,
+//! and writes the value into the `T` component of the entity if it has one. Otherwise, e.g. for `f32` tweens, the value
+//! can be read with [`Tween::value`]. When a tween completes, a [`TweenCompleted`] event is written to the [`Events`]
+//! resource.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use game_engine::transform::components::Position;
+//! use game_engine::transform::tween::{Easing, Repeat, Tween, TweenStep};
+//!
+//! // Move to x = 3 in 0.3 seconds, overshooting a bit at the end
+//! let mut tween = Tween::to(Position::from_x(3.0), 0.3, Easing::BackOut).with_value(Position::from_x(0.0));
+//! assert!(tween.advance(0.3));
+//! assert_eq!(tween.value(), Some(Position::from_x(3.0)));
+//!
+//! // Pulse between 1 and 1.5 forever
+//! let pulse = Tween::new(
+//!     TweenStep::from_to(1.0, 1.5, 0.2, Easing::SineInOut).then(TweenStep::delay(0.1)),
+//! )
+//! .with_repeat(Repeat::Forever)
+//! .with_yoyo(true);
+//! assert_eq!(pulse.step().duration(), 0.3);
+//! ```
+//!
+//! [`Position`]: ../components/struct.Position.html
+//! [`Rotation`]: ../components/struct.Rotation.html
+//! [`Interpolate`]: ./trait.Interpolate.html
+//! [`Tween`]: ./struct.Tween.html
+//! [`Easing`]: ./enum.Easing.html
+//! [`TweenStep`]: ./struct.TweenStep.html
+//! [`build_tween_system`]: ../systems/fn.build_tween_system.html
+//! [`Tween<T>`]: ./struct.Tween.html
+//! [`Time`]: ../../game_engine_core/time/struct.Time.html
+//! [`Tween::value`]: ./struct.Tween.html#method.value
+//! [`TweenCompleted`]: ./struct.TweenCompleted.html
+//! [`Events`]: ../../game_engine_core/events/struct.Events.html
+
+use crate::animation::Animatable;
+use crate::ecs::entity::Entity;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Value that can be interpolated between two instances
+///
+/// This is implemented for every [`Animatable`] value, so the values that can be animated by keyframes can be tweened
+/// as well.
+///
+/// [`Animatable`]: ../animation/trait.Animatable.html
+pub trait Interpolate: Copy {
+    /// Interpolate from this value to the other one
+    ///
+    /// `t` is usually in the range of `[0, 1]`, but it can be slightly out of the range for overshooting [`Easing`]
+    /// functions.
+    ///
+    /// [`Easing`]: ./enum.Easing.html
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl<T: Animatable> Interpolate for T {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Animatable::interpolate(self, other, t)
+    }
+}
+
+impl Animatable for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+/// Function that maps the linear progress of a [`TweenStep`] to the progress of its value
+///
+/// The functions follow the ones listed in [easings.net](https://easings.net). `In` functions start slowly, `Out`
+/// functions end slowly, and `InOut` functions do both.
+///
+/// [`TweenStep`]: ./struct.TweenStep.html
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Apply this function to the progress `t`
+    ///
+    /// `t` is clamped to `[0, 1]`, and the result is exactly 0 and 1 at both ends. `Back` and `Elastic` functions go
+    /// out of the range in between.
+    pub fn apply(self, t: f32) -> f32 {
+        const BACK: f32 = 1.701_58;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+        const ELASTIC: f32 = 2.0 * PI / 3.0;
+        const ELASTIC_IN_OUT: f32 = 2.0 * PI / 4.5;
+
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= 1.0 {
+            return 1.0;
+        }
+        match self {
+            Easing::Linear => t,
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => (1.0 - (t * PI).cos()) / 2.0,
+            Easing::QuadIn => t.powi(2),
+            Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Easing::QuadInOut => in_out(t, |t| t.powi(2)),
+            Easing::CubicIn => t.powi(3),
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => in_out(t, |t| t.powi(3)),
+            Easing::QuartIn => t.powi(4),
+            Easing::QuartOut => 1.0 - (1.0 - t).powi(4),
+            Easing::QuartInOut => in_out(t, |t| t.powi(4)),
+            Easing::QuintIn => t.powi(5),
+            Easing::QuintOut => 1.0 - (1.0 - t).powi(5),
+            Easing::QuintInOut => in_out(t, |t| t.powi(5)),
+            Easing::ExpoIn => 2f32.powf(10.0 * t - 10.0),
+            Easing::ExpoOut => 1.0 - 2f32.powf(-10.0 * t),
+            Easing::ExpoInOut => in_out(t, |t| 2f32.powf(10.0 * t - 10.0)),
+            Easing::CircIn => 1.0 - (1.0 - t.powi(2)).sqrt(),
+            Easing::CircOut => (1.0 - (t - 1.0).powi(2)).sqrt(),
+            Easing::CircInOut => in_out(t, |t| 1.0 - (1.0 - t.powi(2)).sqrt()),
+            Easing::BackIn => (BACK + 1.0) * t.powi(3) - BACK * t.powi(2),
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Easing::BackInOut => in_out(t, |t| {
+                (BACK_IN_OUT + 1.0) * t.powi(3) - BACK_IN_OUT * t.powi(2)
+            }),
+            Easing::ElasticIn => {
+                -(2f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * ELASTIC).sin()
+            }
+            Easing::ElasticOut => 2f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * ELASTIC).sin() + 1.0,
+            Easing::ElasticInOut => {
+                let wave = ((20.0 * t - 11.125) * ELASTIC_IN_OUT).sin();
+                if t < 0.5 {
+                    -(2f32.powf(20.0 * t - 10.0) * wave) / 2.0
+                } else {
+                    2f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+        }
+    }
+}
+
+/// Ease in with the given function for the first half, and ease out with its mirror for the second half
+fn in_out<F: Fn(f32) -> f32>(t: f32, ease_in: F) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in(2.0 - t * 2.0) / 2.0
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984_375
+    }
+}
+
+/// Building block of a [`Tween`]
+///
+/// A step either moves the value to a target, waits, or groups other steps. Steps in a [`sequence`] run one after
+/// another, and steps in a [`parallel`] group start together and the group lasts as long as the longest one. While
+/// several steps of a parallel group are running, the one that comes later in the group wins.
+///
+/// Steps created by [`to`] start from whatever the value is when they start, and remember it so that repeating the
+/// tween starts from the same value again.
+///
+/// [`Tween`]: ./struct.Tween.html
+/// [`sequence`]: #method.sequence
+/// [`parallel`]: #method.parallel
+/// [`to`]: #method.to
+#[derive(Clone, Debug, PartialEq)]
+pub struct TweenStep<T> {
+    kind: StepKind<T>,
+    duration: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum StepKind<T> {
+    Move {
+        from: Option<T>,
+        /// The value when the step started, used when `from` is not given
+        start: Option<T>,
+        to: T,
+        easing: Easing,
+    },
+    Delay,
+    Sequence(Vec<TweenStep<T>>),
+    Parallel(Vec<TweenStep<T>>),
+}
+
+impl<T> TweenStep<T> {
+    /// Create a step that moves the value to `to` over `duration` seconds
+    pub fn to(to: T, duration: f32, easing: Easing) -> TweenStep<T> {
+        TweenStep {
+            kind: StepKind::Move {
+                from: None,
+                start: None,
+                to,
+                easing,
+            },
+            duration: duration.max(0.0),
+        }
+    }
+
+    /// Create a step that moves the value from `from` to `to` over `duration` seconds
+    pub fn from_to(from: T, to: T, duration: f32, easing: Easing) -> TweenStep<T> {
+        TweenStep {
+            kind: StepKind::Move {
+                from: Some(from),
+                start: None,
+                to,
+                easing,
+            },
+            duration: duration.max(0.0),
+        }
+    }
+
+    /// Create a step that keeps the value for `duration` seconds
+    pub fn delay(duration: f32) -> TweenStep<T> {
+        TweenStep {
+            kind: StepKind::Delay,
+            duration: duration.max(0.0),
+        }
+    }
+
+    /// Create a step that runs the given steps one after another
+    pub fn sequence<I: IntoIterator<Item = TweenStep<T>>>(steps: I) -> TweenStep<T> {
+        let steps: Vec<_> = steps.into_iter().collect();
+        let duration = steps.iter().map(|step| step.duration).sum();
+        TweenStep {
+            kind: StepKind::Sequence(steps),
+            duration,
+        }
+    }
+
+    /// Create a step that runs the given steps at the same time
+    pub fn parallel<I: IntoIterator<Item = TweenStep<T>>>(steps: I) -> TweenStep<T> {
+        let steps: Vec<_> = steps.into_iter().collect();
+        let duration = steps.iter().map(|step| step.duration).fold(0.0, f32::max);
+        TweenStep {
+            kind: StepKind::Parallel(steps),
+            duration,
+        }
+    }
+
+    /// Run the given step after this one
+    pub fn then(self, next: TweenStep<T>) -> TweenStep<T> {
+        match self.kind {
+            StepKind::Sequence(mut steps) => {
+                let duration = self.duration + next.duration;
+                steps.push(next);
+                TweenStep {
+                    kind: StepKind::Sequence(steps),
+                    duration,
+                }
+            }
+            kind => TweenStep::sequence(vec![
+                TweenStep {
+                    kind,
+                    duration: self.duration,
+                },
+                next,
+            ]),
+        }
+    }
+
+    /// Duration of this step in seconds
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Forget the values that the steps started from
+    fn reset(&mut self) {
+        match &mut self.kind {
+            StepKind::Move { start, .. } => *start = None,
+            StepKind::Delay => {}
+            StepKind::Sequence(steps) | StepKind::Parallel(steps) => {
+                steps.iter_mut().for_each(TweenStep::reset)
+            }
+        }
+    }
+}
+
+impl<T: Interpolate> TweenStep<T> {
+    /// Update the value to the one at `time` seconds from the start of this step
+    fn apply(&mut self, time: f32, value: &mut Option<T>) {
+        match &mut self.kind {
+            StepKind::Move {
+                from,
+                start,
+                to,
+                easing,
+            } => {
+                let from = from.unwrap_or_else(|| *start.get_or_insert(value.unwrap_or(*to)));
+                *value = Some(if time >= self.duration {
+                    *to
+                } else {
+                    from.interpolate(to, easing.apply(time / self.duration))
+                });
+            }
+            StepKind::Delay => {}
+            StepKind::Sequence(steps) => {
+                let mut start = 0.0;
+                for step in steps {
+                    if time < start {
+                        break;
+                    }
+                    step.apply(time - start, value);
+                    start += step.duration;
+                }
+            }
+            StepKind::Parallel(steps) => {
+                for step in steps {
+                    step.apply(time, value);
+                }
+            }
+        }
+    }
+}
+
+/// How many times a [`Tween`] is played
+///
+/// [`Tween`]: ./struct.Tween.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Repeat {
+    /// Play the given number of times, where `Times(0)` is the same as `Times(1)`
+    Times(u32),
+
+    /// Play until the tween is removed
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Repeat::Times(1)
+    }
+}
+
+/// Event written to the [`Events`] resource when a [`Tween`] completes
+///
+/// [`Events`]: ../../game_engine_core/events/struct.Events.html
+/// [`Tween`]: ./struct.Tween.html
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TweenCompleted {
+    /// The entity with the [`Tween`]
+    ///
+    /// [`Tween`]: ./struct.Tween.html
+    pub entity: Entity,
+    /// The name of the tween, which is empty unless set by [`Tween::with_name`]
+    ///
+    /// [`Tween::with_name`]: ./struct.Tween.html#method.with_name
+    pub name: String,
+}
+
+/// Component that moves a value of type `T` along a [`TweenStep`]
+///
+/// See the [module documentation] for how it is driven.
+///
+/// [`TweenStep`]: ./struct.TweenStep.html
+/// [module documentation]: ./index.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween<T> {
+    step: TweenStep<T>,
+    repeat: Repeat,
+    yoyo: bool,
+    name: String,
+    value: Option<T>,
+    /// Time in the current cycle
+    elapsed: f32,
+    /// Number of the completed cycles
+    cycle: u32,
+    paused: bool,
+    finished: bool,
+}
+
+impl<T> Tween<T> {
+    /// Create a new instance that plays the given step once
+    pub fn new(step: TweenStep<T>) -> Tween<T> {
+        Tween {
+            step,
+            repeat: Repeat::default(),
+            yoyo: false,
+            name: String::new(),
+            value: None,
+            elapsed: 0.0,
+            cycle: 0,
+            paused: false,
+            finished: false,
+        }
+    }
+
+    /// Create a new instance that moves the value to `to` over `duration` seconds
+    pub fn to(to: T, duration: f32, easing: Easing) -> Tween<T> {
+        Tween::new(TweenStep::to(to, duration, easing))
+    }
+
+    /// Create a new instance that moves the value from `from` to `to` over `duration` seconds
+    pub fn from_to(from: T, to: T, duration: f32, easing: Easing) -> Tween<T> {
+        Tween::new(TweenStep::from_to(from, to, duration, easing))
+    }
+
+    /// Set how many times the tween is played
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Set whether every other cycle is played backwards
+    pub fn with_yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    /// Set the name passed to [`TweenCompleted`] event
+    ///
+    /// [`TweenCompleted`]: ./struct.TweenCompleted.html
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the value to start from, for the tweens whose entity does not have the `T` component
+    pub fn with_value(mut self, value: T) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Get the step played by this tween
+    pub fn step(&self) -> &TweenStep<T> {
+        &self.step
+    }
+
+    /// Get how many times the tween is played
+    pub fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    /// Check whether every other cycle is played backwards
+    pub fn is_yoyo(&self) -> bool {
+        self.yoyo
+    }
+
+    /// Get the name passed to [`TweenCompleted`] event
+    ///
+    /// [`TweenCompleted`]: ./struct.TweenCompleted.html
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replace the current value, e.g. with the one of the component being tweened
+    pub fn set_value(&mut self, value: T) {
+        self.value = Some(value);
+    }
+
+    /// Get the time in seconds from the start of the current cycle
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Get the number of the completed cycles
+    pub fn completed_cycles(&self) -> u32 {
+        self.cycle
+    }
+
+    /// Stop advancing the tween until [`resume`] is called
+    ///
+    /// [`resume`]: #method.resume
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continue advancing the tween paused by [`pause`]
+    ///
+    /// [`pause`]: #method.pause
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check whether the tween is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Check whether the tween has completed
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Play the tween again from the start, with the steps created by [`TweenStep::to`] starting from the current value
+    ///
+    /// [`TweenStep::to`]: ./struct.TweenStep.html#method.to
+    pub fn restart(&mut self) {
+        self.step.reset();
+        self.elapsed = 0.0;
+        self.cycle = 0;
+        self.finished = false;
+    }
+}
+
+impl<T: Interpolate> Tween<T> {
+    /// Get the current value
+    ///
+    /// This is `None` until the tween is advanced for the first time, unless it is given by [`with_value`].
+    ///
+    /// [`with_value`]: #method.with_value
+    pub fn value(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Advance the tween by `delta` seconds and update the value, returning `true` if the tween has just completed
+    ///
+    /// Nothing happens while the tween is paused or after it has completed.
+    pub fn advance(&mut self, delta: f32) -> bool {
+        if self.paused || self.finished {
+            return false;
+        }
+
+        let duration = self.step.duration();
+        self.elapsed += delta.max(0.0);
+        while self.elapsed >= duration {
+            let last = match self.repeat {
+                Repeat::Times(times) => self.cycle + 1 >= times,
+                Repeat::Forever => false,
+            };
+            if last {
+                self.elapsed = duration;
+                self.finished = true;
+                break;
+            }
+            if duration <= 0.0 {
+                self.elapsed = 0.0;
+                break;
+            }
+            self.elapsed -= duration;
+            self.cycle += 1;
+        }
+
+        let time = if self.yoyo && self.cycle % 2 == 1 {
+            duration - self.elapsed
+        } else {
+            self.elapsed
+        };
+        self.step.apply(time, &mut self.value);
+        self.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Easing, Repeat, Tween, TweenStep};
+    use crate::components::Position;
+
+    #[test]
+    fn easing_ends() {
+        let easings = [
+            Easing::Linear,
+            Easing::SineIn,
+            Easing::SineOut,
+            Easing::SineInOut,
+            Easing::QuadIn,
+            Easing::QuadOut,
+            Easing::QuadInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+            Easing::QuartIn,
+            Easing::QuartOut,
+            Easing::QuartInOut,
+            Easing::QuintIn,
+            Easing::QuintOut,
+            Easing::QuintInOut,
+            Easing::ExpoIn,
+            Easing::ExpoOut,
+            Easing::ExpoInOut,
+            Easing::CircIn,
+            Easing::CircOut,
+            Easing::CircInOut,
+            Easing::BackIn,
+            Easing::BackOut,
+            Easing::BackInOut,
+            Easing::ElasticIn,
+            Easing::ElasticOut,
+            Easing::ElasticInOut,
+            Easing::BounceIn,
+            Easing::BounceOut,
+            Easing::BounceInOut,
+        ];
+        for &easing in easings.iter() {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            // Both halves of the curve meet in the middle
+            assert!(
+                (easing.apply(0.499_99) - easing.apply(0.500_01)).abs() < 0.01,
+                "{:?}",
+                easing
+            );
+        }
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::QuadInOut.apply(0.25), 0.125);
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+        assert!(Easing::BackIn.apply(0.2) < 0.0);
+    }
+
+    #[test]
+    fn sequence_and_parallel() {
+        let mut tween = Tween::new(
+            TweenStep::to(Position::from_x(4.0), 1.0, Easing::Linear)
+                .then(TweenStep::delay(1.0))
+                .then(TweenStep::parallel(vec![
+                    TweenStep::to(Position::from_y(2.0), 1.0, Easing::Linear),
+                    TweenStep::delay(2.0),
+                ])),
+        )
+        .with_value(Position::from_z(4.0));
+        assert_eq!(tween.step().duration(), 4.0);
+
+        assert!(!tween.advance(0.5));
+        assert_eq!(tween.value(), Some(Position::from_xyz(2.0, 0.0, 2.0)));
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), Some(Position::from_x(4.0)));
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), Some(Position::from_xyz(2.0, 1.0, 0.0)));
+        assert!(tween.advance(1.5));
+        assert_eq!(tween.value(), Some(Position::from_y(2.0)));
+        assert!(tween.is_finished());
+        assert!(!tween.advance(1.0));
+    }
+
+    #[test]
+    fn repeat_and_yoyo() {
+        let mut tween = Tween::from_to(0.0, 4.0, 1.0, Easing::Linear)
+            .with_repeat(Repeat::Times(3))
+            .with_yoyo(true);
+        assert!(!tween.advance(0.5));
+        assert_eq!(tween.value(), Some(2.0));
+        assert!(!tween.advance(0.75));
+        assert_eq!(tween.value(), Some(3.0));
+        assert_eq!(tween.completed_cycles(), 1);
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), Some(1.0));
+        tween.pause();
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), Some(1.0));
+        tween.resume();
+        assert!(tween.advance(1.0));
+        assert_eq!(tween.value(), Some(4.0));
+
+        // Without yoyo, the next cycle starts over from the value the first one started from
+        let mut tween = Tween::to(4.0, 1.0, Easing::Linear)
+            .with_repeat(Repeat::Forever)
+            .with_value(2.0);
+        tween.advance(1.5);
+        assert_eq!(tween.value(), Some(3.0));
+        assert!(!tween.advance(10.0));
+        assert!(!tween.is_finished());
+
+        tween.restart();
+        tween.advance(0.5);
+        assert_eq!(tween.value(), Some(3.5));
+    }
+}
@@ -1,19 +1,30 @@
 //! Utility things
 
-use crate::components::{Position, Rotation};
+use crate::components::{Position, Position2, Rotation, Rotation2, Scale, Scale2};
 use crate::ecs::entity::Entity;
 use crate::ecs::system::SubWorld;
 use crate::ecs::world::World;
+use crate::math::{Matrix3, Matrix4};
 
 macro_rules! entity_should_have_transform_fn {
     (
         $( #[$attrs:meta] )*
         pub fn $fn_name:ident in $world:ident (entity: Entity) -> bool;
+    ) => {
+        entity_should_have_transform_fn! {
+            $( #[$attrs] )*
+            pub fn $fn_name in $world (entity: Entity) -> bool { Position, Rotation, Scale }
+        }
+    };
+    (
+        $( #[$attrs:meta] )*
+        pub fn $fn_name:ident in $world:ident (entity: Entity) -> bool { $position:ident, $rotation:ident, $scale:ident }
     ) => {
         $( #[$attrs] )*
         pub fn $fn_name(entity: Entity, world: &$world) -> bool {
-            world.get_component::<Position>(entity).is_some()
-                || world.get_component::<Rotation>(entity).is_some()
+            world.get_component::<$position>(entity).is_some()
+                || world.get_component::<$rotation>(entity).is_some()
+                || world.get_component::<$scale>(entity).is_some()
         }
     };
 }
@@ -29,3 +40,70 @@ entity_should_have_transform_fn! {
     /// a sub-world
     pub fn entity_should_have_transform_in_sub_world in SubWorld (entity: Entity) -> bool;
 }
+
+entity_should_have_transform_fn! {
+    /// Check if this entity has any of components that represent transform in the 2D game world,
+    /// using the world
+    pub fn entity_should_have_transform2_in_world in World (entity: Entity) -> bool {
+        Position2, Rotation2, Scale2
+    }
+}
+
+entity_should_have_transform_fn! {
+    /// Check if this entity has any of components that represent transform in the 2D game world,
+    /// using a sub-world
+    pub fn entity_should_have_transform2_in_sub_world in SubWorld (entity: Entity) -> bool {
+        Position2, Rotation2, Scale2
+    }
+}
+
+/// Compose the local transform matrix from whichever of [`Position`], [`Rotation`], and [`Scale`] components an entity
+/// has
+///
+/// The scale is applied first, then the rotation, and the translation last. Missing components are treated as identity.
+///
+/// [`Position`]: ../components/struct.Position.html
+/// [`Rotation`]: ../components/struct.Rotation.html
+/// [`Scale`]: ../components/struct.Scale.html
+pub fn local_transform_matrix(
+    position: Option<&Position>,
+    rotation: Option<&Rotation>,
+    scale: Option<&Scale>,
+) -> Matrix4<f32> {
+    let mut matrix = match (rotation, scale) {
+        (Some(rotation), Some(scale)) => rotation.to_homogeneous() * scale.to_homogeneous(),
+        (Some(rotation), None) => rotation.to_homogeneous(),
+        (None, Some(scale)) => scale.to_homogeneous(),
+        (None, None) => Matrix4::identity(),
+    };
+    if let Some(position) = position {
+        matrix.append_translation_mut(&position.vector);
+    }
+    matrix
+}
+
+/// Compose the local 2D transform matrix from whichever of [`Position2`], [`Rotation2`], and [`Scale2`] components an
+/// entity has
+///
+/// The order is the same as [`local_transform_matrix`]. Missing components are treated as identity.
+///
+/// [`Position2`]: ../components/struct.Position2.html
+/// [`Rotation2`]: ../components/struct.Rotation2.html
+/// [`Scale2`]: ../components/struct.Scale2.html
+/// [`local_transform_matrix`]: ./fn.local_transform_matrix.html
+pub fn local_transform2_matrix(
+    position: Option<&Position2>,
+    rotation: Option<&Rotation2>,
+    scale: Option<&Scale2>,
+) -> Matrix3<f32> {
+    let mut matrix = match (rotation, scale) {
+        (Some(rotation), Some(scale)) => rotation.to_homogeneous() * scale.to_homogeneous(),
+        (Some(rotation), None) => rotation.to_homogeneous(),
+        (None, Some(scale)) => scale.to_homogeneous(),
+        (None, None) => Matrix3::identity(),
+    };
+    if let Some(position) = position {
+        matrix.append_translation_mut(&position.vector);
+    }
+    matrix
+}
@@ -17,8 +17,13 @@ pub struct WindowSize {
 }
 
 impl WindowSize {
-    /// Create a new instance from the given size and hidpi factor
-    pub(crate) fn new(size: impl Into<PhysicalSize>, dpi_factor: f64) -> Self {
+    /// Create a new instance from the given physical size and hidpi factor
+    ///
+    /// The resource is inserted by [`WindowSizeControl`] system, so this is only needed to set up
+    /// a world without a real window, e.g. in tests.
+    ///
+    /// [`WindowSizeControl`]: ./fn.create_window_size_control_system.html
+    pub fn new(size: impl Into<PhysicalSize>, dpi_factor: f64) -> Self {
         let size = size.into();
         let aspect_ratio = (size.width / size.height) as f32;
 